@@ -1,22 +1,43 @@
+use crate::query::{FilterOp, QuerySpec};
+#[cfg(feature = "gui")]
 use egui::Ui;
+#[cfg(feature = "gui")]
 use egui_extras::Column as TableColumnConfig;
+use rvariant::{Variant, VariantTy};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct RowUid(pub u32);
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct VisualRowIdx(pub usize);
 
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub struct ColumnUid(pub u32);
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct CellCoord {
     pub row_uid: RowUid,
     pub col_uid: ColumnUid,
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CellState {
+    /// Value is present locally or the cell is empty.
+    Available,
+    /// Row exists, but the value is not fetched yet, a placeholder is shown instead.
+    Loading,
+}
+
+/// Range of rows that could not be loaded.
+#[derive(Clone, Debug)]
+pub struct FetchError {
+    pub rows: Range<VisualRowIdx>,
+    pub message: String,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 // #[cfg_attr(feature = "persistency", derive(serde::Serialize, serde::Deserialize))]
 pub struct BackendColumn {
@@ -25,6 +46,17 @@ pub struct BackendColumn {
     pub is_sortable: bool,
 }
 
+/// Where values of a column come from, shown as an indicator in the column header.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ColumnOrigin {
+    #[default]
+    Stored,
+    /// Derived from other cells of the row each time it is shown, cannot be edited.
+    Computed,
+    /// Was computed, values were then stored and are no longer updated.
+    Materialized,
+}
+
 pub trait TableBackend {
     /// Drop all data from memory and start loading from scratch. No-op if memory based backend.
     fn reload(&mut self) {}
@@ -63,20 +95,247 @@ pub trait TableBackend {
     // Choose whether to use certain columns or not.
     // fn use_columns(&mut self, cols: impl Iterator<Item = (usize, bool)>);
 
+    /// Whether column's name can be changed from the UI.
+    fn can_rename_column(&self, col_uid: ColumnUid) -> bool {
+        let _ = col_uid;
+        false
+    }
+    /// Change column's name, only called if [can_rename_column](Self::can_rename_column) returned true.
+    fn rename_column(&mut self, col_uid: ColumnUid, name: String) {
+        let (_, _) = (col_uid, name);
+    }
+
+    /// Types the column can be converted to from the UI, empty if not supported.
+    fn column_type_choices(&self, col_uid: ColumnUid) -> Vec<VariantTy> {
+        let _ = col_uid;
+        vec![]
+    }
+    /// Convert all cells of a column to one of the [column_type_choices](Self::column_type_choices),
+    /// returns cells that could not be converted and were left as is.
+    fn change_column_type(&mut self, col_uid: ColumnUid, ty: VariantTy) -> Vec<CellCoord> {
+        let (_, _) = (col_uid, ty);
+        vec![]
+    }
+
+    fn column_origin(&self, col_uid: ColumnUid) -> ColumnOrigin {
+        let _ = col_uid;
+        ColumnOrigin::Stored
+    }
+
+    /// Store current values of a computed column as regular cells, so that they are exported and edited
+    /// like any other, and are kept when the computation is no longer available.
+    fn materialize_column(&mut self, col_uid: ColumnUid) {
+        let _ = col_uid;
+    }
+
+    /// Alternative names used to match this column when importing data, None if not applicable.
+    fn column_synonyms(&self, col_uid: ColumnUid) -> Option<&[String]> {
+        let _ = col_uid;
+        None
+    }
+    /// Only called if [column_synonyms](Self::column_synonyms) returned Some.
+    fn set_column_synonyms(&mut self, col_uid: ColumnUid, synonyms: Vec<String>) {
+        let (_, _) = (col_uid, synonyms);
+    }
+
+    /// What the column is for, shown when hovering the example row of an empty table.
+    fn column_description(&self, col_uid: ColumnUid) -> Option<&str> {
+        let _ = col_uid;
+        None
+    }
+
+    /// Value shown in the example row of an empty table, so that users know what to type.
+    fn column_example(&self, col_uid: ColumnUid) -> Option<Variant> {
+        let _ = col_uid;
+        None
+    }
+
     /// Returns the rendering configuration for the column.
+    #[cfg(feature = "gui")]
     fn column_render_config(&mut self, col_uid: ColumnUid) -> TableColumnConfig {
         let _ = col_uid;
         TableColumnConfig::auto().resizable(true)
     }
 
+    /// Returns Some if sorting and filtering can be done by the backend itself, e.g. by an SQL query.
+    /// Wrap backends returning None into [LocalQuery](crate::backends::local_query::LocalQuery)
+    /// to sort and filter locally instead.
+    fn queryable(&self) -> Option<&dyn QueryableBackend> {
+        None
+    }
+    fn queryable_mut(&mut self) -> Option<&mut dyn QueryableBackend> {
+        None
+    }
+
     /// Returns row count, with filters applied.
     fn row_count(&self) -> usize;
     /// Map index from [0..row_count) range to unique row id, applying sort order in the process.
     fn row_uid(&self, row_idx: VisualRowIdx) -> Option<RowUid>;
+    /// All rows in the order they are shown, with filters and sorting applied.
+    fn visible_rows(&self) -> Vec<RowUid> {
+        (0..self.row_count())
+            .filter_map(|row_idx| self.row_uid(VisualRowIdx(row_idx)))
+            .collect()
+    }
+    /// Same as [visible_rows](Self::visible_rows), without skipped rows. These are the rows that are
+    /// exported and extracted.
+    fn un_skipped_rows(&self) -> Vec<RowUid> {
+        self.visible_rows()
+            .into_iter()
+            .filter(|row_uid| !self.is_row_skipped(*row_uid))
+            .collect()
+    }
+
+    /// Called each frame with the rows currently on screen, so that paging backends can prefetch
+    /// the ones around it before they are scrolled into view.
+    fn hint_visible_range(&mut self, range: Range<VisualRowIdx>) {
+        let _ = range;
+    }
+
+    /// Row ranges that failed to load, shown above the table with a Retry button.
+    fn failed_fetches(&self) -> Vec<FetchError> {
+        vec![]
+    }
+    /// Request rows of one of the [failed_fetches](Self::failed_fetches) again.
+    fn retry_fetch(&mut self, rows: Range<VisualRowIdx>) {
+        let _ = rows;
+    }
+
+    /// Returns cell value if it is available, used for copying to clipboard and other non-UI operations.
+    /// Value is returned by value, so that backends not storing [Variant] can convert on the fly.
+    fn get(&self, coord: CellCoord) -> Option<Variant> {
+        let _ = coord;
+        None
+    }
+
+    /// Cell value as shown in the table, used when copying formatted values, see
+    /// [CopyValues](crate::table_view::CopyValues). Same as [get](Self::get) by default.
+    fn display_text(&self, coord: CellCoord) -> Option<String> {
+        self.get(coord)
+            .map(|value| crate::util::variant_to_string(&value))
+    }
 
+    /// Short text shown in the top right corner of a cell, e.g. the value type in columns of mixed types.
+    fn cell_badge(&self, coord: CellCoord) -> Option<String> {
+        let _ = coord;
+        None
+    }
+
+    /// Why the cell value is invalid, None if it is valid or not checked. Invalid cells are tinted in the view.
+    fn cell_error(&self, coord: CellCoord) -> Option<String> {
+        let _ = coord;
+        None
+    }
+
+    /// All invalid cells with their errors, in the order rows are shown, listed below the table.
+    fn validation_errors(&self) -> Vec<(CellCoord, String)> {
+        vec![]
+    }
+
+    /// Used by Ctrl+Arrow navigation to find edges of filled blocks.
+    fn is_cell_empty(&self, coord: CellCoord) -> bool {
+        match self.get(coord) {
+            None | Some(Variant::Empty) => true,
+            Some(Variant::Str(s)) => s.is_empty(),
+            Some(_) => false,
+        }
+    }
+
+    /// Whether cell's value can be shown, used by paged and remote backends to mark cells still being fetched.
+    fn cell_state(&self, coord: CellCoord) -> CellState {
+        let _ = coord;
+        CellState::Available
+    }
+
+    #[cfg(feature = "gui")]
     fn show_cell_view(&self, coord: CellCoord, ui: &mut Ui);
+    #[cfg(feature = "gui")]
     fn show_cell_editor(&self, coord: CellCoord, ui: &mut Ui) -> Option<egui::Response>;
     fn commit_cell_edit(&mut self, coord: CellCoord);
+    /// Editor was closed with Escape, drop the edited value so that the cell keeps its original one
+    /// and the next edit starts from it.
+    fn cancel_cell_edit(&mut self, coord: CellCoord) {
+        let _ = coord;
+    }
+
+    /// Called when the editor is opened from the keyboard, before the first [show_cell_editor](Self::show_cell_editor).
+    /// With `typed` text the editor should start with it instead of the current value, like in spreadsheets.
+    fn start_cell_edit(&mut self, coord: CellCoord, typed: Option<String>) {
+        let _ = (coord, typed);
+    }
+
+    /// Set values of many cells as one action, e.g. when pasting.
+    /// Backends with typed columns convert [Variant::Str] values into column types.
+    fn modify_many(&mut self, values: Vec<(CellCoord, Variant)>) {
+        let _ = values;
+    }
+
+    /// Remove values of many cells as one action, e.g. when Delete is pressed. Sets them to [Variant::Empty] by default.
+    fn clear_cells(&mut self, coords: impl Iterator<Item = CellCoord>) {
+        self.modify_many(coords.map(|coord| (coord, Variant::Empty)).collect());
+    }
+
+    /// Skipped rows are still shown, but excluded from extraction and export.
+    fn skip_row(&mut self, row_uid: RowUid, skip: bool) {
+        let (_, _) = (row_uid, skip);
+    }
+    fn is_row_skipped(&self, row_uid: RowUid) -> bool {
+        let _ = row_uid;
+        false
+    }
+
+    /// Not skipped rows with required cells empty or holding values of a wrong type, in the order they are shown.
+    fn incomplete_rows(&self) -> Vec<RowUid> {
+        vec![]
+    }
+
+    /// Whether new rows can be added from the UI.
+    fn can_append_rows(&self) -> bool {
+        false
+    }
+    /// Add a row at the end with default values, only called if [can_append_rows](Self::can_append_rows) returned true.
+    /// Should set [OneShotFlags::row_set_updated].
+    fn append_row(&mut self) -> Option<RowUid> {
+        None
+    }
+
+    /// Whether rows can be removed from the UI.
+    fn can_remove_rows(&self) -> bool {
+        false
+    }
+    /// Remove rows with all their cells, only called if [can_remove_rows](Self::can_remove_rows) returned true.
+    /// Should set [OneShotFlags::row_set_updated].
+    fn remove_rows(&mut self, row_uids: Vec<RowUid>) {
+        let _ = row_uids;
+    }
+
+    /// Keep a row visible above the scrolled rows, e.g. to compare it with others.
+    fn pin_row(&mut self, row_uid: RowUid, pinned: bool) {
+        let (_, _) = (row_uid, pinned);
+    }
+    /// Pinned rows in the order they are shown.
+    fn pinned_rows(&self) -> Vec<RowUid> {
+        vec![]
+    }
+
+    /// Move row to another position, rows in between are shifted by one.
+    fn move_row(&mut self, row_uid: RowUid, to: VisualRowIdx) {
+        let (_, _) = (row_uid, to);
+    }
+
+    /// Move value of a cell to another row in the same column, shifting values in between by one row.
+    fn move_cell_value(&mut self, col_uid: ColumnUid, from: VisualRowIdx, to: VisualRowIdx) {
+        let (_, _, _) = (col_uid, from, to);
+    }
+
+    /// Revert last change, if backend keeps history.
+    fn undo(&mut self) {}
+    /// Apply last reverted change again.
+    fn redo(&mut self) {}
+    /// Number of changes that can be undone and of all recorded changes, None if backend doesn't keep history.
+    fn history_position(&self) -> Option<(usize, usize)> {
+        None
+    }
     // fn modify_one(&mut self, cell: CellCoord, new_value: Variant);
     // fn modify_many(&mut self, new_values: impl Iterator<Item = (CellCoord, Value)>, commit: bool);
     // fn remove_one(&mut self, cell: CellCoord, commit: bool);
@@ -86,10 +345,10 @@ pub trait TableBackend {
     // If there are not default value for Column, ui should show warning and do not allow committing.
     // If commit is tried anyway, it will be rejected.
     // fn create_row(&mut self, values: HashMap<u32, Variant>) -> Option<u32>;
-    // fn remove_rows(&mut self, row_ids: Vec<u32>);
 
     /// Use this to check if given cell is going to take any dropped payload / use as drag
     /// source.
+    #[cfg(feature = "gui")]
     fn on_cell_view_response(&mut self, coord: CellCoord, resp: &egui::Response) -> Option<()> {
         let _ = (coord, resp);
         None
@@ -110,6 +369,17 @@ pub trait TableBackend {
     // fn row_filters(&self) -> &[(RowFilter, String)];
 }
 
+/// Backend that sorts and filters rows itself, instead of the whole data set being pulled in and processed locally.
+pub trait QueryableBackend {
+    /// Whether rows can be sorted by this column.
+    fn supports_sort(&self, col_uid: ColumnUid) -> bool;
+    /// Whether rows can be filtered by this column with the given operation.
+    fn supports_filter(&self, col_uid: ColumnUid, op: FilterOp) -> bool;
+    /// Sort and filter rows as specified, only supported keys and filters are passed in.
+    /// Should set [OneShotFlags::visible_row_vec_updated] once the visible rows are changed.
+    fn apply(&mut self, spec: &QuerySpec);
+}
+
 #[derive(Default)]
 pub struct PersistentFlags {
     // Persistent flags: value is kept across poll() calls
@@ -134,6 +404,10 @@ pub struct PersistentFlags {
 }
 
 /// One shot flags: all flags are reset to false after poll() call
+///
+/// [TableView](crate::TableView) resets them on each frame after handling them. A backend used without
+/// a view keeps adding to [cells_updated](Self::cells_updated) and [events](Self::events) on every change,
+/// so the application must reset them itself once handled, e.g. `*backend.one_shot_flags_mut() = OneShotFlags::default()`.
 #[derive(Default)]
 pub struct OneShotFlags {
     /// Set once data backend is created
@@ -146,8 +420,73 @@ pub struct OneShotFlags {
     pub row_set_updated: bool,
     /// Set once when visible row set was changed (after filtering or sorting)
     pub visible_row_vec_updated: bool,
-    // Set once when received updated for already available cell's
-    // pub cells_updated: Vec<CellCoord>,
+    /// Cells whose values changed, whether updated by the backend itself or edited, pasted, undone or redone.
+    /// Lets applications react to exactly these cells instead of comparing the whole table.
+    pub cells_updated: Vec<CellCoord>,
     /// Set once when clear() is called.
     pub cleared: bool,
+    /// Set once when row uids were reassigned, e.g. on reload, maps old uids to new ones.
+    /// Rows missing from the map are gone, the view drops what it kept for them.
+    /// Without it, the view forgets all row state on [cleared](Self::cleared) or [reloaded](Self::reloaded).
+    pub rows_remapped: Option<HashMap<RowUid, RowUid>>,
+    /// Set by the table view when selection changes, rows of all selected ranges in the order they are shown.
+    pub rows_selected: Option<Vec<RowUid>>,
+    /// What changed, in order, with more details than the flags above.
+    /// Moved to the table view before the flags are reset, see [TableView::drain_events](crate::TableView::drain_events).
+    pub events: Vec<TableEvent>,
+}
+
+impl OneShotFlags {
+    /// Queue an event, merging it into the last one if both are about the same kind of change to cells or rows.
+    pub fn push_event(&mut self, event: TableEvent) {
+        match (self.events.last_mut(), event) {
+            (Some(TableEvent::CellsChanged(last)), TableEvent::CellsChanged(coords)) => {
+                last.extend(coords)
+            }
+            (Some(TableEvent::RowsInserted(last)), TableEvent::RowsInserted(row_uids)) => {
+                last.extend(row_uids)
+            }
+            (Some(TableEvent::RowsRemoved(last)), TableEvent::RowsRemoved(row_uids)) => {
+                last.extend(row_uids)
+            }
+            (_, event) => self.events.push(event),
+        }
+    }
+}
+
+/// Action chosen with one of the buttons in the actions column of the table view.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RowAction {
+    Edit,
+    Delete,
+    Open,
+    Custom(String),
+}
+
+/// Change to the data or the view, see [OneShotFlags::events].
+#[derive(Clone, Debug, PartialEq)]
+pub enum TableEvent {
+    /// Cell values changed, by editing, pasting, undo or redo, or by the backend itself.
+    CellsChanged(Vec<CellCoord>),
+    RowsInserted(Vec<RowUid>),
+    RowsRemoved(Vec<RowUid>),
+    ColumnRenamed {
+        col_uid: ColumnUid,
+        name: String,
+    },
+    ColumnTypeChanged {
+        col_uid: ColumnUid,
+        ty: VariantTy,
+    },
+    /// Rows of all selected ranges in the order they are shown, reported by the table view.
+    SelectionChanged(Vec<RowUid>),
+    /// Sorting or filtering applied by the table view.
+    QueryChanged(QuerySpec),
+    /// Button clicked in the actions column of the table view.
+    RowAction {
+        row_uid: RowUid,
+        action: RowAction,
+    },
+    Reloaded,
+    Cleared,
 }
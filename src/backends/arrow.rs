@@ -0,0 +1,231 @@
+use crate::backend::{
+    BackendColumn, CellCoord, ColumnUid, OneShotFlags, PersistentFlags, RowUid, TableBackend,
+    VisualRowIdx,
+};
+use arrow::array::{Array, AsArray};
+use arrow::datatypes::{
+    DataType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, SchemaRef,
+    UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow::error::ArrowError;
+use arrow::record_batch::{RecordBatch, RecordBatchReader};
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+#[cfg(feature = "gui")]
+use egui::{Response, Ui};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::errors::ParquetError;
+use rvariant::Variant;
+use std::fs::File;
+use std::path::Path;
+
+/// Read only table backed by Arrow record batches, data is kept in columnar form and is only
+/// converted to text for the visible cells.
+/// Column uids are field indices in the schema and row uids are row indices across all batches.
+pub struct ArrowBackend {
+    schema: SchemaRef,
+    batches: Vec<RecordBatch>,
+    /// Index of the first row of each batch.
+    batch_offsets: Vec<usize>,
+    row_count: usize,
+    columns: Vec<BackendColumn>,
+    is_used: Vec<bool>,
+    persistent_flags: PersistentFlags,
+    one_shot_flags: OneShotFlags,
+}
+
+impl ArrowBackend {
+    pub fn new(batch: RecordBatch) -> Self {
+        Self::from_batches(batch.schema(), vec![batch])
+    }
+
+    /// Use all the batches as one table without copying them, all batches must have the provided schema.
+    pub fn from_batches(schema: SchemaRef, batches: Vec<RecordBatch>) -> Self {
+        let mut batch_offsets = Vec::with_capacity(batches.len());
+        let mut row_count = 0;
+        for batch in &batches {
+            batch_offsets.push(row_count);
+            row_count += batch.num_rows();
+        }
+        let columns: Vec<BackendColumn> = schema
+            .fields()
+            .iter()
+            .map(|field| BackendColumn {
+                name: field.name().clone(),
+                ty: field.data_type().to_string(),
+                is_sortable: false,
+            })
+            .collect();
+        ArrowBackend {
+            schema,
+            batches,
+            batch_offsets,
+            row_count,
+            is_used: vec![true; columns.len()],
+            columns,
+            persistent_flags: PersistentFlags {
+                is_read_only: true,
+                column_info_present: true,
+                row_set_present: true,
+                have_all_cells: true,
+                ..Default::default()
+            },
+            one_shot_flags: OneShotFlags {
+                first_pass: true,
+                column_info_updated: true,
+                row_set_updated: true,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Read whole Parquet file into memory.
+    pub fn from_parquet(path: impl AsRef<Path>) -> Result<Self, ParquetError> {
+        let file = File::open(path)?;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file)?.build()?;
+        let schema = reader.schema();
+        let batches = reader.collect::<Result<Vec<_>, ArrowError>>()?;
+        Ok(Self::from_batches(schema, batches))
+    }
+
+    pub fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    pub fn record_batches(&self) -> &[RecordBatch] {
+        &self.batches
+    }
+
+    /// Returns column array and row index in it.
+    fn locate(&self, coord: CellCoord) -> Option<(&dyn Array, usize)> {
+        let row = coord.row_uid.0 as usize;
+        if row >= self.row_count {
+            return None;
+        }
+        let batch_idx = self.batch_offsets.partition_point(|offset| *offset <= row) - 1;
+        let batch = &self.batches[batch_idx];
+        let array = batch.columns().get(coord.col_uid.0 as usize)?;
+        Some((array.as_ref(), row - self.batch_offsets[batch_idx]))
+    }
+}
+
+fn format_value(array: &dyn Array, idx: usize) -> String {
+    match ArrayFormatter::try_new(array, &FormatOptions::default()) {
+        Ok(formatter) => formatter.value(idx).to_string(),
+        Err(e) => e.to_string(),
+    }
+}
+
+/// Convert to the closest [Variant], types without a counterpart are converted to strings.
+fn to_variant(array: &dyn Array, idx: usize) -> Variant {
+    if array.is_null(idx) {
+        return Variant::Empty;
+    }
+    match array.data_type() {
+        DataType::Boolean => Variant::Bool(array.as_boolean().value(idx)),
+        DataType::Int8 => Variant::I32(array.as_primitive::<Int8Type>().value(idx) as i32),
+        DataType::Int16 => Variant::I32(array.as_primitive::<Int16Type>().value(idx) as i32),
+        DataType::Int32 => Variant::I32(array.as_primitive::<Int32Type>().value(idx)),
+        DataType::Int64 => Variant::I64(array.as_primitive::<Int64Type>().value(idx)),
+        DataType::UInt8 => Variant::U32(array.as_primitive::<UInt8Type>().value(idx) as u32),
+        DataType::UInt16 => Variant::U32(array.as_primitive::<UInt16Type>().value(idx) as u32),
+        DataType::UInt32 => Variant::U32(array.as_primitive::<UInt32Type>().value(idx)),
+        DataType::UInt64 => Variant::U64(array.as_primitive::<UInt64Type>().value(idx)),
+        DataType::Float32 => Variant::F32(array.as_primitive::<Float32Type>().value(idx)),
+        DataType::Float64 => Variant::F64(array.as_primitive::<Float64Type>().value(idx)),
+        DataType::Utf8 => Variant::Str(array.as_string::<i32>().value(idx).to_string()),
+        DataType::LargeUtf8 => Variant::Str(array.as_string::<i64>().value(idx).to_string()),
+        _ => Variant::Str(format_value(array, idx)),
+    }
+}
+
+impl TableBackend for ArrowBackend {
+    fn clear(&mut self) {
+        self.batches.clear();
+        self.batch_offsets.clear();
+        self.row_count = 0;
+        self.one_shot_flags.cleared = true;
+        self.one_shot_flags.row_set_updated = true;
+    }
+
+    fn persistent_flags(&self) -> &PersistentFlags {
+        &self.persistent_flags
+    }
+
+    fn one_shot_flags(&self) -> &OneShotFlags {
+        &self.one_shot_flags
+    }
+
+    fn one_shot_flags_mut(&mut self) -> &mut OneShotFlags {
+        &mut self.one_shot_flags
+    }
+
+    fn available_columns(&self) -> impl Iterator<Item = ColumnUid> {
+        (0..self.columns.len()).map(|idx| ColumnUid(idx as u32))
+    }
+
+    fn used_columns(&self) -> impl Iterator<Item = ColumnUid> {
+        self.is_used
+            .iter()
+            .enumerate()
+            .filter(|(_, is_used)| **is_used)
+            .map(|(idx, _)| ColumnUid(idx as u32))
+    }
+
+    fn column_info(&self, col_uid: ColumnUid) -> Option<&BackendColumn> {
+        self.columns.get(col_uid.0 as usize)
+    }
+
+    fn use_column(&mut self, col_uid: ColumnUid, is_used: bool) {
+        if let Some(used) = self.is_used.get_mut(col_uid.0 as usize) {
+            *used = is_used;
+            self.one_shot_flags.column_info_updated = true;
+        }
+    }
+
+    fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    fn row_uid(&self, row_idx: VisualRowIdx) -> Option<RowUid> {
+        (row_idx.0 < self.row_count).then_some(RowUid(row_idx.0 as u32))
+    }
+
+    fn get(&self, coord: CellCoord) -> Option<Variant> {
+        let (array, idx) = self.locate(coord)?;
+        Some(to_variant(array, idx))
+    }
+
+    fn display_text(&self, coord: CellCoord) -> Option<String> {
+        let (array, idx) = self.locate(coord)?;
+        if array.is_null(idx) {
+            return Some(String::new());
+        }
+        Some(format_value(array, idx))
+    }
+
+    #[cfg(feature = "gui")]
+    fn show_cell_view(&self, coord: CellCoord, ui: &mut Ui) {
+        let Some((array, idx)) = self.locate(coord) else {
+            return;
+        };
+        if array.is_null(idx) {
+            return;
+        }
+        if let DataType::Boolean = array.data_type() {
+            let mut v = array.as_boolean().value(idx);
+            ui.checkbox(&mut v, "");
+        } else {
+            ui.label(format_value(array, idx));
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    fn show_cell_editor(&self, coord: CellCoord, ui: &mut Ui) -> Option<Response> {
+        self.show_cell_view(coord, ui);
+        None
+    }
+
+    fn commit_cell_edit(&mut self, coord: CellCoord) {
+        let _ = coord;
+    }
+}
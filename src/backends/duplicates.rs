@@ -0,0 +1,135 @@
+use crate::backend::{ColumnUid, RowUid};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Rows with the same key values as an earlier inserted row, kept up to date by rescanning only the rows
+/// that changed, see [VariantBackend::find_duplicates](crate::backends::variant::VariantBackend::find_duplicates).
+#[derive(Default)]
+pub struct DuplicateRows {
+    pub(super) columns: Vec<ColumnUid>,
+    /// Key of each row, rows with all key cells empty are left out.
+    keys: HashMap<RowUid, Vec<String>>,
+    /// Rows sharing each key, the first one is the original.
+    groups: HashMap<Vec<String>, BTreeSet<RowUid>>,
+    duplicates: HashSet<RowUid>,
+}
+
+impl DuplicateRows {
+    pub(super) fn new(columns: Vec<ColumnUid>) -> Self {
+        DuplicateRows {
+            columns,
+            ..Default::default()
+        }
+    }
+
+    /// Set a new key of a row, None if it has no key or was removed.
+    /// Returns rows that became or stopped being duplicates.
+    pub(super) fn update(
+        &mut self,
+        keys: impl IntoIterator<Item = (RowUid, Option<Vec<String>>)>,
+    ) -> Vec<RowUid> {
+        let mut affected_keys = HashSet::new();
+        let mut changed = vec![];
+        for (row_uid, key) in keys {
+            if let Some(old) = self.keys.remove(&row_uid) {
+                if let Some(group) = self.groups.get_mut(&old) {
+                    group.remove(&row_uid);
+                    if group.is_empty() {
+                        self.groups.remove(&old);
+                    }
+                }
+                affected_keys.insert(old);
+            }
+            match key {
+                Some(key) => {
+                    self.groups.entry(key.clone()).or_default().insert(row_uid);
+                    self.keys.insert(row_uid, key.clone());
+                    affected_keys.insert(key);
+                }
+                None => {
+                    if self.duplicates.remove(&row_uid) {
+                        changed.push(row_uid);
+                    }
+                }
+            }
+        }
+        for key in affected_keys {
+            let Some(group) = self.groups.get(&key) else {
+                continue;
+            };
+            for (idx, row_uid) in group.iter().enumerate() {
+                let status_changed = if idx > 0 {
+                    self.duplicates.insert(*row_uid)
+                } else {
+                    self.duplicates.remove(row_uid)
+                };
+                if status_changed {
+                    changed.push(*row_uid);
+                }
+            }
+        }
+        changed
+    }
+
+    pub fn contains(&self, row_uid: RowUid) -> bool {
+        self.duplicates.contains(&row_uid)
+    }
+
+    /// Duplicate rows in the order they were inserted.
+    pub fn rows(&self) -> Vec<RowUid> {
+        let mut rows: Vec<RowUid> = self.duplicates.iter().copied().collect();
+        rows.sort();
+        rows
+    }
+
+    pub fn len(&self) -> usize {
+        self.duplicates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.duplicates.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::{CellCoord, ColumnUid, RowUid, TableBackend};
+    use crate::backends::variant::VariantBackend;
+    use rvariant::{Variant, VariantTy};
+
+    const NAME: ColumnUid = ColumnUid(0);
+
+    fn backend(names: &[&str]) -> (VariantBackend, Vec<RowUid>) {
+        let mut backend = VariantBackend::new([("name".to_string(), VariantTy::Str, None)]);
+        let rows = names
+            .iter()
+            .map(|name| backend.insert_row([(NAME, Variant::Str(name.to_string()))]))
+            .collect();
+        (backend, rows)
+    }
+
+    #[test]
+    fn empty_keys_are_not_duplicates() {
+        let (backend, rows) = backend(&["a", "", "a", " ", ""]);
+        assert_eq!(backend.duplicate_rows(&[NAME]), vec![rows[2]]);
+    }
+
+    #[test]
+    fn update_rescans_edited_rows() {
+        let (mut backend, rows) = backend(&["a", "b", "a"]);
+        let mut duplicates = backend.find_duplicates(&[NAME]);
+        assert_eq!(duplicates.rows(), vec![rows[2]]);
+
+        // First occurrence edited, the later row becomes the original
+        backend.modify_many(vec![(
+            CellCoord {
+                row_uid: rows[0],
+                col_uid: NAME,
+            },
+            Variant::Str("b".to_string()),
+        )]);
+        let mut changed = backend.update_duplicates(&mut duplicates, [rows[0]]);
+        assert_eq!(duplicates.rows(), vec![rows[1]]);
+        changed.sort();
+        assert_eq!(changed, vec![rows[1], rows[2]]);
+    }
+}
@@ -0,0 +1,519 @@
+use crate::backend::{
+    BackendColumn, CellCoord, CellState, ColumnOrigin, ColumnUid, FetchError, OneShotFlags,
+    PersistentFlags, QueryableBackend, RowUid, TableBackend, VisualRowIdx,
+};
+use crate::query::{FilterOp, QuerySpec};
+#[cfg(feature = "gui")]
+use egui::Ui;
+#[cfg(feature = "gui")]
+use egui_extras::Column as TableColumnConfig;
+use rvariant::{Variant, VariantTy};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+/// Sorts and filters rows of a backend that cannot do it by itself, reading all the cells through
+/// [TableBackend::get]. Rows must be available locally for this to work.
+pub struct LocalQuery<B> {
+    inner: B,
+    query: QuerySpec,
+    /// Inner backend rows matching the query in sorted order, None if not computed yet or invalidated by a change.
+    visible_rows: RefCell<Option<Vec<RowUid>>>,
+    /// Positions of rows in the inner backend order, None if not computed yet or invalidated by a change.
+    inner_positions: RefCell<Option<HashMap<RowUid, VisualRowIdx>>>,
+}
+
+impl<B: TableBackend> LocalQuery<B> {
+    pub fn new(inner: B) -> Self {
+        LocalQuery {
+            inner,
+            query: QuerySpec::default(),
+            visible_rows: RefCell::new(None),
+            inner_positions: RefCell::new(None),
+        }
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    /// Sorted rows are recomputed on next access, call after modifying the inner backend directly.
+    pub fn inner_mut(&mut self) -> &mut B {
+        self.invalidate();
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    /// Run `f` on the rows matching the query, in sorted order.
+    fn with_visible_rows<R>(&self, f: impl FnOnce(&[RowUid]) -> R) -> R {
+        let mut visible_rows = self.visible_rows.borrow_mut();
+        let rows = visible_rows.get_or_insert_with(|| {
+            let rows = self.inner.visible_rows();
+            self.query.apply(&rows, |coord| self.inner.get(coord))
+        });
+        f(rows)
+    }
+
+    /// Row index in the inner backend order.
+    fn inner_row_idx(&self, row_idx: VisualRowIdx) -> Option<VisualRowIdx> {
+        let row_uid = self.row_uid(row_idx)?;
+        let mut inner_positions = self.inner_positions.borrow_mut();
+        let positions = inner_positions.get_or_insert_with(|| {
+            self.inner
+                .visible_rows()
+                .into_iter()
+                .enumerate()
+                .map(|(idx, row_uid)| (row_uid, VisualRowIdx(idx)))
+                .collect()
+        });
+        positions.get(&row_uid).copied()
+    }
+
+    /// Drop sorted rows and positions, they are recomputed on next access.
+    fn invalidate(&mut self) {
+        self.visible_rows.get_mut().take();
+        self.inner_positions.get_mut().take();
+    }
+
+    fn invalidate_if_changed(&mut self) {
+        let flags = self.inner.one_shot_flags();
+        if flags.row_set_updated
+            || flags.visible_row_vec_updated
+            || flags.cleared
+            || flags.reloaded
+            || flags.rows_remapped.is_some()
+            || !flags.cells_updated.is_empty()
+        {
+            self.invalidate();
+        }
+    }
+}
+
+impl<B: TableBackend> TableBackend for LocalQuery<B> {
+    fn reload(&mut self) {
+        self.invalidate();
+        self.inner.reload();
+    }
+
+    fn clear(&mut self) {
+        self.invalidate();
+        self.inner.clear();
+    }
+
+    fn commit_all(&mut self) {
+        self.inner.commit_all();
+    }
+
+    fn commit_immediately(&mut self, enabled: bool) {
+        self.inner.commit_immediately(enabled);
+    }
+
+    fn persistent_flags(&self) -> &PersistentFlags {
+        self.inner.persistent_flags()
+    }
+
+    fn one_shot_flags(&self) -> &OneShotFlags {
+        self.inner.one_shot_flags()
+    }
+
+    fn one_shot_flags_mut(&mut self) -> &mut OneShotFlags {
+        // Flags are reset through this method, last chance to see what changed
+        self.invalidate_if_changed();
+        self.inner.one_shot_flags_mut()
+    }
+
+    fn poll(&mut self) {
+        self.inner.poll();
+        self.invalidate_if_changed();
+    }
+
+    fn available_columns(&self) -> impl Iterator<Item = ColumnUid> {
+        self.inner.available_columns()
+    }
+
+    fn used_columns(&self) -> impl Iterator<Item = ColumnUid> {
+        self.inner.used_columns()
+    }
+
+    fn column_info(&self, col_uid: ColumnUid) -> Option<&BackendColumn> {
+        self.inner.column_info(col_uid)
+    }
+
+    fn use_column(&mut self, col_uid: ColumnUid, is_used: bool) {
+        self.inner.use_column(col_uid, is_used);
+    }
+
+    fn can_rename_column(&self, col_uid: ColumnUid) -> bool {
+        self.inner.can_rename_column(col_uid)
+    }
+
+    fn rename_column(&mut self, col_uid: ColumnUid, name: String) {
+        self.inner.rename_column(col_uid, name);
+    }
+
+    fn column_type_choices(&self, col_uid: ColumnUid) -> Vec<VariantTy> {
+        self.inner.column_type_choices(col_uid)
+    }
+
+    fn change_column_type(&mut self, col_uid: ColumnUid, ty: VariantTy) -> Vec<CellCoord> {
+        self.invalidate();
+        self.inner.change_column_type(col_uid, ty)
+    }
+
+    fn column_origin(&self, col_uid: ColumnUid) -> ColumnOrigin {
+        self.inner.column_origin(col_uid)
+    }
+
+    fn materialize_column(&mut self, col_uid: ColumnUid) {
+        self.invalidate();
+        self.inner.materialize_column(col_uid);
+    }
+
+    fn column_synonyms(&self, col_uid: ColumnUid) -> Option<&[String]> {
+        self.inner.column_synonyms(col_uid)
+    }
+
+    fn set_column_synonyms(&mut self, col_uid: ColumnUid, synonyms: Vec<String>) {
+        self.inner.set_column_synonyms(col_uid, synonyms);
+    }
+
+    fn column_description(&self, col_uid: ColumnUid) -> Option<&str> {
+        self.inner.column_description(col_uid)
+    }
+
+    fn column_example(&self, col_uid: ColumnUid) -> Option<Variant> {
+        self.inner.column_example(col_uid)
+    }
+
+    #[cfg(feature = "gui")]
+    fn column_render_config(&mut self, col_uid: ColumnUid) -> TableColumnConfig {
+        self.inner.column_render_config(col_uid)
+    }
+
+    fn queryable(&self) -> Option<&dyn QueryableBackend> {
+        Some(self)
+    }
+
+    fn queryable_mut(&mut self) -> Option<&mut dyn QueryableBackend> {
+        Some(self)
+    }
+
+    fn row_count(&self) -> usize {
+        if self.query.is_empty() {
+            return self.inner.row_count();
+        }
+        self.with_visible_rows(|rows| rows.len())
+    }
+
+    fn row_uid(&self, row_idx: VisualRowIdx) -> Option<RowUid> {
+        if self.query.is_empty() {
+            return self.inner.row_uid(row_idx);
+        }
+        self.with_visible_rows(|rows| rows.get(row_idx.0).copied())
+    }
+
+    fn visible_rows(&self) -> Vec<RowUid> {
+        if self.query.is_empty() {
+            return self.inner.visible_rows();
+        }
+        self.with_visible_rows(|rows| rows.to_vec())
+    }
+
+    fn hint_visible_range(&mut self, range: Range<VisualRowIdx>) {
+        if self.query.is_empty() {
+            self.inner.hint_visible_range(range);
+        }
+    }
+
+    fn failed_fetches(&self) -> Vec<FetchError> {
+        self.inner.failed_fetches()
+    }
+
+    fn retry_fetch(&mut self, rows: Range<VisualRowIdx>) {
+        self.inner.retry_fetch(rows);
+    }
+
+    fn get(&self, coord: CellCoord) -> Option<Variant> {
+        self.inner.get(coord)
+    }
+
+    fn display_text(&self, coord: CellCoord) -> Option<String> {
+        self.inner.display_text(coord)
+    }
+
+    fn cell_error(&self, coord: CellCoord) -> Option<String> {
+        self.inner.cell_error(coord)
+    }
+
+    fn validation_errors(&self) -> Vec<(CellCoord, String)> {
+        let errors = self.inner.validation_errors();
+        if self.query.is_empty() || errors.is_empty() {
+            return errors;
+        }
+        let mut by_row: HashMap<RowUid, Vec<(CellCoord, String)>> = HashMap::new();
+        for (coord, error) in errors {
+            by_row
+                .entry(coord.row_uid)
+                .or_default()
+                .push((coord, error));
+        }
+        self.with_visible_rows(|rows| {
+            rows.iter()
+                .filter_map(|row_uid| by_row.remove(row_uid))
+                .flatten()
+                .collect()
+        })
+    }
+
+    fn cell_state(&self, coord: CellCoord) -> CellState {
+        self.inner.cell_state(coord)
+    }
+
+    #[cfg(feature = "gui")]
+    fn show_cell_view(&self, coord: CellCoord, ui: &mut Ui) {
+        self.inner.show_cell_view(coord, ui);
+    }
+
+    #[cfg(feature = "gui")]
+    fn show_cell_editor(&self, coord: CellCoord, ui: &mut Ui) -> Option<egui::Response> {
+        self.inner.show_cell_editor(coord, ui)
+    }
+
+    fn commit_cell_edit(&mut self, coord: CellCoord) {
+        self.invalidate();
+        self.inner.commit_cell_edit(coord);
+    }
+
+    fn cancel_cell_edit(&mut self, coord: CellCoord) {
+        self.inner.cancel_cell_edit(coord);
+    }
+
+    fn start_cell_edit(&mut self, coord: CellCoord, typed: Option<String>) {
+        self.inner.start_cell_edit(coord, typed);
+    }
+
+    fn modify_many(&mut self, values: Vec<(CellCoord, Variant)>) {
+        self.invalidate();
+        self.inner.modify_many(values);
+    }
+
+    fn clear_cells(&mut self, coords: impl Iterator<Item = CellCoord>) {
+        self.invalidate();
+        self.inner.clear_cells(coords);
+    }
+
+    fn skip_row(&mut self, row_uid: RowUid, skip: bool) {
+        self.inner.skip_row(row_uid, skip);
+    }
+
+    fn is_row_skipped(&self, row_uid: RowUid) -> bool {
+        self.inner.is_row_skipped(row_uid)
+    }
+
+    fn incomplete_rows(&self) -> Vec<RowUid> {
+        let incomplete = self.inner.incomplete_rows();
+        if self.query.is_empty() || incomplete.is_empty() {
+            return incomplete;
+        }
+        let incomplete: HashSet<RowUid> = incomplete.into_iter().collect();
+        self.with_visible_rows(|rows| {
+            rows.iter()
+                .filter(|row_uid| incomplete.contains(row_uid))
+                .copied()
+                .collect()
+        })
+    }
+
+    fn can_append_rows(&self) -> bool {
+        self.inner.can_append_rows()
+    }
+
+    fn append_row(&mut self) -> Option<RowUid> {
+        self.invalidate();
+        self.inner.append_row()
+    }
+
+    fn can_remove_rows(&self) -> bool {
+        self.inner.can_remove_rows()
+    }
+
+    fn remove_rows(&mut self, row_uids: Vec<RowUid>) {
+        self.invalidate();
+        self.inner.remove_rows(row_uids);
+    }
+
+    fn pin_row(&mut self, row_uid: RowUid, pinned: bool) {
+        self.inner.pin_row(row_uid, pinned);
+    }
+
+    fn pinned_rows(&self) -> Vec<RowUid> {
+        self.inner.pinned_rows()
+    }
+
+    fn move_row(&mut self, row_uid: RowUid, to: VisualRowIdx) {
+        let Some(to) = self.inner_row_idx(to) else {
+            return;
+        };
+        self.invalidate();
+        self.inner.move_row(row_uid, to);
+    }
+
+    fn move_cell_value(&mut self, col_uid: ColumnUid, from: VisualRowIdx, to: VisualRowIdx) {
+        if self.query.is_empty() {
+            self.inner.move_cell_value(col_uid, from, to);
+            return;
+        }
+        if from.0 == to.0 || from.0.max(to.0) >= self.row_count() {
+            return;
+        }
+        // Values only move through the rows that are shown, hidden rows in between are left as they are
+        let rows =
+            self.with_visible_rows(|rows| rows[from.0.min(to.0)..=from.0.max(to.0)].to_vec());
+        let mut values: Vec<Variant> = rows
+            .iter()
+            .map(|row_uid| {
+                self.inner
+                    .get(CellCoord {
+                        row_uid: *row_uid,
+                        col_uid,
+                    })
+                    .unwrap_or(Variant::Empty)
+            })
+            .collect();
+        if from.0 < to.0 {
+            values.rotate_left(1);
+        } else {
+            values.rotate_right(1);
+        }
+        let values = rows
+            .into_iter()
+            .zip(values)
+            .map(|(row_uid, value)| (CellCoord { row_uid, col_uid }, value))
+            .collect();
+        self.invalidate();
+        self.inner.modify_many(values);
+    }
+
+    fn undo(&mut self) {
+        self.invalidate();
+        self.inner.undo();
+    }
+
+    fn redo(&mut self) {
+        self.invalidate();
+        self.inner.redo();
+    }
+
+    fn history_position(&self) -> Option<(usize, usize)> {
+        self.inner.history_position()
+    }
+
+    #[cfg(feature = "gui")]
+    fn on_cell_view_response(&mut self, coord: CellCoord, resp: &egui::Response) -> Option<()> {
+        self.inner.on_cell_view_response(coord, resp)
+    }
+
+    fn on_highlight_cell(&mut self, coord: CellCoord) {
+        self.inner.on_highlight_cell(coord);
+    }
+}
+
+impl<B: TableBackend> QueryableBackend for LocalQuery<B> {
+    fn supports_sort(&self, col_uid: ColumnUid) -> bool {
+        self.inner.column_info(col_uid).is_some()
+    }
+
+    fn supports_filter(&self, col_uid: ColumnUid, _op: FilterOp) -> bool {
+        self.inner.column_info(col_uid).is_some()
+    }
+
+    fn apply(&mut self, spec: &QuerySpec) {
+        self.query = spec.clone();
+        self.invalidate();
+        self.inner.one_shot_flags_mut().visible_row_vec_updated = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::variant::VariantBackend;
+    use crate::query::ColumnFilter;
+
+    const KIND: ColumnUid = ColumnUid(0);
+    const VALUE: ColumnUid = ColumnUid(1);
+
+    fn filtered_backend() -> (LocalQuery<VariantBackend>, Vec<RowUid>) {
+        let inner = VariantBackend::new([
+            ("kind".to_string(), VariantTy::Str, None),
+            ("value".to_string(), VariantTy::Str, None),
+        ]);
+        let mut backend = LocalQuery::new(inner);
+        let rows = ["a", "b", "a", "b", "a"]
+            .into_iter()
+            .enumerate()
+            .map(|(idx, kind)| {
+                backend.inner_mut().insert_row([
+                    (KIND, Variant::Str(kind.to_string())),
+                    (VALUE, Variant::Str(format!("v{idx}"))),
+                ])
+            })
+            .collect();
+        backend.apply(&QuerySpec {
+            filters: vec![ColumnFilter {
+                col_uid: KIND,
+                op: FilterOp::Contains,
+                value: "a".to_string(),
+            }],
+            ..Default::default()
+        });
+        (backend, rows)
+    }
+
+    fn values(backend: &LocalQuery<VariantBackend>, rows: &[RowUid]) -> Vec<Variant> {
+        rows.iter()
+            .map(|row_uid| {
+                backend
+                    .get(CellCoord {
+                        row_uid: *row_uid,
+                        col_uid: VALUE,
+                    })
+                    .unwrap_or(Variant::Empty)
+            })
+            .collect()
+    }
+
+    fn strs(values: &[&str]) -> Vec<Variant> {
+        values.iter().map(|v| Variant::Str(v.to_string())).collect()
+    }
+
+    #[test]
+    fn move_cell_value_skips_filtered_out_rows() {
+        let (mut backend, rows) = filtered_backend();
+        assert_eq!(backend.row_count(), 3);
+
+        backend.move_cell_value(VALUE, VisualRowIdx(0), VisualRowIdx(2));
+        assert_eq!(
+            values(&backend, &rows),
+            strs(&["v2", "v1", "v4", "v3", "v0"])
+        );
+
+        backend.move_cell_value(VALUE, VisualRowIdx(2), VisualRowIdx(0));
+        assert_eq!(
+            values(&backend, &rows),
+            strs(&["v0", "v1", "v2", "v3", "v4"])
+        );
+    }
+
+    #[test]
+    fn move_row_targets_filtered_row() {
+        let (mut backend, rows) = filtered_backend();
+
+        backend.move_row(rows[4], VisualRowIdx(0));
+        assert_eq!(backend.visible_rows(), vec![rows[4], rows[0], rows[2]]);
+        assert_eq!(backend.inner().visible_rows()[0], rows[4]);
+    }
+}
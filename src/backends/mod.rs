@@ -1 +1,8 @@
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod duplicates;
+pub mod local_query;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod undo;
 pub mod variant;
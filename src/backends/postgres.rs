@@ -0,0 +1,533 @@
+use crate::backend::{
+    BackendColumn, CellCoord, ColumnUid, FetchError, OneShotFlags, PersistentFlags, RowUid,
+    TableBackend, VisualRowIdx,
+};
+use crate::backends::variant::VariantBackend;
+use crate::util::variant_to_string;
+#[cfg(feature = "gui")]
+use egui::{Response, Ui};
+use log::warn;
+use rvariant::{Variant, VariantTy};
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use tokio::runtime::Handle;
+
+/// Table stored in a PostgreSQL database, rows are loaded page by page in the background,
+/// each page continuing after the last key of the previous one.
+/// All values are transferred as text and converted to [Variant] according to column types.
+pub struct PgBackend {
+    pool: PgPool,
+    runtime: Handle,
+    /// Requests a repaint of the UI, see [repaint_on_update](Self::repaint_on_update).
+    repaint: Option<RepaintFn>,
+    /// Schema of the table, the current one (first in the search path) if None.
+    schema: Option<String>,
+    table: String,
+    key_column: String,
+    page_size: usize,
+
+    /// Locally cached data, also used to show and edit cells.
+    cache: VariantBackend,
+    /// Primary key of each loaded row, as text.
+    row_keys: HashMap<RowUid, String>,
+    /// Postgres type name of each column, index is the column uid.
+    column_types: Vec<String>,
+    /// Key of the last loaded row, next page starts after it.
+    last_key: Option<String>,
+    /// Incremented on each reload, to ignore data that was requested before.
+    generation: u32,
+    tx: Sender<(u32, Message)>,
+    rx: Receiver<(u32, Message)>,
+
+    commit_immediately: bool,
+    uncommitted: Vec<CellCoord>,
+    commits_in_flight: usize,
+    last_error: Option<String>,
+    /// Pages that could not be loaded, loading stops at the first one until it is retried.
+    failed_pages: Vec<FetchError>,
+    persistent_flags: PersistentFlags,
+    one_shot_flags: OneShotFlags,
+}
+
+type RepaintFn = Arc<dyn Fn() + Send + Sync>;
+
+enum Message {
+    /// (name, postgres type name) of each column
+    Columns(Vec<(String, String)>),
+    /// Primary key and values of each row, is_last_page
+    Rows(Vec<(String, Vec<Option<String>>)>, bool),
+    /// Offset and error of a page that could not be loaded
+    PageFailed(usize, String),
+    /// Result of a cell update
+    Committed(Result<(), String>),
+    Error(String),
+}
+
+impl PgBackend {
+    /// Start loading `table` ordered by `key_column`, which must uniquely identify rows for editing to work.
+    /// All database requests are spawned on the provided tokio `runtime`.
+    pub fn new(
+        pool: PgPool,
+        runtime: Handle,
+        table: impl Into<String>,
+        key_column: impl Into<String>,
+    ) -> Self {
+        let (tx, rx) = channel();
+        let mut backend = PgBackend {
+            pool,
+            runtime,
+            repaint: None,
+            schema: None,
+            table: table.into(),
+            key_column: key_column.into(),
+            page_size: 1000,
+            cache: VariantBackend::new([]),
+            row_keys: HashMap::new(),
+            column_types: Vec::new(),
+            last_key: None,
+            generation: 0,
+            tx,
+            rx,
+            commit_immediately: true,
+            uncommitted: Vec::new(),
+            commits_in_flight: 0,
+            last_error: None,
+            failed_pages: Vec::new(),
+            persistent_flags: PersistentFlags::default(),
+            one_shot_flags: OneShotFlags {
+                first_pass: true,
+                ..Default::default()
+            },
+        };
+        backend.reload();
+        backend
+    }
+
+    /// Number of rows requested at once.
+    pub fn set_page_size(&mut self, page_size: usize) {
+        self.page_size = page_size.max(1);
+    }
+
+    /// Load the table from `schema` instead of the current one, all data is reloaded.
+    pub fn set_schema(&mut self, schema: impl Into<String>) {
+        self.schema = Some(schema.into());
+        self.reload();
+    }
+
+    fn qualified_table(&self) -> String {
+        match &self.schema {
+            Some(schema) => format!("{}.{}", quote_ident(schema), quote_ident(&self.table)),
+            None => quote_ident(&self.table),
+        }
+    }
+
+    /// Request repaint when new data arrives, so that poll() is called without user interaction.
+    #[cfg(feature = "gui")]
+    pub fn repaint_on_update(&mut self, ctx: egui::Context) {
+        self.repaint = Some(Arc::new(move || ctx.request_repaint()));
+    }
+
+    /// Last error reported by the database, if any.
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    fn spawn_load(&self) {
+        let pool = self.pool.clone();
+        let tx = self.tx.clone();
+        let repaint = self.repaint.clone();
+        let generation = self.generation;
+        let schema = self.schema.clone();
+        let table = self.table.clone();
+        let qualified_table = self.qualified_table();
+        let key_column = self.key_column.clone();
+        let page_size = self.page_size;
+        self.runtime.spawn(async move {
+            let send = |message: Message| {
+                let _ = tx.send((generation, message));
+                if let Some(repaint) = &repaint {
+                    repaint();
+                }
+            };
+            let columns: Vec<(String, String)> = match sqlx::query(
+                "SELECT column_name::text, udt_name::text FROM information_schema.columns \
+                 WHERE table_schema = COALESCE($2, current_schema()) AND table_name = $1 \
+                 ORDER BY ordinal_position",
+            )
+            .bind(&table)
+            .bind(schema)
+            .fetch_all(&pool)
+            .await
+            {
+                Ok(rows) => rows.iter().map(|row| (row.get(0), row.get(1))).collect(),
+                Err(e) => {
+                    send(Message::Error(e.to_string()));
+                    return;
+                }
+            };
+            let Some((_, key_type)) = columns.iter().find(|(name, _)| *name == key_column) else {
+                send(Message::Error(format!(
+                    "key column {key_column} not found in {qualified_table}"
+                )));
+                return;
+            };
+            let sql = page_sql(
+                &qualified_table,
+                &key_column,
+                key_type,
+                columns.iter().map(|(name, _)| name.as_str()),
+            );
+            let column_count = columns.len();
+            send(Message::Columns(columns));
+            load_pages(&pool, &sql, column_count, page_size, 0, None, send).await;
+        });
+    }
+
+    /// Continue loading pages after the last loaded row at `offset`, after one of them failed.
+    fn spawn_load_pages(&self, offset: usize) {
+        let pool = self.pool.clone();
+        let tx = self.tx.clone();
+        let repaint = self.repaint.clone();
+        let generation = self.generation;
+        let column_names: Vec<String> = (0..self.column_types.len())
+            .filter_map(|idx| self.cache.column_info(ColumnUid(idx as u32)))
+            .map(|c| c.name.clone())
+            .collect();
+        let Some(key_type) = column_names
+            .iter()
+            .position(|name| *name == self.key_column)
+            .and_then(|idx| self.column_types.get(idx))
+        else {
+            return;
+        };
+        let sql = page_sql(
+            &self.qualified_table(),
+            &self.key_column,
+            key_type,
+            column_names.iter().map(|name| name.as_str()),
+        );
+        let column_count = column_names.len();
+        let page_size = self.page_size;
+        let after = self.last_key.clone();
+        self.runtime.spawn(async move {
+            let send = |message: Message| {
+                let _ = tx.send((generation, message));
+                if let Some(repaint) = &repaint {
+                    repaint();
+                }
+            };
+            load_pages(&pool, &sql, column_count, page_size, offset, after, send).await;
+        });
+    }
+
+    fn spawn_update(&mut self, coord: CellCoord) {
+        let (Some(key), Some(column), Some(udt_name)) = (
+            self.row_keys.get(&coord.row_uid),
+            self.cache.column_info(coord.col_uid),
+            self.column_types.get(coord.col_uid.0 as usize),
+        ) else {
+            return;
+        };
+        let value = match self.cache.get(coord) {
+            None | Some(Variant::Empty) => None,
+            Some(value) => Some(variant_to_string(&value)),
+        };
+        let sql = format!(
+            "UPDATE {} SET {} = $1::{} WHERE {}::text = $2",
+            self.qualified_table(),
+            quote_ident(&column.name),
+            quote_ident(udt_name),
+            quote_ident(&self.key_column),
+        );
+        let key = key.clone();
+        let pool = self.pool.clone();
+        let tx = self.tx.clone();
+        let repaint = self.repaint.clone();
+        let generation = self.generation;
+        self.commits_in_flight += 1;
+        self.runtime.spawn(async move {
+            let result = sqlx::query(&sql).bind(value).bind(key).execute(&pool).await;
+            let message = Message::Committed(result.map(|_| ()).map_err(|e| e.to_string()));
+            let _ = tx.send((generation, message));
+            if let Some(repaint) = &repaint {
+                repaint();
+            }
+        });
+    }
+
+    fn update_uncommitted_flag(&mut self) {
+        self.persistent_flags.have_uncommitted_data =
+            !self.uncommitted.is_empty() || self.commits_in_flight > 0;
+    }
+}
+
+fn variant_ty(udt_name: &str) -> VariantTy {
+    match udt_name {
+        "bool" => VariantTy::Bool,
+        "int2" | "int4" => VariantTy::I32,
+        "int8" => VariantTy::I64,
+        "float4" => VariantTy::F32,
+        "float8" => VariantTy::F64,
+        _ => VariantTy::Str,
+    }
+}
+
+/// Query of one page of rows with keys greater than $2, or the first page if it is NULL.
+/// `table` is already quoted, `key_type` is the postgres type name of the key column.
+fn page_sql<'a>(
+    table: &str,
+    key_column: &str,
+    key_type: &str,
+    columns: impl Iterator<Item = &'a str>,
+) -> String {
+    let select = columns
+        .map(|name| format!("{}::text", quote_ident(name)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let key = quote_ident(key_column);
+    format!(
+        "SELECT {key}::text, {select} FROM {table} \
+         WHERE $2::text IS NULL OR {key} > $2::text::{} ORDER BY {key} LIMIT $1",
+        quote_ident(key_type),
+    )
+}
+
+/// Load pages one by one after the row with key `after` at `offset`, stops at the last one or on the first error.
+/// Offset is only used to report which rows failed to load.
+async fn load_pages(
+    pool: &PgPool,
+    sql: &str,
+    column_count: usize,
+    page_size: usize,
+    mut offset: usize,
+    mut after: Option<String>,
+    send: impl Fn(Message),
+) {
+    loop {
+        let page = match sqlx::query(sql)
+            .bind(page_size as i64)
+            .bind(after.clone())
+            .fetch_all(pool)
+            .await
+        {
+            Ok(page) => page,
+            Err(e) => {
+                send(Message::PageFailed(offset, e.to_string()));
+                return;
+            }
+        };
+        let is_last_page = page.len() < page_size;
+        offset += page.len();
+        let rows: Vec<(String, Vec<Option<String>>)> = page
+            .iter()
+            .map(|row| {
+                let key: Option<String> = row.get(0);
+                let values = (1..=column_count).map(|idx| row.get(idx)).collect();
+                (key.unwrap_or_default(), values)
+            })
+            .collect();
+        if let Some((key, _)) = rows.last() {
+            after = Some(key.clone());
+        }
+        send(Message::Rows(rows, is_last_page));
+        if is_last_page {
+            break;
+        }
+    }
+}
+
+fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+impl TableBackend for PgBackend {
+    fn reload(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        self.cache.remove_all_columns();
+        self.row_keys.clear();
+        self.column_types.clear();
+        self.last_key = None;
+        self.uncommitted.clear();
+        self.last_error = None;
+        self.failed_pages.clear();
+        self.persistent_flags = PersistentFlags {
+            cells_loading: true,
+            ..Default::default()
+        };
+        self.one_shot_flags.reloaded = true;
+        self.one_shot_flags.column_info_updated = true;
+        self.one_shot_flags.row_set_updated = true;
+        self.spawn_load();
+    }
+
+    fn clear(&mut self) {
+        self.cache.clear();
+        self.row_keys.clear();
+        self.uncommitted.clear();
+        self.update_uncommitted_flag();
+        self.one_shot_flags.cleared = true;
+    }
+
+    fn commit_all(&mut self) {
+        for coord in core::mem::take(&mut self.uncommitted) {
+            self.spawn_update(coord);
+        }
+        self.update_uncommitted_flag();
+    }
+
+    fn commit_immediately(&mut self, enabled: bool) {
+        self.commit_immediately = enabled;
+        if enabled {
+            self.commit_all();
+        }
+    }
+
+    fn persistent_flags(&self) -> &PersistentFlags {
+        &self.persistent_flags
+    }
+
+    fn one_shot_flags(&self) -> &OneShotFlags {
+        &self.one_shot_flags
+    }
+
+    fn one_shot_flags_mut(&mut self) -> &mut OneShotFlags {
+        &mut self.one_shot_flags
+    }
+
+    fn poll(&mut self) {
+        while let Ok((generation, message)) = self.rx.try_recv() {
+            if let Message::Committed(result) = message {
+                self.commits_in_flight = self.commits_in_flight.saturating_sub(1);
+                self.update_uncommitted_flag();
+                if let Err(e) = result {
+                    warn!("PgBackend: commit failed: {e}");
+                    self.last_error = Some(e);
+                }
+                continue;
+            }
+            if generation != self.generation {
+                continue;
+            }
+            match message {
+                Message::Columns(columns) => {
+                    self.cache.remove_all_columns();
+                    for (idx, (name, udt_name)) in columns.into_iter().enumerate() {
+                        let ty = variant_ty(&udt_name);
+                        self.cache
+                            .insert_column(ColumnUid(idx as u32), name, ty, None);
+                        self.column_types.push(udt_name);
+                    }
+                    self.persistent_flags.column_info_present = true;
+                    self.one_shot_flags.column_info_updated = true;
+                }
+                Message::Rows(rows, is_last_page) => {
+                    if let Some((key, _)) = rows.last() {
+                        self.last_key = Some(key.clone());
+                    }
+                    for (key, values) in rows {
+                        let row_uid = self.cache.insert_row(values.into_iter().enumerate().map(
+                            |(idx, value)| {
+                                let ty = variant_ty(&self.column_types[idx]);
+                                let value = value
+                                    .map(|v| Variant::from_str(&v, ty))
+                                    .unwrap_or(Variant::Empty);
+                                (ColumnUid(idx as u32), value)
+                            },
+                        ));
+                        self.row_keys.insert(row_uid, key);
+                    }
+                    if is_last_page {
+                        self.persistent_flags.cells_loading = false;
+                        self.persistent_flags.have_all_cells = true;
+                        self.persistent_flags.row_set_present = true;
+                    }
+                    self.one_shot_flags.row_set_updated = true;
+                }
+                Message::PageFailed(offset, e) => {
+                    warn!("PgBackend: loading rows from {offset} failed: {e}");
+                    self.failed_pages.push(FetchError {
+                        rows: VisualRowIdx(offset)..VisualRowIdx(offset + self.page_size),
+                        message: e.clone(),
+                    });
+                    self.last_error = Some(e);
+                    self.persistent_flags.cells_loading = false;
+                }
+                Message::Error(e) => {
+                    warn!("PgBackend: {e}");
+                    self.last_error = Some(e);
+                    self.persistent_flags.cells_loading = false;
+                }
+                Message::Committed(_) => {}
+            }
+        }
+    }
+
+    fn available_columns(&self) -> impl Iterator<Item = ColumnUid> {
+        self.cache.available_columns()
+    }
+
+    fn used_columns(&self) -> impl Iterator<Item = ColumnUid> {
+        self.cache.used_columns()
+    }
+
+    fn column_info(&self, col_uid: ColumnUid) -> Option<&BackendColumn> {
+        self.cache.column_info(col_uid)
+    }
+
+    fn use_column(&mut self, col_uid: ColumnUid, is_used: bool) {
+        self.cache.use_column(col_uid, is_used);
+        self.one_shot_flags.column_info_updated = true;
+    }
+
+    fn row_count(&self) -> usize {
+        self.cache.row_count()
+    }
+
+    fn row_uid(&self, row_idx: VisualRowIdx) -> Option<RowUid> {
+        self.cache.row_uid(row_idx)
+    }
+
+    fn failed_fetches(&self) -> Vec<FetchError> {
+        self.failed_pages.clone()
+    }
+
+    fn retry_fetch(&mut self, rows: Range<VisualRowIdx>) {
+        let len = self.failed_pages.len();
+        self.failed_pages.retain(|f| f.rows != rows);
+        if self.failed_pages.len() == len {
+            return;
+        }
+        self.persistent_flags.cells_loading = true;
+        self.spawn_load_pages(rows.start.0);
+    }
+
+    fn get(&self, coord: CellCoord) -> Option<Variant> {
+        self.cache.get(coord)
+    }
+
+    #[cfg(feature = "gui")]
+    fn show_cell_view(&self, coord: CellCoord, ui: &mut Ui) {
+        self.cache.show_cell_view(coord, ui);
+    }
+
+    #[cfg(feature = "gui")]
+    fn show_cell_editor(&self, coord: CellCoord, ui: &mut Ui) -> Option<Response> {
+        self.cache.show_cell_editor(coord, ui)
+    }
+
+    fn commit_cell_edit(&mut self, coord: CellCoord) {
+        if self.persistent_flags.is_read_only {
+            return;
+        }
+        self.cache.commit_cell_edit(coord);
+        if self.commit_immediately {
+            self.spawn_update(coord);
+        } else if !self.uncommitted.contains(&coord) {
+            self.uncommitted.push(coord);
+        }
+        self.update_uncommitted_flag();
+    }
+}
@@ -0,0 +1,240 @@
+use crate::backend::{CellCoord, ColumnUid, RowUid};
+use rvariant::{Variant, VariantTy};
+use std::collections::VecDeque;
+
+/// Limits of the undo history, oldest entries are dropped when any of them is exceeded.
+#[derive(Clone, Debug)]
+pub struct UndoConfig {
+    /// Maximum number of undoable actions, 0 disables undo.
+    pub max_depth: usize,
+    /// Approximate maximum memory used by the history in bytes, None for no limit.
+    pub max_memory: Option<usize>,
+}
+
+impl Default for UndoConfig {
+    fn default() -> Self {
+        UndoConfig {
+            max_depth: 100,
+            max_memory: Some(16 * 1024 * 1024),
+        }
+    }
+}
+
+/// Value of one cell before and after a change, None if the cell was absent.
+#[derive(Clone, Debug)]
+pub struct CellChange {
+    pub coord: CellCoord,
+    pub before: Option<Variant>,
+    pub after: Option<Variant>,
+}
+
+/// Type of a column with the settings that are changed along with it.
+#[derive(Clone, Debug)]
+pub struct ColumnTypeState {
+    pub ty: VariantTy,
+    pub is_mixed: bool,
+    pub default: Option<Variant>,
+}
+
+/// Row removed with all its cells, see [UndoEntry::RowsRemoved].
+#[derive(Clone, Debug)]
+pub struct RemovedRow {
+    pub row_uid: RowUid,
+    /// Position in the backend row order before removal.
+    pub position: usize,
+    pub cells: Vec<(ColumnUid, Variant)>,
+    /// Columns of the cells that were edited by the user.
+    pub edited: Vec<ColumnUid>,
+    pub is_skipped: bool,
+    /// Position among the pinned rows, None if the row was not pinned.
+    pub pinned: Option<usize>,
+}
+
+/// Changes made by one user action, reverted and applied again as a whole.
+#[derive(Clone, Debug)]
+pub enum UndoEntry {
+    /// Values of a group of cells.
+    Cells(Vec<CellChange>),
+    /// Column converted to another type, with all its values.
+    ColumnType {
+        col_uid: ColumnUid,
+        before: ColumnTypeState,
+        after: ColumnTypeState,
+        cells: Vec<CellChange>,
+    },
+    /// Rows removed, in the order they were in before removal.
+    RowsRemoved(Vec<RemovedRow>),
+    /// Values of a computed column stored, see [TableBackend::materialize_column](crate::backend::TableBackend::materialize_column).
+    Materialized {
+        col_uid: ColumnUid,
+        cells: Vec<CellChange>,
+    },
+    /// Row moved between two positions in the backend row order.
+    RowMoved {
+        row_uid: RowUid,
+        from: usize,
+        to: usize,
+    },
+}
+
+/// Undo and redo stacks, each entry is a group of changes made by one user action.
+#[derive(Default)]
+pub struct UndoHistory {
+    config: UndoConfig,
+    undo: VecDeque<UndoEntry>,
+    redo: Vec<UndoEntry>,
+    memory_usage: usize,
+}
+
+impl UndoHistory {
+    pub fn new(config: UndoConfig) -> Self {
+        UndoHistory {
+            config,
+            ..Default::default()
+        }
+    }
+
+    pub fn config(&self) -> &UndoConfig {
+        &self.config
+    }
+
+    /// Change limits, dropping oldest entries if they are now exceeded.
+    pub fn set_config(&mut self, config: UndoConfig) {
+        self.config = config;
+        self.enforce_limits();
+    }
+
+    /// Record cell changes of an action, clears redo stack.
+    pub fn push(&mut self, changes: Vec<CellChange>) {
+        if changes.is_empty() {
+            return;
+        }
+        self.push_entry(UndoEntry::Cells(changes));
+    }
+
+    /// Record an action, clears redo stack.
+    pub fn push_entry(&mut self, entry: UndoEntry) {
+        for entry in self.redo.drain(..) {
+            self.memory_usage -= entry_size(&entry);
+        }
+        self.memory_usage += entry_size(&entry);
+        self.undo.push_back(entry);
+        self.enforce_limits();
+    }
+
+    /// Take the last action to be reverted, it is moved to the redo stack.
+    pub fn undo(&mut self) -> Option<&UndoEntry> {
+        let entry = self.undo.pop_back()?;
+        self.redo.push(entry);
+        self.redo.last()
+    }
+
+    /// Take the last reverted action to be applied again, it is moved back to the undo stack.
+    pub fn redo(&mut self) -> Option<&UndoEntry> {
+        let entry = self.redo.pop()?;
+        self.undo.push_back(entry);
+        self.undo.back()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Number of actions that can be undone.
+    pub fn undo_len(&self) -> usize {
+        self.undo.len()
+    }
+
+    /// Number of actions that can be redone.
+    pub fn redo_len(&self) -> usize {
+        self.redo.len()
+    }
+
+    /// Approximate memory used by both stacks in bytes.
+    pub fn memory_usage(&self) -> usize {
+        self.memory_usage
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.memory_usage = 0;
+    }
+
+    fn enforce_limits(&mut self) {
+        while self.undo.len() > self.config.max_depth
+            || self
+                .config
+                .max_memory
+                .map(|max| self.memory_usage > max)
+                .unwrap_or(false)
+        {
+            let Some(entry) = self.undo.pop_front() else {
+                // Only redo entries are left, drop them as well if memory is still exceeded
+                self.redo.clear();
+                self.memory_usage = 0;
+                break;
+            };
+            self.memory_usage -= entry_size(&entry);
+        }
+    }
+}
+
+fn entry_size(entry: &UndoEntry) -> usize {
+    size_of::<UndoEntry>()
+        + match entry {
+            UndoEntry::Cells(changes) => changes_size(changes),
+            UndoEntry::ColumnType {
+                before,
+                after,
+                cells,
+                ..
+            } => {
+                before.default.as_ref().map(variant_heap_size).unwrap_or(0)
+                    + after.default.as_ref().map(variant_heap_size).unwrap_or(0)
+                    + changes_size(cells)
+            }
+            UndoEntry::RowsRemoved(rows) => rows
+                .iter()
+                .map(|row| {
+                    size_of::<RemovedRow>()
+                        + row.edited.capacity() * size_of::<ColumnUid>()
+                        + row
+                            .cells
+                            .iter()
+                            .map(|(_, value)| {
+                                size_of::<(ColumnUid, Variant)>() + variant_heap_size(value)
+                            })
+                            .sum::<usize>()
+                })
+                .sum(),
+            UndoEntry::Materialized { cells, .. } => changes_size(cells),
+            UndoEntry::RowMoved { .. } => 0,
+        }
+}
+
+fn changes_size(changes: &[CellChange]) -> usize {
+    changes
+        .iter()
+        .map(|c| {
+            size_of::<CellChange>()
+                + c.before.as_ref().map(variant_heap_size).unwrap_or(0)
+                + c.after.as_ref().map(variant_heap_size).unwrap_or(0)
+        })
+        .sum()
+}
+
+fn variant_heap_size(value: &Variant) -> usize {
+    match value {
+        Variant::Str(s) => s.capacity(),
+        Variant::StrList(list) => list
+            .iter()
+            .map(|s| size_of::<String>() + s.capacity())
+            .sum(),
+        _ => 0,
+    }
+}
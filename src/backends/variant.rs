@@ -1,18 +1,49 @@
 use crate::backend::{
-    BackendColumn, CellCoord, ColumnUid, OneShotFlags, PersistentFlags, RowUid, TableBackend,
-    VisualRowIdx,
+    BackendColumn, CellCoord, ColumnOrigin, ColumnUid, OneShotFlags, PersistentFlags,
+    QueryableBackend, RowUid, TableBackend, TableEvent, VisualRowIdx,
 };
+use crate::backends::duplicates::DuplicateRows;
+use crate::backends::undo::{
+    CellChange, ColumnTypeState, RemovedRow, UndoConfig, UndoEntry, UndoHistory,
+};
+use crate::codegen::{struct_code, StructField};
+use crate::input_mask::InputMask;
+use crate::query::{FilterOp, QuerySpec};
+use crate::util::{row_hash, variant_to_string, variant_ty};
+use crate::validation::{ColumnValidator, RowRule};
+#[cfg(feature = "gui")]
+use egui::text::{CCursor, CCursorRange};
+#[cfg(feature = "gui")]
 use egui::{ComboBox, DragValue, Response, TextEdit, Ui, Widget};
 use rvariant::{Variant, VariantTy};
-use std::cell::Cell;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 
+/// Table kept in memory, with undo history, sorting and filtering. Without a table view showing it,
+/// reset its [OneShotFlags] after handling them, as every change is recorded there.
 pub struct VariantBackend {
     cell_data: HashMap<CellCoord, Variant>,
     row_order: Vec<RowUid>,
     next_row_uid: RowUid,
     columns: HashMap<ColumnUid, (BackendColumn, VariantColumn)>,
     cell_edit: Cell<Option<(CellCoord, Variant)>>,
+    /// Type chosen in the editor of a mixed column cell, the edited value is kept as text until committed.
+    cell_edit_ty: Cell<Option<VariantTy>>,
+    /// Cells modified by the user, as opposed to loaded or inserted programmatically.
+    edited_cells: HashSet<CellCoord>,
+    /// Rows that are shown, but excluded from extraction and export.
+    skipped_rows: HashSet<RowUid>,
+    /// Rows kept visible above the scrolled ones.
+    pinned_rows: Vec<RowUid>,
+    history: UndoHistory,
+    query: QuerySpec,
+    /// Rows matching the query in sorted order, None if not computed yet or invalidated by a change.
+    visible_rows: RefCell<Option<Vec<RowUid>>>,
+    /// Distinct non-empty strings of each column most frequent first, computed when suggestions
+    /// are first requested and dropped on any cell change.
+    distinct_values: RefCell<HashMap<ColumnUid, Vec<String>>>,
+    /// Checks of several cells of a row, in addition to column validators.
+    row_rules: Vec<RowRule>,
     persistent_flags: PersistentFlags,
     one_shot_flags: OneShotFlags,
 }
@@ -20,8 +51,53 @@ pub struct VariantBackend {
 struct VariantColumn {
     ty: VariantTy,
     default: Option<Variant>,
+    /// Unused columns are still shown, but excluded from extraction.
+    is_used: bool,
+    /// Name the column was created with, stays the same after renaming and is used to match columns on reload.
+    source_name: String,
+    is_renamable: bool,
+    /// Alternative names used by importers to match this column.
+    synonyms: Option<Vec<String>>,
+    /// Show distinct values already present in the column as suggestions in the string editor.
+    suggest_distinct: bool,
+    /// Rows with this cell empty or not converted to the column type are incomplete.
+    is_required: bool,
+    /// Pattern enforced while editing string cells.
+    mask: Option<InputMask>,
+    /// Left out of CSV export unless explicitly included, see [set_column_sensitive](VariantBackend::set_column_sensitive).
+    is_sensitive: bool,
+    /// Values are derived from other cells of the row instead of being stored.
+    compute: Option<ComputeFn>,
+    /// Values were computed once and then stored, see [materialize_column](TableBackend::materialize_column).
+    is_materialized: bool,
+    /// Computation of a materialized column, kept to undo the materialization.
+    materialized_from: Option<ComputeFn>,
+    /// Each cell keeps its own type, see [set_column_mixed](VariantBackend::set_column_mixed).
+    is_mixed: bool,
+    description: Option<String>,
+    /// Shown in the example row of an empty table instead of the default value.
+    example: Option<Variant>,
+    validators: Vec<ColumnValidator>,
 }
 
+/// Computes a cell value from other cells of the same row, given a getter of stored values by column.
+type ComputeFn = Box<dyn Fn(&dyn Fn(ColumnUid) -> Option<Variant>) -> Option<Variant> + Send>;
+
+/// Types a column can be converted to from the UI.
+const CONVERTIBLE_TYPES: &[VariantTy] = &[
+    VariantTy::Str,
+    VariantTy::U32,
+    VariantTy::U64,
+    VariantTy::I32,
+    VariantTy::I64,
+    VariantTy::F32,
+    VariantTy::F64,
+    VariantTy::Bool,
+];
+
+/// Maximum number of suggestions shown above the string editor.
+const MAX_SUGGESTIONS: usize = 8;
+
 impl VariantBackend {
     pub fn new(columns: impl IntoIterator<Item = (String, VariantTy, Option<Variant>)>) -> Self {
         VariantBackend {
@@ -33,16 +109,43 @@ impl VariantBackend {
                 .enumerate()
                 .map(|(idx, (name, ty, default))| {
                     let col_uid = ColumnUid(idx as u32);
+                    let variant_column = VariantColumn {
+                        ty,
+                        default,
+                        is_used: true,
+                        source_name: name.clone(),
+                        is_renamable: true,
+                        synonyms: None,
+                        suggest_distinct: false,
+                        is_required: false,
+                        mask: None,
+                        is_sensitive: false,
+                        compute: None,
+                        is_materialized: false,
+                        materialized_from: None,
+                        is_mixed: false,
+                        description: None,
+                        example: None,
+                        validators: vec![],
+                    };
                     let backend_column = BackendColumn {
                         name,
                         ty: format!("{ty}"),
                         is_sortable: true,
                     };
-                    let variant_column = VariantColumn { ty: ty, default };
                     (col_uid, (backend_column, variant_column))
                 })
                 .collect(),
             cell_edit: Cell::new(None),
+            cell_edit_ty: Cell::new(None),
+            edited_cells: HashSet::new(),
+            skipped_rows: HashSet::new(),
+            pinned_rows: Vec::new(),
+            history: UndoHistory::default(),
+            query: QuerySpec::default(),
+            visible_rows: RefCell::new(None),
+            distinct_values: RefCell::new(HashMap::new()),
+            row_rules: Vec::new(),
             persistent_flags: PersistentFlags {
                 is_read_only: false,
                 column_info_present: true,
@@ -56,7 +159,7 @@ impl VariantBackend {
         }
     }
 
-    pub fn insert_row(&mut self, values: impl IntoIterator<Item = (ColumnUid, Variant)>) {
+    pub fn insert_row(&mut self, values: impl IntoIterator<Item = (ColumnUid, Variant)>) -> RowUid {
         let mut provided_cells = vec![];
         for (col_uid, v) in values {
             let coord = CellCoord {
@@ -77,8 +180,14 @@ impl VariantBackend {
                 }
             }
         }
-        self.row_order.push(self.next_row_uid);
-        self.next_row_uid = RowUid(self.next_row_uid.0 + 1)
+        let row_uid = self.next_row_uid;
+        self.row_order.push(row_uid);
+        self.visible_rows.get_mut().take();
+        self.distinct_values.get_mut().clear();
+        self.next_row_uid = RowUid(row_uid.0 + 1);
+        self.one_shot_flags
+            .push_event(TableEvent::RowsInserted(vec![row_uid]));
+        row_uid
     }
 
     /// Remove all columns and all data
@@ -95,23 +204,855 @@ impl VariantBackend {
         ty: VariantTy,
         default: Option<Variant>,
     ) {
+        let variant_column = VariantColumn {
+            ty,
+            default,
+            is_used: true,
+            source_name: name.clone(),
+            is_renamable: true,
+            synonyms: None,
+            suggest_distinct: false,
+            is_required: false,
+            mask: None,
+            is_sensitive: false,
+            compute: None,
+            is_materialized: false,
+            materialized_from: None,
+            is_mixed: false,
+            description: None,
+            example: None,
+            validators: vec![],
+        };
         let backend_column = BackendColumn {
             name,
             ty: format!("{ty}"),
             is_sortable: true,
         };
-        let variant_column = VariantColumn { ty: ty, default };
         self.columns
             .insert(col_uid, (backend_column, variant_column));
         self.one_shot_flags.column_info_updated = true;
     }
+
+    /// Add a column with values derived from other cells of the row, e.g. a total from price and quantity.
+    /// Computed cells can't be edited and are recomputed each time they are shown, sorted or extracted,
+    /// see [materialize_column](TableBackend::materialize_column) to store them.
+    pub fn insert_computed_column(
+        &mut self,
+        col_uid: ColumnUid,
+        name: String,
+        ty: VariantTy,
+        compute: impl Fn(&dyn Fn(ColumnUid) -> Option<Variant>) -> Option<Variant> + Send + 'static,
+    ) {
+        self.insert_column(col_uid, name, ty, None);
+        if let Some((_, c)) = self.columns.get_mut(&col_uid) {
+            c.compute = Some(Box::new(compute));
+        }
+    }
+
+    /// Add a computed column with a [row_hash](crate::util::row_hash) of the given columns, in that order,
+    /// to find rows changed between exported revisions of the same data set.
+    pub fn insert_row_hash_column(
+        &mut self,
+        col_uid: ColumnUid,
+        name: String,
+        columns: Vec<ColumnUid>,
+    ) {
+        self.insert_computed_column(col_uid, name, VariantTy::Str, move |get| {
+            Some(Variant::Str(row_hash(columns.iter().map(|c| get(*c)))))
+        });
+    }
+
+    /// Value of a computed cell, None if the column is not computed.
+    fn compute(&self, coord: CellCoord) -> Option<Variant> {
+        let (_, column) = self.columns.get(&coord.col_uid)?;
+        let compute = column.compute.as_ref()?;
+        compute(&|col_uid| {
+            let coord = CellCoord {
+                row_uid: coord.row_uid,
+                col_uid,
+            };
+            self.cell_data.get(&coord).cloned()
+        })
+    }
+
+    fn is_computed(&self, col_uid: ColumnUid) -> bool {
+        self.columns
+            .get(&col_uid)
+            .is_some_and(|(_, c)| c.compute.is_some())
+    }
+
+    /// Replace all columns and rows with the ones from `staging`, keeping the uids of rows that are still present.
+    /// `matched_rows` maps staging row uids to row uids in self, cells edited by the user are preserved
+    /// in matched rows, if a column with the same source name still exists. Columns marked as unused stay unused,
+    /// renamed columns keep their new names and validators.
+    /// Returns a map from staging row uids to row uids in self.
+    pub fn merge_reloaded(
+        &mut self,
+        mut staging: VariantBackend,
+        matched_rows: &HashMap<RowUid, RowUid>,
+    ) -> HashMap<RowUid, RowUid> {
+        let mut validators: HashMap<String, Vec<ColumnValidator>> = self
+            .columns
+            .values_mut()
+            .filter(|(_, c)| !c.validators.is_empty())
+            .map(|(_, c)| (c.source_name.clone(), std::mem::take(&mut c.validators)))
+            .collect();
+        let old_col_by_source_name: HashMap<&str, (&BackendColumn, &VariantColumn)> = self
+            .columns
+            .values()
+            .map(|(b, c)| (c.source_name.as_str(), (b, c)))
+            .collect();
+        for (backend_column, variant_column) in staging.columns.values_mut() {
+            if let Some((old_backend_column, old_column)) =
+                old_col_by_source_name.get(variant_column.source_name.as_str())
+            {
+                variant_column.is_used = old_column.is_used;
+                if variant_column.validators.is_empty() {
+                    variant_column.validators = validators
+                        .remove(&variant_column.source_name)
+                        .unwrap_or_default();
+                }
+                if variant_column.is_renamable {
+                    backend_column.name = old_backend_column.name.clone();
+                }
+            }
+        }
+        let new_col_by_source_name: HashMap<&str, ColumnUid> = staging
+            .columns
+            .iter()
+            .map(|(col_uid, (_, c))| (c.source_name.as_str(), *col_uid))
+            .collect();
+        let mut row_map = HashMap::new();
+        let mut row_order = Vec::with_capacity(staging.row_order.len());
+        for staged_uid in &staging.row_order {
+            let row_uid = match matched_rows.get(staged_uid) {
+                Some(row_uid) => *row_uid,
+                None => {
+                    let row_uid = self.next_row_uid;
+                    self.next_row_uid = RowUid(row_uid.0 + 1);
+                    row_uid
+                }
+            };
+            row_map.insert(*staged_uid, row_uid);
+            row_order.push(row_uid);
+        }
+
+        let mut cell_data: HashMap<CellCoord, Variant> = staging
+            .cell_data
+            .into_iter()
+            .filter_map(|(coord, value)| {
+                let row_uid = *row_map.get(&coord.row_uid)?;
+                Some((
+                    CellCoord {
+                        row_uid,
+                        col_uid: coord.col_uid,
+                    },
+                    value,
+                ))
+            })
+            .collect();
+        let matched: HashSet<RowUid> = matched_rows.values().copied().collect();
+        let mut edited_cells = HashSet::new();
+        for coord in self.edited_cells.drain() {
+            if !matched.contains(&coord.row_uid) {
+                continue;
+            }
+            let Some((_, old_column)) = self.columns.get(&coord.col_uid) else {
+                continue;
+            };
+            let Some(col_uid) = new_col_by_source_name
+                .get(old_column.source_name.as_str())
+                .copied()
+            else {
+                continue;
+            };
+            let Some(value) = self.cell_data.remove(&coord) else {
+                continue;
+            };
+            let new_coord = CellCoord {
+                row_uid: coord.row_uid,
+                col_uid,
+            };
+            cell_data.insert(new_coord, value);
+            edited_cells.insert(new_coord);
+        }
+
+        self.columns = staging.columns;
+        self.cell_data = cell_data;
+        self.row_order = row_order;
+        self.visible_rows.get_mut().take();
+        self.distinct_values.get_mut().clear();
+        self.edited_cells = edited_cells;
+        self.skipped_rows
+            .retain(|row_uid| matched.contains(row_uid));
+        self.pinned_rows.retain(|row_uid| matched.contains(row_uid));
+        self.history.clear();
+        self.cell_edit.set(None);
+        self.one_shot_flags.column_info_updated = true;
+        self.one_shot_flags.row_set_updated = true;
+        // Matched rows keep their uids, others are gone
+        self.one_shot_flags.rows_remapped =
+            Some(matched.iter().map(|row_uid| (*row_uid, *row_uid)).collect());
+        self.one_shot_flags.push_event(TableEvent::Reloaded);
+        row_map
+    }
+
+    /// Allow or forbid renaming column from the UI, all columns can be renamed by default.
+    pub fn set_column_renamable(&mut self, col_uid: ColumnUid, is_renamable: bool) {
+        if let Some((_, c)) = self.columns.get_mut(&col_uid) {
+            c.is_renamable = is_renamable;
+        }
+    }
+
+    /// Show values already present in a string column as clickable suggestions while editing it,
+    /// most frequent first. Useful for categorical data without defining an enum.
+    pub fn set_column_suggestions(&mut self, col_uid: ColumnUid, enabled: bool) {
+        if let Some((_, c)) = self.columns.get_mut(&col_uid) {
+            c.suggest_distinct = enabled;
+        }
+    }
+
+    /// Mark column as required, rows with its cells empty or not converted to the column type are reported
+    /// by [incomplete_rows](TableBackend::incomplete_rows) and prevent [extract](Self::extract).
+    pub fn set_column_required(&mut self, col_uid: ColumnUid, is_required: bool) {
+        if let Some((_, c)) = self.columns.get_mut(&col_uid) {
+            c.is_required = is_required;
+        }
+    }
+
+    /// Enforce a pattern in the string cell editor, characters not fitting it are rejected while typing.
+    /// Required cells not filling the whole mask make the row incomplete.
+    pub fn set_column_mask(&mut self, col_uid: ColumnUid, mask: Option<InputMask>) {
+        if let Some((_, c)) = self.columns.get_mut(&col_uid) {
+            c.mask = mask;
+        }
+    }
+
+    /// Mark column as holding secrets or personal data, it is left out of [export_csv](crate::util::export_csv)
+    /// unless [include_sensitive](crate::util::CsvExportConfig::include_sensitive) is set.
+    /// Mask its values in the view with [sensitive_columns](crate::table_view::TableViewConfig::sensitive_columns).
+    pub fn set_column_sensitive(&mut self, col_uid: ColumnUid, is_sensitive: bool) {
+        if let Some((_, c)) = self.columns.get_mut(&col_uid) {
+            c.is_sensitive = is_sensitive;
+        }
+    }
+
+    /// Current type of a column, None if there is no such column.
+    pub fn column_ty(&self, col_uid: ColumnUid) -> Option<VariantTy> {
+        self.columns.get(&col_uid).map(|(_, c)| c.ty)
+    }
+
+    pub fn is_column_sensitive(&self, col_uid: ColumnUid) -> bool {
+        self.columns
+            .get(&col_uid)
+            .map(|(_, c)| c.is_sensitive)
+            .unwrap_or(false)
+    }
+
+    /// Explain what the column is for and give an example value, shown in the example row of an empty table.
+    pub fn set_column_hint(
+        &mut self,
+        col_uid: ColumnUid,
+        description: Option<String>,
+        example: Option<Variant>,
+    ) {
+        if let Some((_, c)) = self.columns.get_mut(&col_uid) {
+            c.description = description;
+            c.example = example;
+        }
+    }
+
+    /// Check values of the column, invalid cells are tinted and listed below the table, see [TableBackend::cell_error].
+    pub fn add_column_validator(&mut self, col_uid: ColumnUid, validator: ColumnValidator) {
+        if let Some((_, c)) = self.columns.get_mut(&col_uid) {
+            c.validators.push(validator);
+            self.one_shot_flags.column_info_updated = true;
+        }
+    }
+
+    pub fn clear_column_validators(&mut self, col_uid: ColumnUid) {
+        if let Some((_, c)) = self.columns.get_mut(&col_uid) {
+            c.validators.clear();
+            self.one_shot_flags.column_info_updated = true;
+        }
+    }
+
+    /// Check cells of several columns together, e.g. that one column is not greater than another,
+    /// all cells of a failing rule are marked as invalid.
+    pub fn add_row_rule(&mut self, rule: RowRule) {
+        self.row_rules.push(rule);
+        self.one_shot_flags.column_info_updated = true;
+    }
+
+    pub fn clear_row_rules(&mut self) {
+        self.row_rules.clear();
+        self.one_shot_flags.column_info_updated = true;
+    }
+
+    /// Error of the first failing row rule that involves the cell.
+    fn row_rule_error(&self, coord: CellCoord) -> Option<String> {
+        self.row_rules.iter().find_map(|rule| {
+            let columns = rule.columns();
+            if !columns.contains(&coord.col_uid) {
+                return None;
+            }
+            let values: Vec<Variant> = columns
+                .iter()
+                .map(|col_uid| {
+                    self.get(CellCoord {
+                        row_uid: coord.row_uid,
+                        col_uid: *col_uid,
+                    })
+                    .unwrap_or(Variant::Empty)
+                })
+                .collect();
+            let column_name = |col_uid: ColumnUid| {
+                self.columns
+                    .get(&col_uid)
+                    .map(|(b, _)| b.name.clone())
+                    .unwrap_or_default()
+            };
+            rule.check(&values, column_name).err()
+        })
+    }
+
+    /// Let each cell of the column keep its own type, e.g. the value column of key/value data.
+    /// Cells show a type badge, are sorted by type and then by value, and the editor has a type choice.
+    /// Pasted text is kept as is. Changing the column type turns this off.
+    pub fn set_column_mixed(&mut self, col_uid: ColumnUid, is_mixed: bool) {
+        if let Some((b, c)) = self.columns.get_mut(&col_uid) {
+            c.is_mixed = is_mixed;
+            b.ty = if is_mixed {
+                "Any".to_string()
+            } else {
+                format!("{}", c.ty)
+            };
+            self.visible_rows.get_mut().take();
+            self.one_shot_flags.column_info_updated = true;
+        }
+    }
+
+    fn is_mixed(&self, col_uid: ColumnUid) -> bool {
+        self.columns.get(&col_uid).is_some_and(|(_, c)| c.is_mixed)
+    }
+
+    /// Type choice next to a text field, the value is converted to the chosen type on commit.
+    #[cfg(feature = "gui")]
+    fn show_mixed_cell_editor(&self, coord: CellCoord, ui: &mut Ui) -> Option<Response> {
+        let (mut text, mut ty) = match self.cell_edit.take() {
+            Some((edited_coord, Variant::Str(text))) if edited_coord == coord => {
+                (text, self.cell_edit_ty.get().unwrap_or(VariantTy::Str))
+            }
+            _ => {
+                let value = self.cell_data.get(&coord);
+                (
+                    value.map(variant_to_string).unwrap_or_default(),
+                    value.and_then(variant_ty).unwrap_or(VariantTy::Str),
+                )
+            }
+        };
+        let resp = ui
+            .horizontal(|ui| {
+                ComboBox::from_id_salt("_egui_tabular_mixed_ty")
+                    .selected_text(ty.to_string())
+                    .width(48.)
+                    .show_ui(ui, |ui| {
+                        for choice in CONVERTIBLE_TYPES {
+                            ui.selectable_value(&mut ty, *choice, choice.to_string());
+                        }
+                    });
+                TextEdit::singleline(&mut text)
+                    .desired_width(f32::INFINITY)
+                    .ui(ui)
+            })
+            .inner;
+        self.cell_edit.set(Some((coord, Variant::Str(text))));
+        self.cell_edit_ty.set(Some(ty));
+        Some(resp)
+    }
+
+    /// Whether all required cells of the row are filled with values of the column type.
+    fn is_row_complete(&self, row_uid: RowUid) -> bool {
+        self.columns
+            .iter()
+            .filter(|(_, (_, c))| c.is_required && c.is_used)
+            .all(|(col_uid, (_, c))| {
+                let coord = CellCoord {
+                    row_uid,
+                    col_uid: *col_uid,
+                };
+                self.is_cell_complete(coord, c)
+            })
+    }
+
+    /// Cell is non-empty and, unless the column is of string type, was converted to it.
+    /// Computed cells are checked by their computed value.
+    fn is_cell_complete(&self, coord: CellCoord, c: &VariantColumn) -> bool {
+        let computed = self.compute(coord);
+        match computed.as_ref().or_else(|| self.cell_data.get(&coord)) {
+            None | Some(Variant::Empty) => false,
+            Some(Variant::Str(s)) => {
+                (c.is_mixed || matches!(c.ty, VariantTy::Str | VariantTy::StrList))
+                    && !s.trim().is_empty()
+                    && c.mask.as_ref().map(|m| m.is_complete(s)).unwrap_or(true)
+            }
+            Some(_) => true,
+        }
+    }
+
+    /// Number of empty or not converted cells in used required columns of not skipped rows,
+    /// see [set_column_required](Self::set_column_required).
+    pub fn incomplete_cell_count(&self) -> usize {
+        let required: Vec<(ColumnUid, &VariantColumn)> = self
+            .columns
+            .iter()
+            .filter(|(_, (_, c))| c.is_required && c.is_used)
+            .map(|(col_uid, (_, c))| (*col_uid, c))
+            .collect();
+        if required.is_empty() {
+            return 0;
+        }
+        self.un_skipped_rows()
+            .into_iter()
+            .map(|row_uid| {
+                required
+                    .iter()
+                    .filter(|(col_uid, c)| {
+                        let coord = CellCoord {
+                            row_uid,
+                            col_uid: *col_uid,
+                        };
+                        !self.is_cell_complete(coord, c)
+                    })
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Convert all cells and the default value of a column to another type through their string representation.
+    /// Cells that could not be converted are kept as strings and returned. The conversion is undone as one action.
+    pub fn turn_column_into(&mut self, col_uid: ColumnUid, ty: VariantTy) -> Vec<CellCoord> {
+        let Some((_, column)) = self.columns.get(&col_uid) else {
+            return vec![];
+        };
+        let before = ColumnTypeState {
+            ty: column.ty,
+            is_mixed: column.is_mixed,
+            default: column.default.clone(),
+        };
+        let after = ColumnTypeState {
+            ty,
+            is_mixed: false,
+            default: column
+                .default
+                .as_ref()
+                .map(|v| Variant::from_str(&variant_to_string(v), ty)),
+        };
+        let mut failed = vec![];
+        let mut cells = vec![];
+        for row_uid in &self.row_order {
+            let coord = CellCoord {
+                row_uid: *row_uid,
+                col_uid,
+            };
+            let Some(value) = self.cell_data.get(&coord) else {
+                continue;
+            };
+            let text = variant_to_string(value);
+            let converted = Variant::from_str(&text, ty);
+            let is_failed = ty != VariantTy::Str
+                && !text.trim().is_empty()
+                && matches!(converted, Variant::Empty | Variant::Str(_));
+            let converted = if is_failed {
+                failed.push(coord);
+                Variant::Str(text)
+            } else {
+                converted
+            };
+            if *value != converted {
+                cells.push(CellChange {
+                    coord,
+                    before: Some(value.clone()),
+                    after: Some(converted),
+                });
+            }
+        }
+        let values = cells.iter().map(|c| (c.coord, c.after.clone())).collect();
+        self.set_column_type_state(col_uid, &after, values);
+        self.history.push_entry(UndoEntry::ColumnType {
+            col_uid,
+            before,
+            after,
+            cells,
+        });
+        failed
+    }
+
+    /// Remove rows with all their cells, without recording history.
+    fn remove_rows_unrecorded(&mut self, row_uids: &HashSet<RowUid>) {
+        self.row_order.retain(|row_uid| !row_uids.contains(row_uid));
+        self.cell_data
+            .retain(|coord, _| !row_uids.contains(&coord.row_uid));
+        self.edited_cells
+            .retain(|coord| !row_uids.contains(&coord.row_uid));
+        self.skipped_rows
+            .retain(|row_uid| !row_uids.contains(row_uid));
+        self.pinned_rows
+            .retain(|row_uid| !row_uids.contains(row_uid));
+        self.visible_rows.get_mut().take();
+        self.distinct_values.get_mut().clear();
+        self.one_shot_flags.row_set_updated = true;
+        self.one_shot_flags
+            .push_event(TableEvent::RowsRemoved(row_uids.iter().copied().collect()));
+    }
+
+    /// Switch a column between computed and materialized, storing or restoring its values,
+    /// without recording history.
+    fn set_materialized(
+        &mut self,
+        col_uid: ColumnUid,
+        is_materialized: bool,
+        values: Vec<(CellCoord, Option<Variant>)>,
+    ) {
+        let Some((_, c)) = self.columns.get_mut(&col_uid) else {
+            return;
+        };
+        if is_materialized {
+            c.materialized_from = c.compute.take();
+        } else {
+            c.compute = c.materialized_from.take();
+        }
+        c.is_materialized = is_materialized;
+        self.write_cells(values);
+        self.one_shot_flags.column_info_updated = true;
+    }
+
+    /// Write or remove stored cell values, without marking them as edited or recording history.
+    fn write_cells(&mut self, values: Vec<(CellCoord, Option<Variant>)>) {
+        let coords = values.iter().map(|(coord, _)| *coord).collect();
+        for (coord, value) in values {
+            match value {
+                Some(value) => {
+                    self.cell_data.insert(coord, value);
+                }
+                None => {
+                    self.cell_data.remove(&coord);
+                }
+            }
+        }
+        self.cells_changed(coords);
+    }
+
+    /// Move a row within the backend order, without recording history.
+    fn reposition_row(&mut self, from: usize, to: usize) {
+        let row_uid = self.row_order.remove(from);
+        self.row_order.insert(to, row_uid);
+        self.visible_rows.get_mut().take();
+        self.one_shot_flags.visible_row_vec_updated = true;
+    }
+
+    /// Put removed rows back at their previous positions.
+    fn restore_rows(&mut self, rows: Vec<RemovedRow>) {
+        let mut pinned = vec![];
+        let row_uids = rows.iter().map(|row| row.row_uid).collect();
+        // Rows are in the removal order, so each position is valid once the previous rows are back
+        for row in rows {
+            let position = row.position.min(self.row_order.len());
+            self.row_order.insert(position, row.row_uid);
+            for (col_uid, value) in row.cells {
+                self.cell_data.insert(
+                    CellCoord {
+                        row_uid: row.row_uid,
+                        col_uid,
+                    },
+                    value,
+                );
+            }
+            self.edited_cells
+                .extend(row.edited.into_iter().map(|col_uid| CellCoord {
+                    row_uid: row.row_uid,
+                    col_uid,
+                }));
+            if row.is_skipped {
+                self.skipped_rows.insert(row.row_uid);
+            }
+            if let Some(idx) = row.pinned {
+                pinned.push((idx, row.row_uid));
+            }
+        }
+        pinned.sort_by_key(|(idx, _)| *idx);
+        for (idx, row_uid) in pinned {
+            let idx = idx.min(self.pinned_rows.len());
+            self.pinned_rows.insert(idx, row_uid);
+        }
+        self.cell_edit.set(None);
+        self.visible_rows.get_mut().take();
+        self.distinct_values.get_mut().clear();
+        self.one_shot_flags.row_set_updated = true;
+        self.one_shot_flags
+            .push_event(TableEvent::RowsInserted(row_uids));
+    }
+
+    /// Set type of a column along with its settings and converted values, without recording history.
+    fn set_column_type_state(
+        &mut self,
+        col_uid: ColumnUid,
+        state: &ColumnTypeState,
+        values: Vec<(CellCoord, Option<Variant>)>,
+    ) {
+        let Some((backend_column, column)) = self.columns.get_mut(&col_uid) else {
+            return;
+        };
+        column.ty = state.ty;
+        column.is_mixed = state.is_mixed;
+        column.default = state.default.clone();
+        backend_column.ty = if state.is_mixed {
+            "Any".to_string()
+        } else {
+            format!("{}", state.ty)
+        };
+        self.write_cells(values);
+        self.one_shot_flags.column_info_updated = true;
+        self.one_shot_flags
+            .push_event(TableEvent::ColumnTypeChanged {
+                col_uid,
+                ty: state.ty,
+            });
+    }
+
+    /// Distinct non-empty strings in a column containing `filter` (ignoring case), most frequent first,
+    /// as suggested in the string editor. Values of the whole column are counted once and reused
+    /// until cells change.
+    pub fn distinct_values(&self, col_uid: ColumnUid, filter: &str) -> Vec<String> {
+        let filter = filter.to_lowercase();
+        let mut distinct_values = self.distinct_values.borrow_mut();
+        distinct_values
+            .entry(col_uid)
+            .or_insert_with(|| self.count_distinct_values(col_uid))
+            .iter()
+            .filter(|value| value.to_lowercase().contains(&filter))
+            .take(MAX_SUGGESTIONS)
+            .cloned()
+            .collect()
+    }
+
+    fn count_distinct_values(&self, col_uid: ColumnUid) -> Vec<String> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for row_uid in &self.row_order {
+            if let Some(Variant::Str(value)) = self.cell_data.get(&CellCoord {
+                row_uid: *row_uid,
+                col_uid,
+            }) {
+                if !value.is_empty() {
+                    *counts.entry(value.as_str()).or_default() += 1;
+                }
+            }
+        }
+        let mut values: Vec<_> = counts.into_iter().collect();
+        values.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+        values
+            .into_iter()
+            .map(|(value, _)| value.to_string())
+            .collect()
+    }
+
+    /// Returns (source name, current name) pairs of all the columns that were renamed.
+    pub fn renamed_columns(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.columns
+            .values()
+            .filter(|(b, c)| b.name != c.source_name)
+            .map(|(b, c)| (c.source_name.as_str(), b.name.as_str()))
+    }
+
+    /// Limit undo history depth and memory usage.
+    pub fn set_undo_config(&mut self, config: UndoConfig) {
+        self.history.set_config(config);
+    }
+
+    pub fn undo_history(&self) -> &UndoHistory {
+        &self.history
+    }
+
+    pub fn clear_undo_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// Report changed cells through [cells_updated](OneShotFlags::cells_updated) and a
+    /// [CellsChanged](TableEvent::CellsChanged) event, sorted rows are recomputed as the values might be sorted on.
+    fn cells_changed(&mut self, coords: Vec<CellCoord>) {
+        self.visible_rows.get_mut().take();
+        self.distinct_values.get_mut().clear();
+        self.one_shot_flags
+            .cells_updated
+            .extend(coords.iter().copied());
+        self.one_shot_flags
+            .push_event(TableEvent::CellsChanged(coords));
+    }
+
+    /// Set a cell value from code, without adding it to the undo history or marking the cell as edited.
+    pub fn set(&mut self, coord: CellCoord, value: Variant) {
+        self.cell_data.insert(coord, value);
+        self.cells_changed(vec![coord]);
+    }
+
+    fn apply_changes(&mut self, changes: Vec<(CellCoord, Option<Variant>)>) {
+        self.cells_changed(changes.iter().map(|(coord, _)| *coord).collect());
+        for (coord, value) in changes {
+            match value {
+                Some(value) => {
+                    self.cell_data.insert(coord, value);
+                }
+                None => {
+                    self.cell_data.remove(&coord);
+                }
+            }
+            self.edited_cells.insert(coord);
+        }
+        self.cell_edit.set(None);
+        self.visible_rows.get_mut().take();
+    }
+
+    /// Run `f` on the rows matching the query, in sorted order.
+    fn with_visible_rows<R>(&self, f: impl FnOnce(&[RowUid]) -> R) -> R {
+        if self.query.is_empty() {
+            return f(&self.row_order);
+        }
+        let mut visible_rows = self.visible_rows.borrow_mut();
+        let rows = visible_rows.get_or_insert_with(|| {
+            self.query.apply_with_mixed(
+                &self.row_order,
+                |coord| self.get(coord),
+                |col_uid| self.is_mixed(col_uid),
+            )
+        });
+        f(rows)
+    }
+
+    #[deprecated(note = "extract now fails on incomplete rows as well")]
+    #[allow(clippy::type_complexity)]
+    pub fn try_extract(&self) -> Result<Vec<(RowUid, Vec<(ColumnUid, Variant)>)>, Vec<RowUid>> {
+        self.extract()
+    }
+
+    /// Rows with the same values in `columns` as an earlier row, in the order rows were inserted.
+    /// All used columns are compared if `columns` is empty, skipped rows are included and rows with
+    /// all compared cells empty are not.
+    pub fn duplicate_rows(&self, columns: &[ColumnUid]) -> Vec<RowUid> {
+        self.find_duplicates(columns).rows()
+    }
+
+    /// Same as [duplicate_rows](Self::duplicate_rows), but can be kept up to date with
+    /// [update_duplicates](Self::update_duplicates) after cells are edited.
+    pub fn find_duplicates(&self, columns: &[ColumnUid]) -> DuplicateRows {
+        let mut columns = columns.to_vec();
+        if columns.is_empty() {
+            columns = self.used_columns().collect();
+            columns.sort();
+        }
+        let mut duplicates = DuplicateRows::new(columns);
+        self.update_duplicates(&mut duplicates, self.row_order.iter().copied());
+        duplicates
+    }
+
+    /// Compare edited rows again, returns rows that became or stopped being duplicates.
+    /// Rows must still exist, find all duplicates again after rows are inserted or removed.
+    pub fn update_duplicates(
+        &self,
+        duplicates: &mut DuplicateRows,
+        rows: impl IntoIterator<Item = RowUid>,
+    ) -> Vec<RowUid> {
+        let keys: Vec<(RowUid, Option<Vec<String>>)> = rows
+            .into_iter()
+            .map(|row_uid| {
+                let key: Vec<String> = duplicates
+                    .columns
+                    .iter()
+                    .map(|col_uid| {
+                        self.get(CellCoord {
+                            row_uid,
+                            col_uid: *col_uid,
+                        })
+                        .map(|v| variant_to_string(&v))
+                        .unwrap_or_default()
+                    })
+                    .collect();
+                let is_empty = key.iter().all(|v| v.trim().is_empty());
+                (row_uid, (!is_empty).then_some(key))
+            })
+            .collect();
+        duplicates.update(keys)
+    }
+
+    /// Rust struct with a field per used column, along with its [FromVariantRow](crate::codegen::FromVariantRow)
+    /// implementation, so that [extract_as](Self::extract_as) returns typed rows. Fields of required columns are
+    /// not optional, mixed columns are kept as [Variant].
+    pub fn struct_code(&self, struct_name: &str) -> String {
+        let mut fields: Vec<StructField> = self
+            .columns
+            .iter()
+            .filter(|(_, (_, c))| c.is_used)
+            .map(|(col_uid, (b, c))| StructField {
+                col_uid: *col_uid,
+                name: b.name.clone(),
+                ty: if c.is_mixed { VariantTy::Empty } else { c.ty },
+                is_required: c.is_required,
+            })
+            .collect();
+        fields.sort_by_key(|f| f.col_uid);
+        struct_code(struct_name, fields)
+    }
+
+    /// Returns [un_skipped_rows](TableBackend::un_skipped_rows) in the order they are shown, with values
+    /// of used columns only, sorted by column uid. Absent cells are returned as [Variant::Empty].
+    /// Fails with the incomplete rows if there are any, see [set_column_required](Self::set_column_required).
+    #[allow(clippy::type_complexity)]
+    pub fn extract(&self) -> Result<Vec<(RowUid, Vec<(ColumnUid, Variant)>)>, Vec<RowUid>> {
+        let incomplete = self.incomplete_rows();
+        if incomplete.is_empty() {
+            Ok(self.extract_unchecked())
+        } else {
+            Err(incomplete)
+        }
+    }
+
+    /// Same as [extract](Self::extract), with incomplete rows included as they are.
+    pub fn extract_unchecked(&self) -> Vec<(RowUid, Vec<(ColumnUid, Variant)>)> {
+        let mut used_columns: Vec<ColumnUid> = self.used_columns().collect();
+        used_columns.sort();
+        self.un_skipped_rows()
+            .iter()
+            .map(|row_uid| {
+                let values = used_columns
+                    .iter()
+                    .map(|col_uid| {
+                        let coord = CellCoord {
+                            row_uid: *row_uid,
+                            col_uid: *col_uid,
+                        };
+                        let value = self.get(coord).unwrap_or(Variant::Empty);
+                        (*col_uid, value)
+                    })
+                    .collect();
+                (*row_uid, values)
+            })
+            .collect()
+    }
 }
 
 impl TableBackend for VariantBackend {
     fn clear(&mut self) {
         self.cell_data.clear();
+        self.edited_cells.clear();
+        self.skipped_rows.clear();
+        self.pinned_rows.clear();
+        self.history.clear();
         self.row_order.clear();
+        self.visible_rows.get_mut().take();
+        self.distinct_values.get_mut().clear();
+        // Row uids are reused from now on
         self.next_row_uid = RowUid(0);
+        self.one_shot_flags.cleared = true;
+        self.one_shot_flags.push_event(TableEvent::Cleared);
     }
 
     fn persistent_flags(&self) -> &PersistentFlags {
@@ -131,59 +1072,238 @@ impl TableBackend for VariantBackend {
     }
 
     fn used_columns(&self) -> impl Iterator<Item = ColumnUid> {
-        self.columns.keys().copied()
+        self.columns
+            .iter()
+            .filter(|(_, (_, c))| c.is_used)
+            .map(|(col_uid, _)| *col_uid)
     }
 
     fn column_info(&self, col_uid: ColumnUid) -> Option<&BackendColumn> {
         self.columns.get(&col_uid).map(|(b, _)| b)
     }
 
+    fn use_column(&mut self, col_uid: ColumnUid, is_used: bool) {
+        if let Some((_, c)) = self.columns.get_mut(&col_uid) {
+            c.is_used = is_used;
+            self.one_shot_flags.column_info_updated = true;
+        }
+    }
+
+    fn can_rename_column(&self, col_uid: ColumnUid) -> bool {
+        self.columns
+            .get(&col_uid)
+            .map(|(_, c)| c.is_renamable)
+            .unwrap_or(false)
+    }
+
+    fn rename_column(&mut self, col_uid: ColumnUid, name: String) {
+        if let Some((b, _)) = self.columns.get_mut(&col_uid) {
+            b.name = name.clone();
+            self.one_shot_flags.column_info_updated = true;
+            self.one_shot_flags
+                .push_event(TableEvent::ColumnRenamed { col_uid, name });
+        }
+    }
+
+    fn column_type_choices(&self, col_uid: ColumnUid) -> Vec<VariantTy> {
+        let Some((_, column)) = self
+            .columns
+            .get(&col_uid)
+            .filter(|(_, c)| c.compute.is_none())
+        else {
+            return vec![];
+        };
+        CONVERTIBLE_TYPES
+            .iter()
+            .copied()
+            .filter(|ty| *ty != column.ty)
+            .collect()
+    }
+
+    fn change_column_type(&mut self, col_uid: ColumnUid, ty: VariantTy) -> Vec<CellCoord> {
+        self.turn_column_into(col_uid, ty)
+    }
+
+    fn column_origin(&self, col_uid: ColumnUid) -> ColumnOrigin {
+        match self.columns.get(&col_uid) {
+            Some((_, c)) if c.compute.is_some() => ColumnOrigin::Computed,
+            Some((_, c)) if c.is_materialized => ColumnOrigin::Materialized,
+            _ => ColumnOrigin::Stored,
+        }
+    }
+
+    fn materialize_column(&mut self, col_uid: ColumnUid) {
+        if !self.is_computed(col_uid) {
+            return;
+        }
+        let values: Vec<(CellCoord, Variant)> = self
+            .row_order
+            .iter()
+            .filter_map(|row_uid| {
+                let coord = CellCoord {
+                    row_uid: *row_uid,
+                    col_uid,
+                };
+                Some((coord, self.compute(coord)?))
+            })
+            .collect();
+        let cells: Vec<CellChange> = values
+            .into_iter()
+            .map(|(coord, value)| CellChange {
+                coord,
+                before: self.cell_data.get(&coord).cloned(),
+                after: Some(value),
+            })
+            .collect();
+        let values = cells.iter().map(|c| (c.coord, c.after.clone())).collect();
+        self.set_materialized(col_uid, true, values);
+        self.history
+            .push_entry(UndoEntry::Materialized { col_uid, cells });
+    }
+
+    fn column_synonyms(&self, col_uid: ColumnUid) -> Option<&[String]> {
+        self.columns
+            .get(&col_uid)
+            .and_then(|(_, c)| c.synonyms.as_deref())
+    }
+
+    fn set_column_synonyms(&mut self, col_uid: ColumnUid, synonyms: Vec<String>) {
+        if let Some((_, c)) = self.columns.get_mut(&col_uid) {
+            c.synonyms = Some(synonyms);
+        }
+    }
+
+    fn column_description(&self, col_uid: ColumnUid) -> Option<&str> {
+        self.columns
+            .get(&col_uid)
+            .and_then(|(_, c)| c.description.as_deref())
+    }
+
+    fn column_example(&self, col_uid: ColumnUid) -> Option<Variant> {
+        let (_, c) = self.columns.get(&col_uid)?;
+        c.example.clone().or_else(|| c.default.clone())
+    }
+
     fn row_count(&self) -> usize {
-        self.row_order.len()
+        self.with_visible_rows(|rows| rows.len())
     }
 
     fn row_uid(&self, row_idx: VisualRowIdx) -> Option<RowUid> {
-        self.row_order.get(row_idx.0).copied()
+        self.with_visible_rows(|rows| rows.get(row_idx.0).copied())
     }
 
-    fn show_cell_view(&self, coord: CellCoord, ui: &mut Ui) {
-        let Some(value) = self.cell_data.get(&coord) else {
-            return;
+    fn visible_rows(&self) -> Vec<RowUid> {
+        self.with_visible_rows(|rows| rows.to_vec())
+    }
+
+    fn queryable(&self) -> Option<&dyn QueryableBackend> {
+        Some(self)
+    }
+
+    fn queryable_mut(&mut self) -> Option<&mut dyn QueryableBackend> {
+        Some(self)
+    }
+
+    fn get(&self, coord: CellCoord) -> Option<Variant> {
+        self.compute(coord)
+            .or_else(|| self.cell_data.get(&coord).cloned())
+    }
+
+    /// Same text as [show_cell_view](TableBackend::show_cell_view) shows, list items are numbered on separate lines.
+    fn display_text(&self, coord: CellCoord) -> Option<String> {
+        let computed = self.compute(coord);
+        let value = computed.as_ref().or_else(|| self.cell_data.get(&coord))?;
+        let text = match value {
+            Variant::Empty => String::new(),
+            Variant::Str(v) => v.clone(),
+            Variant::StrList(list) => list
+                .iter()
+                .enumerate()
+                .map(|(idx, v)| format!("{idx}: {v}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            other => other.to_string(),
         };
-        match value {
-            Variant::Empty => {}
-            Variant::Bool(v) => {
-                let mut v = *v;
-                ui.checkbox(&mut v, "");
-            }
-            Variant::Str(v) => {
-                ui.label(v);
-            }
-            Variant::StrList(list) => {
-                for (idx, v) in list.iter().enumerate() {
-                    ui.horizontal(|ui| {
-                        ui.monospace(format!("{idx}:"));
-                        ui.label(v);
-                    });
-                }
-            }
-            // Variant::List(_list) => {
-            //
-            // }
-            other => {
-                ui.label(other.to_string().as_str());
+        Some(text)
+    }
+
+    /// Type of the value in mixed columns.
+    fn cell_badge(&self, coord: CellCoord) -> Option<String> {
+        if !self.is_mixed(coord.col_uid) {
+            return None;
+        }
+        let computed = self.compute(coord);
+        let value = computed.as_ref().or_else(|| self.cell_data.get(&coord))?;
+        variant_ty(value).map(|ty| ty.to_string())
+    }
+
+    fn cell_error(&self, coord: CellCoord) -> Option<String> {
+        let (_, column) = self.columns.get(&coord.col_uid)?;
+        if !column.validators.is_empty() {
+            let value = self.get(coord).unwrap_or(Variant::Empty);
+            let error = column
+                .validators
+                .iter()
+                .find_map(|validator| validator.check(&value).err());
+            if error.is_some() {
+                return error;
             }
         }
+        self.row_rule_error(coord)
     }
 
+    fn validation_errors(&self) -> Vec<(CellCoord, String)> {
+        let mut checked: Vec<ColumnUid> = self
+            .columns
+            .iter()
+            .filter(|(_, (_, c))| !c.validators.is_empty())
+            .map(|(col_uid, _)| *col_uid)
+            .chain(self.row_rules.iter().flat_map(|rule| rule.columns()))
+            .filter(|col_uid| self.columns.contains_key(col_uid))
+            .collect();
+        if checked.is_empty() {
+            return vec![];
+        }
+        checked.sort();
+        checked.dedup();
+        self.visible_rows()
+            .into_iter()
+            .flat_map(|row_uid| {
+                checked.iter().map(move |col_uid| CellCoord {
+                    row_uid,
+                    col_uid: *col_uid,
+                })
+            })
+            .filter_map(|coord| Some((coord, self.cell_error(coord)?)))
+            .collect()
+    }
+
+    #[cfg(feature = "gui")]
+    fn show_cell_view(&self, coord: CellCoord, ui: &mut Ui) {
+        let computed = self.compute(coord);
+        let Some(value) = computed.as_ref().or_else(|| self.cell_data.get(&coord)) else {
+            return;
+        };
+        crate::table_view::builtin_view(value, ui);
+    }
+
+    #[cfg(feature = "gui")]
     fn show_cell_editor(&self, coord: CellCoord, ui: &mut Ui) -> Option<Response> {
         const INT_DRAG_SPEED: f32 = 0.1;
 
-        let cell_ty = self
+        if self.is_computed(coord.col_uid) {
+            self.show_cell_view(coord, ui);
+            return None;
+        }
+        if self.is_mixed(coord.col_uid) {
+            return self.show_mixed_cell_editor(coord, ui);
+        }
+
+        let (cell_ty, suggest_distinct, mask) = self
             .columns
             .get(&coord.col_uid)
-            .map(|(_, c)| c.ty)
-            .unwrap_or(VariantTy::Str);
+            .map(|(_, c)| (c.ty, c.suggest_distinct, c.mask.as_ref()))
+            .unwrap_or((VariantTy::Str, false, None));
 
         let mut value = if let Some((prev_coord, value)) = self.cell_edit.take() {
             if prev_coord == coord {
@@ -229,9 +1349,47 @@ impl TableBackend for VariantBackend {
                 //     edit.request_focus();
                 //     edit
                 // } else {
-                let resp = TextEdit::singleline(edit_text)
-                    .desired_width(f32::INFINITY)
-                    .ui(ui);
+                // Suggestions go above the text field, cell layout is horizontal by default
+                let resp = ui
+                    .vertical(|ui| {
+                        if suggest_distinct {
+                            let suggestions = self.distinct_values(coord.col_uid, edit_text);
+                            if !suggestions.iter().all(|s| s == edit_text) {
+                                ui.horizontal_wrapped(|ui| {
+                                    for suggestion in suggestions {
+                                        if ui.small_button(&suggestion).clicked() {
+                                            *edit_text = suggestion;
+                                        }
+                                    }
+                                });
+                            }
+                        }
+                        let prev_len = edit_text.chars().count();
+                        let mut output = TextEdit::singleline(edit_text)
+                            .desired_width(f32::INFINITY)
+                            .hint_text(mask.map(|m| m.pattern()).unwrap_or_default())
+                            .show(ui);
+                        if let (Some(mask), true) = (mask, output.response.changed()) {
+                            let is_deleting = edit_text.chars().count() < prev_len;
+                            let cursor = output
+                                .cursor_range
+                                .map(|range| range.primary.ccursor.index)
+                                .unwrap_or(edit_text.chars().count());
+                            let (masked, cursor) = mask.apply_at(edit_text, cursor, !is_deleting);
+                            if masked != *edit_text {
+                                // Literals were inserted or keystrokes rejected, keep the cursor where the edit was
+                                *edit_text = masked;
+                                let cursor = CCursor::new(cursor);
+                                output
+                                    .state
+                                    .cursor
+                                    .set_char_range(Some(CCursorRange::one(cursor)));
+                                output.state.store(ui.ctx(), output.response.id);
+                            }
+                        }
+                        output.response
+                    })
+                    .inner;
 
                 Some(resp)
                 // };
@@ -286,8 +1444,404 @@ impl TableBackend for VariantBackend {
     fn commit_cell_edit(&mut self, coord: CellCoord) {
         if let Some((last_edited_coord, value)) = self.cell_edit.take() {
             if last_edited_coord == coord {
-                self.cell_data.insert(coord, value);
+                // Typed over a non text cell, see start_cell_edit
+                let value = match (value, self.columns.get(&coord.col_uid)) {
+                    (Variant::Str(s), Some((_, column))) if column.is_mixed => {
+                        match self.cell_edit_ty.take() {
+                            Some(ty) if ty != VariantTy::Str => Variant::from_str(&s, ty),
+                            _ => Variant::Str(s),
+                        }
+                    }
+                    (Variant::Str(s), Some((_, column)))
+                        if !matches!(column.ty, VariantTy::Str) =>
+                    {
+                        Variant::from_str(&s, column.ty)
+                    }
+                    (value, _) => value,
+                };
+                // Leaving a cell without changing it is not an edit
+                if self.cell_data.get(&coord) == Some(&value) {
+                    return;
+                }
+                let before = self.cell_data.insert(coord, value.clone());
+                self.edited_cells.insert(coord);
+                self.cells_changed(vec![coord]);
+                self.history.push(vec![CellChange {
+                    coord,
+                    before,
+                    after: Some(value),
+                }]);
+            }
+        }
+    }
+
+    fn cancel_cell_edit(&mut self, coord: CellCoord) {
+        match self.cell_edit.take() {
+            Some((edited_coord, _)) if edited_coord == coord => {}
+            other => self.cell_edit.set(other),
+        }
+    }
+
+    fn start_cell_edit(&mut self, coord: CellCoord, typed: Option<String>) {
+        let is_text_editable = self.columns.get(&coord.col_uid).is_some_and(|(_, c)| {
+            c.is_mixed
+                || matches!(
+                    c.ty,
+                    VariantTy::Str
+                        | VariantTy::U32
+                        | VariantTy::U64
+                        | VariantTy::I32
+                        | VariantTy::I64
+                        | VariantTy::F32
+                        | VariantTy::F64
+                )
+        });
+        match typed {
+            Some(typed) if is_text_editable => {
+                let ty = self.cell_data.get(&coord).and_then(variant_ty);
+                self.cell_edit.set(Some((coord, Variant::Str(typed))));
+                self.cell_edit_ty.set(ty);
             }
+            _ => self.cell_edit.set(None),
         }
     }
+
+    fn modify_many(&mut self, values: Vec<(CellCoord, Variant)>) {
+        if self.persistent_flags.is_read_only {
+            return;
+        }
+        let changes: Vec<CellChange> = values
+            .into_iter()
+            .filter_map(|(coord, value)| {
+                let (_, column) = self
+                    .columns
+                    .get(&coord.col_uid)
+                    .filter(|(_, c)| c.compute.is_none())?;
+                let after = match value {
+                    Variant::Str(s) if !column.is_mixed && !matches!(column.ty, VariantTy::Str) => {
+                        Variant::from_str(&s, column.ty)
+                    }
+                    // E.g. pasted from another table, enums and empty values are kept as they are
+                    value
+                        if !column.is_mixed
+                            && variant_ty(&value).is_some_and(|ty| ty != column.ty) =>
+                    {
+                        Variant::from_str(&variant_to_string(&value), column.ty)
+                    }
+                    value => value,
+                };
+                let before = self.cell_data.get(&coord).cloned();
+                (before.as_ref() != Some(&after)).then_some(CellChange {
+                    coord,
+                    before,
+                    after: Some(after),
+                })
+            })
+            .collect();
+        // E.g. pasting the same values again, nothing to apply or undo
+        if changes.is_empty() {
+            return;
+        }
+        self.apply_changes(changes.iter().map(|c| (c.coord, c.after.clone())).collect());
+        self.history.push(changes);
+    }
+
+    fn clear_cells(&mut self, coords: impl Iterator<Item = CellCoord>) {
+        if self.persistent_flags.is_read_only {
+            return;
+        }
+        let changes: Vec<CellChange> = coords
+            .filter_map(|coord| {
+                let before = self.cell_data.get(&coord).cloned()?;
+                Some(CellChange {
+                    coord,
+                    before: Some(before),
+                    after: None,
+                })
+            })
+            .collect();
+        if changes.is_empty() {
+            return;
+        }
+        self.apply_changes(changes.iter().map(|c| (c.coord, None)).collect());
+        self.history.push(changes);
+    }
+
+    fn skip_row(&mut self, row_uid: RowUid, skip: bool) {
+        if skip {
+            self.skipped_rows.insert(row_uid);
+        } else {
+            self.skipped_rows.remove(&row_uid);
+        }
+    }
+
+    fn is_row_skipped(&self, row_uid: RowUid) -> bool {
+        self.skipped_rows.contains(&row_uid)
+    }
+
+    fn incomplete_rows(&self) -> Vec<RowUid> {
+        if !self.columns.values().any(|(_, c)| c.is_required) {
+            return vec![];
+        }
+        self.un_skipped_rows()
+            .into_iter()
+            .filter(|row_uid| !self.is_row_complete(*row_uid))
+            .collect()
+    }
+
+    fn can_append_rows(&self) -> bool {
+        !self.persistent_flags.is_read_only
+    }
+
+    fn append_row(&mut self) -> Option<RowUid> {
+        let row_uid = self.insert_row([]);
+        self.one_shot_flags.row_set_updated = true;
+        Some(row_uid)
+    }
+
+    fn can_remove_rows(&self) -> bool {
+        !self.persistent_flags.is_read_only
+    }
+
+    fn remove_rows(&mut self, row_uids: Vec<RowUid>) {
+        let row_uids: HashSet<RowUid> = row_uids.into_iter().collect();
+        let removed = self
+            .row_order
+            .iter()
+            .enumerate()
+            .filter(|(_, row_uid)| row_uids.contains(row_uid))
+            .map(|(position, row_uid)| {
+                let mut cells = vec![];
+                let mut edited = vec![];
+                for col_uid in self.columns.keys() {
+                    let coord = CellCoord {
+                        row_uid: *row_uid,
+                        col_uid: *col_uid,
+                    };
+                    if let Some(value) = self.cell_data.get(&coord) {
+                        cells.push((*col_uid, value.clone()));
+                    }
+                    if self.edited_cells.contains(&coord) {
+                        edited.push(*col_uid);
+                    }
+                }
+                RemovedRow {
+                    row_uid: *row_uid,
+                    position,
+                    cells,
+                    edited,
+                    is_skipped: self.skipped_rows.contains(row_uid),
+                    pinned: self.pinned_rows.iter().position(|r| r == row_uid),
+                }
+            })
+            .collect::<Vec<_>>();
+        if removed.is_empty() {
+            return;
+        }
+        self.remove_rows_unrecorded(&row_uids);
+        self.history.push_entry(UndoEntry::RowsRemoved(removed));
+    }
+
+    fn pin_row(&mut self, row_uid: RowUid, pinned: bool) {
+        self.pinned_rows.retain(|r| *r != row_uid);
+        if pinned {
+            self.pinned_rows.push(row_uid);
+        }
+    }
+
+    fn pinned_rows(&self) -> Vec<RowUid> {
+        self.pinned_rows.clone()
+    }
+
+    fn move_row(&mut self, row_uid: RowUid, to: VisualRowIdx) {
+        if self.persistent_flags.is_read_only {
+            return;
+        }
+        let Some(target) = self.row_uid(to) else {
+            return;
+        };
+        let (Some(from), Some(to)) = (
+            self.row_order.iter().position(|r| *r == row_uid),
+            self.row_order.iter().position(|r| *r == target),
+        ) else {
+            return;
+        };
+        if from == to {
+            return;
+        }
+        self.reposition_row(from, to);
+        self.history
+            .push_entry(UndoEntry::RowMoved { row_uid, from, to });
+    }
+
+    fn move_cell_value(&mut self, col_uid: ColumnUid, from: VisualRowIdx, to: VisualRowIdx) {
+        if self.persistent_flags.is_read_only
+            || from.0 == to.0
+            || from.0.max(to.0) >= self.row_count()
+        {
+            return;
+        }
+        let rows =
+            self.with_visible_rows(|rows| rows[from.0.min(to.0)..=from.0.max(to.0)].to_vec());
+        let before: Vec<Option<Variant>> = rows
+            .iter()
+            .map(|row_uid| {
+                self.cell_data
+                    .get(&CellCoord {
+                        row_uid: *row_uid,
+                        col_uid,
+                    })
+                    .cloned()
+            })
+            .collect();
+        let mut after = before.clone();
+        if from.0 < to.0 {
+            after.rotate_left(1);
+        } else {
+            after.rotate_right(1);
+        }
+        let changes: Vec<CellChange> = rows
+            .iter()
+            .zip(before.into_iter().zip(after))
+            .map(|(row_uid, (before, after))| CellChange {
+                coord: CellCoord {
+                    row_uid: *row_uid,
+                    col_uid,
+                },
+                before,
+                after,
+            })
+            .collect();
+        self.apply_changes(changes.iter().map(|c| (c.coord, c.after.clone())).collect());
+        self.history.push(changes);
+    }
+
+    fn undo(&mut self) {
+        let Some(entry) = self.history.undo().cloned() else {
+            return;
+        };
+        match entry {
+            UndoEntry::Cells(changes) => {
+                let changes = changes
+                    .into_iter()
+                    .rev()
+                    .map(|c| (c.coord, c.before))
+                    .collect();
+                self.apply_changes(changes);
+            }
+            UndoEntry::ColumnType {
+                col_uid,
+                before,
+                cells,
+                ..
+            } => {
+                let values = cells
+                    .into_iter()
+                    .rev()
+                    .map(|c| (c.coord, c.before))
+                    .collect();
+                self.set_column_type_state(col_uid, &before, values);
+            }
+            UndoEntry::RowsRemoved(rows) => self.restore_rows(rows),
+            UndoEntry::RowMoved { from, to, .. } => self.reposition_row(to, from),
+            UndoEntry::Materialized { col_uid, cells } => {
+                let values = cells.into_iter().map(|c| (c.coord, c.before)).collect();
+                self.set_materialized(col_uid, false, values);
+            }
+        }
+    }
+
+    fn redo(&mut self) {
+        let Some(entry) = self.history.redo().cloned() else {
+            return;
+        };
+        match entry {
+            UndoEntry::Cells(changes) => {
+                let changes = changes.into_iter().map(|c| (c.coord, c.after)).collect();
+                self.apply_changes(changes);
+            }
+            UndoEntry::ColumnType {
+                col_uid,
+                after,
+                cells,
+                ..
+            } => {
+                let values = cells.into_iter().map(|c| (c.coord, c.after)).collect();
+                self.set_column_type_state(col_uid, &after, values);
+            }
+            UndoEntry::RowsRemoved(rows) => {
+                let row_uids = rows.iter().map(|row| row.row_uid).collect();
+                self.remove_rows_unrecorded(&row_uids);
+            }
+            UndoEntry::RowMoved { from, to, .. } => self.reposition_row(from, to),
+            UndoEntry::Materialized { col_uid, cells } => {
+                let values = cells.into_iter().map(|c| (c.coord, c.after)).collect();
+                self.set_materialized(col_uid, true, values);
+            }
+        }
+    }
+
+    fn history_position(&self) -> Option<(usize, usize)> {
+        let undo_len = self.history.undo_len();
+        Some((undo_len, undo_len + self.history.redo_len()))
+    }
+}
+
+impl QueryableBackend for VariantBackend {
+    fn supports_sort(&self, col_uid: ColumnUid) -> bool {
+        self.columns.contains_key(&col_uid)
+    }
+
+    fn supports_filter(&self, col_uid: ColumnUid, _op: FilterOp) -> bool {
+        self.columns.contains_key(&col_uid)
+    }
+
+    fn apply(&mut self, spec: &QuerySpec) {
+        self.query = spec.clone();
+        self.visible_rows.get_mut().take();
+        self.one_shot_flags.visible_row_vec_updated = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VariantBackend;
+    use crate::backend::{CellCoord, ColumnUid, TableBackend};
+    use rvariant::{Variant, VariantTy};
+
+    const NAME: ColumnUid = ColumnUid(0);
+
+    #[test]
+    fn distinct_values_follow_cell_changes() {
+        let mut backend = VariantBackend::new([("name".to_string(), VariantTy::Str, None)]);
+        let rows: Vec<_> = ["b", "a", "b", ""]
+            .iter()
+            .map(|name| backend.insert_row([(NAME, Variant::Str(name.to_string()))]))
+            .collect();
+        assert_eq!(backend.distinct_values(NAME, ""), vec!["b", "a"]);
+        assert_eq!(backend.distinct_values(NAME, "A"), vec!["a"]);
+
+        backend.set(
+            CellCoord {
+                row_uid: rows[3],
+                col_uid: NAME,
+            },
+            Variant::Str("c".to_string()),
+        );
+        assert_eq!(backend.distinct_values(NAME, ""), vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn unchanged_commit_is_not_an_edit() {
+        let mut backend = VariantBackend::new([("name".to_string(), VariantTy::Str, None)]);
+        let row_uid = backend.insert_row([(NAME, Variant::Str("a".to_string()))]);
+        let coord = CellCoord {
+            row_uid,
+            col_uid: NAME,
+        };
+        backend.start_cell_edit(coord, Some("a".to_string()));
+        backend.commit_cell_edit(coord);
+        assert_eq!(backend.history_position(), Some((0, 0)));
+        assert!(backend.edited_cells.is_empty());
+    }
 }
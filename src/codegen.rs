@@ -0,0 +1,210 @@
+//! Rust code for typed access to imported data, see [VariantBackend::struct_code].
+
+use crate::backend::{ColumnUid, RowUid};
+use crate::backends::variant::VariantBackend;
+use rvariant::{Variant, VariantTy};
+use std::collections::HashSet;
+
+/// Typed row built from the values returned by [VariantBackend::extract], implemented by the code
+/// generated with [VariantBackend::struct_code].
+pub trait FromVariantRow: Sized {
+    /// None if a required value is missing or of a wrong type.
+    fn from_variant_row(row: &[(ColumnUid, Variant)]) -> Option<Self>;
+}
+
+/// Field of the generated struct.
+pub(crate) struct StructField {
+    pub(crate) col_uid: ColumnUid,
+    pub(crate) name: String,
+    /// Fields of other types than plain ones, e.g. of mixed columns, are kept as [Variant].
+    pub(crate) ty: VariantTy,
+    /// Required fields are not wrapped into an Option.
+    pub(crate) is_required: bool,
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "become", "box", "do", "final", "macro", "override", "priv", "try", "typeof",
+    "unsized", "virtual", "yield",
+];
+
+/// Keywords that can't be used as raw identifiers, `Self` is lowercased before the check.
+const PATH_KEYWORDS: &[&str] = &["crate", "self", "super"];
+
+impl VariantBackend {
+    /// Rows of used columns converted into a type generated with [struct_code](Self::struct_code),
+    /// rows that could not be converted are left out. Fails with the incomplete rows the same way as
+    /// [extract](Self::extract).
+    pub fn extract_as<T: FromVariantRow>(&self) -> Result<Vec<T>, Vec<RowUid>> {
+        Ok(self
+            .extract()?
+            .into_iter()
+            .filter_map(|(_, row)| T::from_variant_row(&row))
+            .collect())
+    }
+}
+
+/// Struct definition with a field per column and its [FromVariantRow] implementation.
+pub(crate) fn struct_code(struct_name: &str, fields: Vec<StructField>) -> String {
+    let mut taken = HashSet::new();
+    let fields: Vec<(String, StructField)> = fields
+        .into_iter()
+        .map(|field| {
+            let base = field_name(&field.name, field.col_uid);
+            let mut name = base.clone();
+            let mut n = 2;
+            while !taken.insert(name.clone()) {
+                name = format!("{base}_{n}");
+                n += 1;
+            }
+            (name, field)
+        })
+        .collect();
+
+    let mut code = String::from("use egui_tabular::backend::ColumnUid;\n");
+    code += "use egui_tabular::codegen::FromVariantRow;\n";
+    code += "use egui_tabular::rvariant::Variant;\n\n";
+    code += "#[derive(Clone, Debug, PartialEq)]\n";
+    code += &format!("pub struct {struct_name} {{\n");
+    for (name, field) in &fields {
+        let ty = rust_type(field.ty);
+        if field.is_required {
+            code += &format!("    /// {:?}\n    pub {name}: {ty},\n", field.name);
+        } else {
+            code += &format!("    /// {:?}\n    pub {name}: Option<{ty}>,\n", field.name);
+        }
+    }
+    code += "}\n\n";
+    code += &format!("impl FromVariantRow for {struct_name} {{\n");
+    code += "    fn from_variant_row(row: &[(ColumnUid, Variant)]) -> Option<Self> {\n";
+    code += "        let get = |col_uid: u32| row.iter().find(|(c, _)| c.0 == col_uid).map(|(_, v)| v);\n";
+    code += &format!("        Some({struct_name} {{\n");
+    for (name, field) in &fields {
+        let uid = field.col_uid.0;
+        if rust_type(field.ty) == "Variant" {
+            if field.is_required {
+                code += &format!("            {name}: get({uid})?.clone(),\n");
+            } else {
+                code += &format!("            {name}: get({uid}).cloned(),\n");
+            }
+            continue;
+        }
+        let variant = format!("{:?}", field.ty);
+        let value = if is_copy(field.ty) { "*v" } else { "v.clone()" };
+        if field.is_required {
+            code += &format!(
+                "            {name}: match get({uid})? {{\n                Variant::{variant}(v) => {value},\n                _ => return None,\n            }},\n"
+            );
+        } else {
+            code += &format!(
+                "            {name}: match get({uid}) {{\n                Some(Variant::{variant}(v)) => Some({value}),\n                _ => None,\n            }},\n"
+            );
+        }
+    }
+    code += "        })\n    }\n}\n";
+    code
+}
+
+/// Column name in snake case, usable as an identifier.
+fn field_name(column: &str, col_uid: ColumnUid) -> String {
+    let mut name = String::new();
+    let mut prev_lower = false;
+    for c in column.chars() {
+        if c.is_alphanumeric() {
+            if c.is_uppercase() && prev_lower {
+                name.push('_');
+            }
+            name.extend(c.to_lowercase());
+            prev_lower = c.is_lowercase() || c.is_numeric();
+        } else {
+            if !name.is_empty() && !name.ends_with('_') {
+                name.push('_');
+            }
+            prev_lower = false;
+        }
+    }
+    let name = name.trim_end_matches('_').to_string();
+    if name.is_empty() || !name.is_ascii() {
+        format!("column_{}", col_uid.0)
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{name}")
+    } else if PATH_KEYWORDS.contains(&name.as_str()) {
+        format!("{name}_")
+    } else {
+        name
+    }
+}
+
+fn rust_type(ty: VariantTy) -> &'static str {
+    match ty {
+        VariantTy::Bool => "bool",
+        VariantTy::U32 => "u32",
+        VariantTy::U64 => "u64",
+        VariantTy::I32 => "i32",
+        VariantTy::I64 => "i64",
+        VariantTy::F32 => "f32",
+        VariantTy::F64 => "f64",
+        VariantTy::Str => "String",
+        VariantTy::StrList => "Vec<String>",
+        _ => "Variant",
+    }
+}
+
+fn is_copy(ty: VariantTy) -> bool {
+    !matches!(ty, VariantTy::Str | VariantTy::StrList)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{field_name, struct_code, StructField};
+    use crate::backend::ColumnUid;
+    use rvariant::VariantTy;
+
+    #[test]
+    fn field_names() {
+        let name = |column: &str| field_name(column, ColumnUid(7));
+        assert_eq!(name("Part Number"), "part_number");
+        assert_eq!(name("partNumber"), "part_number");
+        assert_eq!(name("  Qty (pcs) "), "qty_pcs");
+        assert_eq!(name("2nd value"), "_2nd_value");
+        assert_eq!(name("type"), "r#type");
+        assert_eq!(name("Self"), "self_");
+        assert_eq!(name("crate"), "crate_");
+        assert_eq!(name("super"), "super_");
+        assert_eq!(name("Цена"), "column_7");
+        assert_eq!(name("--"), "column_7");
+    }
+
+    #[test]
+    fn struct_fields() {
+        let field = |col_uid: u32, name: &str, ty: VariantTy, is_required: bool| StructField {
+            col_uid: ColumnUid(col_uid),
+            name: name.to_string(),
+            ty,
+            is_required,
+        };
+        let code = struct_code(
+            "Part",
+            vec![
+                field(0, "Name", VariantTy::Str, true),
+                field(1, "name", VariantTy::U32, false),
+                field(2, "Extra", VariantTy::Empty, false),
+            ],
+        );
+        assert!(code.contains("pub struct Part {"));
+        assert!(code.contains("    pub name: String,\n"));
+        assert!(code.contains("    pub name_2: Option<u32>,\n"));
+        assert!(code.contains("    pub extra: Option<Variant>,\n"));
+        assert!(code.contains(
+            "            name: match get(0)? {\n                Variant::Str(v) => v.clone(),\n"
+        ));
+        assert!(code.contains(
+            "            name_2: match get(1) {\n                Some(Variant::U32(v)) => Some(*v),\n"
+        ));
+        assert!(code.contains("            extra: get(2).cloned(),\n"));
+    }
+}
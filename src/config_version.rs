@@ -0,0 +1,73 @@
+//! Versions of configs persisted by host applications, so that configs stored by older versions of this crate
+//! keep loading after fields are added, renamed or change meaning.
+//!
+//! Missing fields take their defaults while deserializing, [VersionedConfig::migrate] then upgrades
+//! what defaults alone can't, e.g. values that moved to another field.
+
+/// Config stored by host applications, configs stored before versioning was introduced are version 0.
+pub trait VersionedConfig: Sized {
+    /// Version written by this version of the crate.
+    const VERSION: u32;
+
+    fn version(&self) -> u32;
+
+    fn set_version(&mut self, version: u32);
+
+    /// Steps upgrading older configs to [VERSION](Self::VERSION).
+    fn migrations() -> ConfigMigrations<Self>;
+
+    /// Upgrade a config loaded from storage, returns true if it was stored by an older version.
+    /// Configs stored by a newer version are left as they are.
+    fn migrate(&mut self) -> bool {
+        Self::migrations().apply(self)
+    }
+}
+
+/// Ordered upgrade steps of one config type.
+pub struct ConfigMigrations<T> {
+    /// Version introducing the change and the upgrade to it.
+    steps: Vec<(u32, MigrationFn<T>)>,
+}
+
+type MigrationFn<T> = fn(&mut T);
+
+impl<T: VersionedConfig> Default for ConfigMigrations<T> {
+    fn default() -> Self {
+        ConfigMigrations { steps: Vec::new() }
+    }
+}
+
+impl<T: VersionedConfig> ConfigMigrations<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Upgrade configs older than `version` with `step`, steps are applied in the order of their versions.
+    pub fn step(mut self, version: u32, step: MigrationFn<T>) -> Self {
+        self.steps.push((version, step));
+        self.steps.sort_by_key(|(version, _)| *version);
+        self
+    }
+
+    /// Apply steps newer than the config version and mark it as current, returns true if it was outdated.
+    pub fn apply(&self, config: &mut T) -> bool {
+        let stored = config.version();
+        if stored > T::VERSION {
+            log::warn!(
+                "Config version {stored} is newer than supported {}, unknown fields are ignored",
+                T::VERSION
+            );
+            return false;
+        }
+        if stored == T::VERSION {
+            return false;
+        }
+        for (version, step) in &self.steps {
+            if stored < *version {
+                step(config);
+            }
+        }
+        config.set_version(T::VERSION);
+        true
+    }
+}
@@ -1,19 +1,34 @@
+use super::import_job::{ImportJob, ImportProgress, JobContext};
+use super::import_report::{ImportIssue, ImportReport};
 use super::required_column::RequiredColumns;
-use crate::backend::{ColumnUid, TableBackend};
+use super::value_transform::{apply_transforms, Transformed, ValueTransform};
+use super::xls;
+use crate::backend::{CellCoord, ColumnUid, RowUid, TableBackend};
 use crate::backends::variant::VariantBackend;
+use crate::util::detect_encoding;
+use chrono::{NaiveDate, NaiveDateTime};
+use encoding_rs::{Encoding, UTF_8};
+use encoding_rs_io::DecodeReaderBytesBuilder;
 use log::{trace, warn};
 use rvariant::{Variant, VariantTy};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs::File;
-use std::io::{BufReader, Read};
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufReader, Read};
 use std::path::PathBuf;
 
 pub(crate) struct CsvImporter {
     required_columns: RequiredColumns,
 
-    separator: Separator,
-    skip_first_rows: usize,
+    config: CsvImporterConfig,
+    /// Header name -> name given by the user, used to match required columns.
+    header_renames: HashMap<String, String>,
+    /// Header name -> type chosen by the user for additional columns, instead of the inferred one.
+    type_overrides: HashMap<String, VariantTy>,
+    load_in_background: bool,
 
     state: State,
 }
@@ -21,6 +36,23 @@ pub(crate) struct CsvImporter {
 #[derive(Default)]
 struct State {
     status: IoStatus,
+    loaded_path: Option<PathBuf>,
+    /// Row identity of the loaded file rows, see [CsvImporter::row_key].
+    row_keys: HashMap<u64, RowUid>,
+    /// Non-fatal problems encountered while loading, to be shown to the user.
+    warnings: Vec<String>,
+    report: ImportReport,
+    /// Required columns not found in the last loaded file.
+    unmapped_required: Vec<ColumnUid>,
+    /// Header name -> name of the required column it was matched to in the last loaded file.
+    matched_headers: HashMap<String, String>,
+    encoding: Option<&'static Encoding>,
+    /// Columns of the last loaded file not matching any required column.
+    additional_columns: Vec<AdditionalColumn>,
+    /// Rows being parsed on a background thread.
+    job: Option<ImportJob<ParsedRows>>,
+    /// Cells missing in rows shorter than the header, see [RaggedRowPolicy::PadEmpty].
+    padded_cells: Vec<CellCoord>,
 }
 
 #[allow(dead_code)]
@@ -31,9 +63,14 @@ pub enum IoStatus {
     IoError(std::io::Error),
     ReaderError(csv::Error),
     ReaderErrorAtLine(usize, csv::Error),
+    SpreadsheetError(calamine::Error),
     Loaded(PathBuf),
     Edited,
     UnknownSeparator,
+    /// Encoding label set in [CsvImporterConfig::encoding] is not known to [encoding_rs].
+    UnknownEncoding(String),
+    /// Loading was cancelled by the user, previously loaded data is kept.
+    Cancelled,
     // Warning,
 }
 
@@ -41,18 +78,21 @@ impl IoStatus {
     pub fn is_error(&self) -> bool {
         match self {
             IoStatus::Empty => false,
-            IoStatus::IoError(_) | IoStatus::ReaderError(_) | IoStatus::ReaderErrorAtLine(_, _) => {
-                true
-            }
+            IoStatus::IoError(_)
+            | IoStatus::ReaderError(_)
+            | IoStatus::ReaderErrorAtLine(_, _)
+            | IoStatus::SpreadsheetError(_) => true,
             IoStatus::Loaded(_) => false,
             IoStatus::Edited => false,
             IoStatus::UnknownSeparator => true,
+            IoStatus::UnknownEncoding(_) => true,
+            IoStatus::Cancelled => false,
         }
     }
 }
 
 #[derive(
-    strum::EnumIter, strum::Display, PartialEq, Copy, Clone, Default, Serialize, Deserialize,
+    strum::EnumIter, strum::Display, PartialEq, Copy, Clone, Debug, Default, Serialize, Deserialize,
 )]
 // #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Separator {
@@ -63,115 +103,1021 @@ pub enum Separator {
     Semicolon,
 }
 
+/// How numbers are written in the file, used when converting cells to numeric column types.
+#[derive(
+    strum::EnumIter, strum::Display, PartialEq, Copy, Clone, Debug, Default, Serialize, Deserialize,
+)]
+pub enum NumberLocale {
+    /// Parsed as is, without thousands separators.
+    #[default]
+    #[strum(to_string = "1234.56")]
+    Plain,
+    #[strum(to_string = "1,234.56")]
+    DotDecimal,
+    #[strum(to_string = "1.234,56")]
+    CommaDecimal,
+}
+
+impl NumberLocale {
+    /// Remove thousands separators and spaces, and use a dot as decimal separator.
+    pub fn normalize(&self, value: &str) -> String {
+        let (group, decimal) = match self {
+            NumberLocale::Plain => return value.to_string(),
+            NumberLocale::DotDecimal => (',', '.'),
+            NumberLocale::CommaDecimal => ('.', ','),
+        };
+        value
+            .chars()
+            .filter(|c| *c != group && !c.is_whitespace())
+            .map(|c| if c == decimal { '.' } else { c })
+            .collect()
+    }
+}
+
+/// What to do with rows that have more or fewer fields than the header row.
+#[derive(
+    strum::EnumIter, strum::Display, PartialEq, Copy, Clone, Debug, Default, Serialize, Deserialize,
+)]
+pub enum RaggedRowPolicy {
+    /// Stop loading at the first such row, rows before it are kept.
+    Error,
+    /// Missing fields are left empty or take column defaults, extra fields are ignored.
+    #[default]
+    #[strum(to_string = "Pad with empty")]
+    PadEmpty,
+    /// Such rows are not loaded.
+    #[strum(to_string = "Skip row")]
+    SkipRow,
+}
+
+/// CSV specific import options, missing fields take their defaults when deserializing.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvImporterConfig {
+    pub separator: Separator,
+    /// Rows to skip before the header row.
+    pub skip_first_rows: usize,
+    /// Rows to skip at the end of the file, e.g. totals or disclaimers.
+    #[serde(default)]
+    pub skip_last_rows: usize,
+    /// Encoding label, e.g. "windows-1252", None to detect it from the first 64 KiB of the file.
+    /// Loading fails with [IoStatus::UnknownEncoding] if the label is not recognized.
+    #[serde(default)]
+    pub encoding: Option<String>,
+    /// Character enclosing fields that contain separators or line breaks.
+    #[serde(default = "default_quote")]
+    pub quote: u8,
+    /// Character escaping quotes inside quoted fields, None if quotes are escaped by doubling them.
+    #[serde(default)]
+    pub escape: Option<u8>,
+    /// Lines starting with this character are ignored.
+    #[serde(default)]
+    pub comment: Option<u8>,
+    /// Number format used by numeric columns, unless overridden in a [RequiredColumn](super::required_column::RequiredColumn).
+    #[serde(default)]
+    pub number_locale: NumberLocale,
+    /// Detect types of columns not matching any required column from the first rows, otherwise they are strings.
+    #[serde(default = "default_infer_types")]
+    pub infer_types: bool,
+    /// Remove leading and trailing whitespace of all cells.
+    #[serde(default)]
+    pub trim: bool,
+    /// Replace runs of whitespace inside cells with a single space.
+    #[serde(default)]
+    pub collapse_whitespace: bool,
+    /// Load empty cells as [Variant::Empty] in string columns as well, instead of empty strings.
+    #[serde(default)]
+    pub empty_to_null: bool,
+    /// Handling of rows with a different number of fields than the header, they are listed in the report.
+    #[serde(default)]
+    pub ragged_rows: RaggedRowPolicy,
+}
+
+fn default_quote() -> u8 {
+    b'"'
+}
+
+fn default_infer_types() -> bool {
+    true
+}
+
+impl Default for CsvImporterConfig {
+    fn default() -> Self {
+        CsvImporterConfig {
+            separator: Separator::default(),
+            skip_first_rows: 0,
+            skip_last_rows: 0,
+            encoding: None,
+            quote: default_quote(),
+            escape: None,
+            comment: None,
+            number_locale: NumberLocale::default(),
+            infer_types: default_infer_types(),
+            trim: false,
+            collapse_whitespace: false,
+            empty_to_null: false,
+            ragged_rows: RaggedRowPolicy::default(),
+        }
+    }
+}
+
+/// Whitespace and empty cell handling applied to all cells before conversion.
+#[derive(Copy, Clone, Debug, Default)]
+struct CellCleaning {
+    trim: bool,
+    collapse_whitespace: bool,
+    empty_to_null: bool,
+}
+
+impl CellCleaning {
+    fn new(config: &CsvImporterConfig) -> Self {
+        CellCleaning {
+            trim: config.trim,
+            collapse_whitespace: config.collapse_whitespace,
+            empty_to_null: config.empty_to_null,
+        }
+    }
+
+    fn apply<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        let value = if self.trim { value.trim() } else { value };
+        if !self.collapse_whitespace {
+            return Cow::Borrowed(value);
+        }
+        let mut collapsed = String::with_capacity(value.len());
+        let mut is_space = false;
+        for c in value.chars() {
+            if !c.is_whitespace() {
+                collapsed.push(c);
+            } else if !is_space {
+                collapsed.push(' ');
+            }
+            is_space = c.is_whitespace();
+        }
+        Cow::Owned(collapsed)
+    }
+}
+
+/// How cells of a required column are converted while importing.
+#[derive(Clone)]
+struct ColumnConversion {
+    ty: VariantTy,
+    number_locale: NumberLocale,
+    date_format: Option<String>,
+    transforms: Vec<ValueTransform>,
+}
+
+/// Rows parsed from a CSV file, possibly on a background thread.
+struct ParsedRows {
+    path: PathBuf,
+    staging: VariantBackend,
+    staged_row_keys: HashMap<u64, RowUid>,
+    /// Line number and error, if parsing stopped early.
+    error: Option<(usize, csv::Error)>,
+    report: ImportReport,
+    /// Cells of the staging backend missing in short rows.
+    padded_cells: Vec<CellCoord>,
+}
+
+/// How often rows parsed on a background thread are reported.
+const PROGRESS_EVERY_ROWS: usize = 1000;
+/// Only the beginning of a file is used to guess separator and encoding.
+const SAMPLE_BYTES: u64 = 64 * 1024;
+const UTF_8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+fn read_sample(path: &PathBuf) -> std::io::Result<Vec<u8>> {
+    let mut sample = Vec::new();
+    File::open(path)?
+        .take(SAMPLE_BYTES)
+        .read_to_end(&mut sample)?;
+    Ok(sample)
+}
+
+/// Dates are converted to this format before converting to the column type.
+pub(crate) const ISO_DATE_TIME: &str = "%Y-%m-%dT%H:%M:%S%.f";
+const ISO_DATE: &str = "%Y-%m-%d";
+
+/// Parse date or date and time in the given format, values already in ISO 8601 (e.g. from spreadsheets) are accepted as well.
+fn normalize_date(value: &str, format: &str) -> Option<String> {
+    for format in [format, ISO_DATE_TIME, ISO_DATE] {
+        if let Ok(date_time) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(date_time.format(ISO_DATE_TIME).to_string());
+        }
+        if let Ok(date) = NaiveDate::parse_from_str(value, format) {
+            return Some(date.format(ISO_DATE).to_string());
+        }
+    }
+    None
+}
+
+/// Type of a column guessed from the first rows of a file.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct InferredType {
+    pub ty: VariantTy,
+    /// Dates are loaded into string columns, converted from this format to ISO 8601.
+    pub date_format: Option<&'static str>,
+    /// Fraction of the sampled non-empty values that parsed as `ty`, from 0.0 to 1.0, 0.0 if all were empty.
+    pub confidence: f32,
+}
+
+impl std::fmt::Display for InferredType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.date_format {
+            Some(format) => write!(f, "Date ({format})")?,
+            None => write!(f, "{}", self.ty)?,
+        }
+        write!(f, ", {:.0}% of values match", self.confidence * 100.0)
+    }
+}
+
+/// Column of a loaded file not matching any required column.
+struct AdditionalColumn {
+    col_uid: ColumnUid,
+    /// Header name in the file.
+    header: String,
+    /// None if type inference is disabled.
+    inferred: Option<InferredType>,
+    /// Type the column was loaded as, inferred or overridden.
+    ty: VariantTy,
+}
+
+impl AdditionalColumn {
+    /// Type the column gets without an override.
+    fn detected_ty(&self) -> VariantTy {
+        self.inferred
+            .map(|inferred| inferred.ty)
+            .unwrap_or(VariantTy::Str)
+    }
+}
+
+/// Number of rows after the header used to infer column types.
+const INFER_SAMPLE_ROWS: usize = 100;
+/// Fraction of non-empty values that must parse for a type to be inferred,
+/// so that a few typos don't turn a numeric column into strings. The rest are reported as conversion errors.
+const INFER_MIN_CONFIDENCE: f32 = 0.95;
+/// Date formats tried when inferring types, day first formats are preferred when ambiguous.
+const INFER_DATE_FORMATS: &[&str] = &[
+    ISO_DATE,
+    "%Y-%m-%d %H:%M:%S",
+    "%d.%m.%Y",
+    "%d/%m/%Y",
+    "%m/%d/%Y",
+    "%d.%m.%Y %H:%M:%S",
+    "%d/%m/%Y %H:%M:%S",
+];
+
+/// The first type in which most non-empty values can be parsed, strings if none.
+fn infer_type<'a>(values: impl Iterator<Item = &'a str>, locale: NumberLocale) -> InferredType {
+    let non_empty: Vec<&str> = values.map(|v| v.trim()).filter(|v| !v.is_empty()).collect();
+    let matching = |parses: &dyn Fn(&str) -> bool| {
+        non_empty.iter().filter(|v| parses(v)).count() as f32 / non_empty.len() as f32
+    };
+    if non_empty.is_empty() {
+        return InferredType {
+            ty: VariantTy::Str,
+            date_format: None,
+            confidence: 0.0,
+        };
+    }
+    let parses = |ty: VariantTy, value: &str| {
+        let number = locale.normalize(value);
+        match ty {
+            VariantTy::U32 => number.parse::<u32>().is_ok(),
+            VariantTy::I64 => number.parse::<i64>().is_ok(),
+            // Don't treat "inf" or "NaN" as numbers
+            VariantTy::F64 => {
+                number.parse::<f64>().is_ok() && number.chars().any(|c| c.is_ascii_digit())
+            }
+            VariantTy::Bool => {
+                value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false")
+            }
+            _ => true,
+        }
+    };
+    for ty in [
+        VariantTy::U32,
+        VariantTy::I64,
+        VariantTy::F64,
+        VariantTy::Bool,
+    ] {
+        let confidence = matching(&|v| parses(ty, v));
+        if confidence >= INFER_MIN_CONFIDENCE {
+            return InferredType {
+                ty,
+                date_format: None,
+                confidence,
+            };
+        }
+    }
+    for format in INFER_DATE_FORMATS {
+        let confidence = matching(&|v| normalize_date(v, format).is_some());
+        if confidence >= INFER_MIN_CONFIDENCE {
+            return InferredType {
+                ty: VariantTy::Str,
+                date_format: Some(format),
+                confidence,
+            };
+        }
+    }
+    InferredType {
+        ty: VariantTy::Str,
+        date_format: None,
+        confidence: 1.0,
+    }
+}
+
+fn is_numeric(ty: VariantTy) -> bool {
+    matches!(
+        ty,
+        VariantTy::U32
+            | VariantTy::U64
+            | VariantTy::I32
+            | VariantTy::I64
+            | VariantTy::F32
+            | VariantTy::F64
+    )
+}
+
+/// Transform and convert cell to the required column type, if any, returns an error message along with the value
+/// if the conversion failed.
+fn convert_cell_value(
+    column_types: &HashMap<ColumnUid, ColumnConversion>,
+    col_uid: ColumnUid,
+    value: &str,
+) -> (Variant, Option<String>) {
+    let Some(conversion) = column_types.get(&col_uid) else {
+        return (Variant::Str(value.to_string()), None);
+    };
+    let value = match apply_transforms(&conversion.transforms, value) {
+        Transformed::Text(text) => text,
+        Transformed::Value(value) => return (value, None),
+    };
+    let value = value.as_ref();
+    let ty = &conversion.ty;
+    let locale = conversion.number_locale;
+    let converted =
+        if let (Some(format), false) = (&conversion.date_format, value.trim().is_empty()) {
+            match normalize_date(value.trim(), format) {
+                Some(iso) => Variant::from_str(&iso, *ty),
+                None => {
+                    let error = format!("Cannot parse {value:?} as date with format {format:?}");
+                    return (Variant::Str(value.to_string()), Some(error));
+                }
+            }
+        } else if is_numeric(*ty) && locale != NumberLocale::Plain {
+            Variant::from_str(&locale.normalize(value), *ty)
+        } else {
+            Variant::from_str(value, *ty)
+        };
+    let is_failed = !matches!(ty, VariantTy::Str | VariantTy::StrList)
+        && !value.trim().is_empty()
+        && matches!(converted, Variant::Empty | Variant::Str(_));
+    let error = is_failed.then(|| format!("Cannot convert {value:?} to {ty}"));
+    (converted, error)
+}
+
+/// Convert all cells of a row and insert it into the staging backend, conversion problems are added to the report.
+fn insert_row<'a>(
+    staging: &mut VariantBackend,
+    report: &mut ImportReport,
+    line: usize,
+    cells: impl Iterator<Item = &'a str>,
+    csv_to_col_uid: &HashMap<usize, ColumnUid>,
+    column_types: &HashMap<ColumnUid, ColumnConversion>,
+    cleaning: CellCleaning,
+) -> RowUid {
+    let mut errors = vec![];
+    let values: Vec<(ColumnUid, Variant)> = cells
+        .enumerate()
+        .filter_map(|(csv_idx, cell_value)| {
+            let col_uid = csv_to_col_uid.get(&csv_idx).copied()?;
+            let cell_value = cleaning.apply(cell_value);
+            if cleaning.empty_to_null && cell_value.is_empty() {
+                return Some((col_uid, Variant::Empty));
+            }
+            let (value, error) = convert_cell_value(column_types, col_uid, &cell_value);
+            if let Some(error) = error {
+                errors.push((col_uid, error));
+            }
+            Some((col_uid, value))
+        })
+        .collect();
+    let staged_uid = staging.insert_row(values);
+    for (col_uid, message) in errors {
+        report.push(ImportIssue {
+            line,
+            row_uid: Some(staged_uid),
+            col_uid: Some(col_uid),
+            message,
+        });
+    }
+    staged_uid
+}
+
+/// Parse all remaining rows into the staging backend.
+/// `line_offset` is the number of rows skipped before the header, used in error messages.
+#[allow(clippy::too_many_arguments)]
+fn parse_rows(
+    path: PathBuf,
+    mut rdr: csv::Reader<Box<dyn Read + Send>>,
+    csv_to_col_uid: HashMap<usize, ColumnUid>,
+    column_types: HashMap<ColumnUid, ColumnConversion>,
+    cleaning: CellCleaning,
+    ragged_rows: RaggedRowPolicy,
+    skip_last_rows: usize,
+    mut staging: VariantBackend,
+    line_offset: usize,
+    cx: Option<&JobContext<ParsedRows>>,
+) -> ParsedRows {
+    profile_function!();
+    let mut record = csv::StringRecord::new();
+    let mut staged_row_keys = HashMap::new();
+    let mut row_idx = 0;
+    let mut error = None;
+    let mut report = ImportReport::default();
+    let mut padded_cells = Vec::new();
+    let mut pending = VecDeque::with_capacity(skip_last_rows + 1);
+    // All header fields are mapped to some column
+    let columns = csv_to_col_uid.len();
+    loop {
+        if cx.map(|cx| cx.is_cancelled()).unwrap_or(false) {
+            break;
+        }
+        let line = rdr.position().line();
+        match rdr.read_record(&mut record) {
+            Ok(true) => {
+                // Rows are held back until it is known that they are not among the last ones
+                pending.push_back((line, core::mem::take(&mut record)));
+                if pending.len() <= skip_last_rows {
+                    continue;
+                }
+                let Some((line, row)) = pending.pop_front() else {
+                    continue;
+                };
+                let fields = row.len();
+                let ragged =
+                    (fields != columns).then(|| format!("{fields} fields instead of {columns}"));
+                if let Some(message) = &ragged {
+                    match ragged_rows {
+                        RaggedRowPolicy::Error => {
+                            let line = line as usize;
+                            report.push(ImportIssue {
+                                line,
+                                row_uid: None,
+                                col_uid: None,
+                                message: message.clone(),
+                            });
+                            let e = io::Error::new(io::ErrorKind::InvalidData, message.clone());
+                            error = Some((line, csv::Error::from(e)));
+                            break;
+                        }
+                        RaggedRowPolicy::SkipRow => {
+                            report.push(ImportIssue {
+                                line: line as usize,
+                                row_uid: None,
+                                col_uid: None,
+                                message: format!("{message}, skipped"),
+                            });
+                            continue;
+                        }
+                        RaggedRowPolicy::PadEmpty => {}
+                    }
+                }
+                let staged_uid = insert_row(
+                    &mut staging,
+                    &mut report,
+                    line as usize,
+                    row.iter(),
+                    &csv_to_col_uid,
+                    &column_types,
+                    cleaning,
+                );
+                if let Some(message) = ragged {
+                    let message = if fields < columns {
+                        padded_cells.extend((fields..columns).filter_map(|csv_idx| {
+                            let col_uid = *csv_to_col_uid.get(&csv_idx)?;
+                            Some(CellCoord {
+                                row_uid: staged_uid,
+                                col_uid,
+                            })
+                        }));
+                        format!("{message}, missing ones left empty")
+                    } else {
+                        format!("{message}, extra ones ignored")
+                    };
+                    report.push(ImportIssue {
+                        line: line as usize,
+                        row_uid: Some(staged_uid),
+                        col_uid: None,
+                        message,
+                    });
+                }
+                staged_row_keys.insert(CsvImporter::row_key(line, row.iter()), staged_uid);
+                row_idx += 1;
+                if let Some(cx) = cx {
+                    if row_idx % PROGRESS_EVERY_ROWS == 0 {
+                        cx.report_progress(rdr.position().byte(), row_idx);
+                    }
+                }
+                record = row;
+            }
+            Ok(false) => break,
+            Err(e) => {
+                let line = row_idx + pending.len() + 1 + line_offset;
+                if let Some(cx) = cx {
+                    cx.report_error(format!("Line {line}: {e}"));
+                }
+                report.push(ImportIssue {
+                    line,
+                    row_uid: None,
+                    col_uid: None,
+                    message: e.to_string(),
+                });
+                error = Some((line, e));
+                break;
+            }
+        }
+    }
+    ParsedRows {
+        path,
+        staging,
+        staged_row_keys,
+        error,
+        report,
+        padded_cells,
+    }
+}
+
 impl CsvImporter {
     pub fn new(required_columns: RequiredColumns) -> Self {
         CsvImporter {
             required_columns,
-            separator: Default::default(),
-            skip_first_rows: 0,
+            config: CsvImporterConfig::default(),
+            header_renames: HashMap::new(),
+            type_overrides: HashMap::new(),
+            load_in_background: false,
             state: State::default(),
         }
     }
 
-    pub fn set_separator(&mut self, separator: Separator) {
-        self.separator = separator;
+    pub fn set_config(&mut self, config: CsvImporterConfig) {
+        self.config = config;
+    }
+
+    /// Encoding used for the last loaded file, either detected or set in the config.
+    pub fn encoding(&self) -> Option<&'static Encoding> {
+        self.state.encoding
     }
 
-    pub fn skip_rows_on_load(&mut self, count: usize) {
-        self.skip_first_rows = count;
+    pub fn required_columns(&self) -> &RequiredColumns {
+        &self.required_columns
     }
 
+    pub fn required_columns_mut(&mut self) -> &mut RequiredColumns {
+        &mut self.required_columns
+    }
+
+    /// Remember types of additional columns changed in the column header, so that they are kept on reload.
+    /// Changing a column back to the inferred type removes its override.
+    pub fn sync_type_overrides(&mut self, backend: &VariantBackend) {
+        for column in &self.state.additional_columns {
+            let Some(ty) = backend.column_ty(column.col_uid) else {
+                continue;
+            };
+            if ty == column.ty {
+                continue;
+            }
+            if ty == column.detected_ty() {
+                self.type_overrides.remove(&column.header);
+            } else {
+                self.type_overrides.insert(column.header.clone(), ty);
+            }
+        }
+    }
+
+    /// Use the provided names instead of the original header names when matching required columns.
+    pub fn set_header_renames(&mut self, header_renames: HashMap<String, String>) {
+        self.header_renames = header_renames;
+    }
+
+    /// Parse rows on a background thread, so that UI stays responsive with very large files.
+    /// Call [poll](Self::poll) periodically to receive the result.
+    pub fn load_in_background(&mut self, enabled: bool) {
+        self.load_in_background = enabled;
+    }
+
+    /// Load a CSV file, the header is read right away and the rows are parsed on a background thread if enabled.
+    /// Current data stays in the backend until the whole file is parsed.
     pub fn load(&mut self, path: PathBuf, backend: &mut VariantBackend) {
+        profile_function!();
         trace!("CsvImporter: loading: {path:?}");
 
-        backend.remove_all_columns();
-        let separator = match self.determine_separator(&path) {
-            Some(value) => value,
-            None => {
-                self.state.status = IoStatus::UnknownSeparator;
+        self.state.job = None;
+        if self.state.loaded_path.as_ref() != Some(&path) {
+            self.state.row_keys.clear();
+        }
+        let sample = match read_sample(&path) {
+            Ok(sample) => sample,
+            Err(e) => {
+                self.state.status = IoStatus::IoError(e);
+                self.discard_loaded(backend);
                 return;
             }
         };
+        let separator = self.determine_separator(&sample);
+        let Some(encoding) = self.determine_encoding(&sample) else {
+            let label = self.config.encoding.clone().unwrap_or_default();
+            self.state.status = IoStatus::UnknownEncoding(label);
+            self.discard_loaded(backend);
+            return;
+        };
+        self.state.encoding = Some(encoding);
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) => {
+                self.state.status = IoStatus::IoError(e);
+                self.discard_loaded(backend);
+                return;
+            }
+        };
+        let total_bytes = file.metadata().map(|m| m.len()).unwrap_or(0);
+        // Rows are streamed from the file while they are parsed, only the sample is read up front
+        let file = BufReader::new(file);
+        let file: Box<dyn Read + Send> = if encoding == UTF_8 && !sample.starts_with(UTF_8_BOM) {
+            Box::new(file)
+        } else {
+            // Decoder also strips byte order mark
+            Box::new(
+                DecodeReaderBytesBuilder::new()
+                    .encoding(Some(encoding))
+                    .build(file),
+            )
+        };
 
-        match csv::ReaderBuilder::new()
-            .delimiter(separator)
-            .has_headers(false) // to be able to ignore first N rows
-            .flexible(true)
-            .from_path(path.clone())
-        {
-            Ok(mut rdr) => {
-                let mut records = rdr.records();
-                for _ in 0..self.skip_first_rows {
-                    records.next();
-                }
-                let csv_to_col_uid = match records.next() {
-                    Some(Ok(headers)) => {
-                        let headers: Vec<&str> = headers.iter().collect();
-                        let csv_to_col_uid = self.map_columns(headers, backend);
-                        // self.state.columns = columns;
-                        csv_to_col_uid
-                    }
-                    Some(Err(e)) => {
-                        self.state.status = IoStatus::ReaderError(e);
-                        return;
-                    }
-                    None => {
-                        self.state.status = IoStatus::Empty;
-                        return;
-                    }
-                };
-                for (row_idx, record) in records.enumerate() {
-                    match record {
-                        Ok(record) => {
-                            backend.insert_row(record.iter().enumerate().map(
-                                |(csv_idx, cell_value)| {
-                                    let col_uid = csv_to_col_uid.get(&csv_idx).copied().unwrap();
-                                    let value = self.convert_cell_value(col_uid, cell_value);
-                                    (col_uid, value)
-                                },
-                            ));
-                        }
-                        Err(e) => {
-                            self.state.status =
-                                IoStatus::ReaderErrorAtLine(row_idx + 1 + self.skip_first_rows, e);
-                            break;
-                        }
-                    }
-                }
+        let sample_rows = self.sample_rows(&sample, separator, encoding);
+        let mut rdr = self.reader_builder(separator).from_reader(file);
+        let mut record = csv::StringRecord::new();
+        for _ in 0..self.config.skip_first_rows {
+            let _ = rdr.read_record(&mut record);
+        }
+
+        // Parse into a staging backend first and then merge it into the current one,
+        // so that rows that didn't change keep their uids and user edits.
+        let mut staging = VariantBackend::new([]);
+        let csv_to_col_uid = match rdr.read_record(&mut record) {
+            Ok(true) => {
+                let headers: Vec<&str> = record.iter().collect();
+                self.map_columns(headers, &sample_rows, &mut staging)
+            }
+            Ok(false) => {
+                self.state.status = IoStatus::Empty;
+                self.discard_loaded(backend);
+                return;
             }
             Err(e) => {
                 self.state.status = IoStatus::ReaderError(e);
+                self.discard_loaded(backend);
+                return;
             }
+        };
+        self.state.status = IoStatus::Loaded(path.clone());
+        let column_types = self.column_types(self.config.number_locale);
+        let line_offset = self.config.skip_first_rows;
+        let cleaning = CellCleaning::new(&self.config);
+        let ragged_rows = self.config.ragged_rows;
+        let skip_last_rows = self.config.skip_last_rows;
+        if self.load_in_background {
+            self.state.job = Some(ImportJob::spawn(total_bytes, move |cx| {
+                parse_rows(
+                    path,
+                    rdr,
+                    csv_to_col_uid,
+                    column_types,
+                    cleaning,
+                    ragged_rows,
+                    skip_last_rows,
+                    staging,
+                    line_offset,
+                    Some(cx),
+                )
+            }));
+        } else {
+            let parsed = parse_rows(
+                path,
+                rdr,
+                csv_to_col_uid,
+                column_types,
+                cleaning,
+                ragged_rows,
+                skip_last_rows,
+                staging,
+                line_offset,
+                None,
+            );
+            self.finish_parsing(parsed, backend);
         }
-        self.state.status = IoStatus::Loaded(path);
-        backend.one_shot_flags_mut().column_info_updated = true;
-        backend.one_shot_flags_mut().reloaded = true;
     }
 
-    fn convert_cell_value(&self, col_uid: ColumnUid, value: &str) -> Variant {
-        if let Some(r) = self.required_columns.get(col_uid) {
-            Variant::from_str(value, r.ty)
+    /// Receive background parsing progress, returns true once a file is fully loaded into the backend.
+    pub fn poll(&mut self, backend: &mut VariantBackend) -> bool {
+        let Some(parsed) = self.state.job.as_mut().and_then(|job| job.poll()) else {
+            return false;
+        };
+        profile_scope!("merge_parsed_rows");
+        self.state.job = None;
+        self.finish_parsing(parsed, backend);
+        true
+    }
+
+    /// Background parsing progress, None if not loading.
+    pub fn progress(&self) -> Option<&ImportProgress> {
+        self.state.job.as_ref().map(|job| job.progress())
+    }
+
+    /// Stop loading a file, data loaded before stays in the backend.
+    pub fn cancel(&mut self) {
+        if let Some(job) = self.state.job.take() {
+            job.cancel();
+            self.state.status = IoStatus::Cancelled;
+        }
+    }
+
+    fn finish_parsing(&mut self, parsed: ParsedRows, backend: &mut VariantBackend) {
+        if let Some((line, e)) = parsed.error {
+            self.state.status = IoStatus::ReaderErrorAtLine(line, e);
+        }
+        let row_map =
+            self.merge_staging(parsed.path, parsed.staging, parsed.staged_row_keys, backend);
+        let mut report = parsed.report;
+        report.remap_rows(&row_map);
+        self.state.report = report;
+        self.state.padded_cells = parsed
+            .padded_cells
+            .into_iter()
+            .filter_map(|coord| {
+                let row_uid = *row_map.get(&coord.row_uid)?;
+                Some(CellCoord {
+                    row_uid,
+                    col_uid: coord.col_uid,
+                })
+            })
+            .collect();
+    }
+
+    /// Required column types with number locales and date formats, `default_locale` is used for columns without an override.
+    fn column_types(&self, default_locale: NumberLocale) -> HashMap<ColumnUid, ColumnConversion> {
+        self.required_columns
+            .required_columns
+            .iter()
+            .map(|(col_uid, c)| {
+                let conversion = ColumnConversion {
+                    ty: c.ty,
+                    number_locale: c.number_locale.unwrap_or(default_locale),
+                    date_format: c.date_format.clone(),
+                    transforms: c.transforms.clone(),
+                };
+                (*col_uid, conversion)
+            })
+            .chain(self.state.additional_columns.iter().filter_map(|column| {
+                let date_format = column
+                    .inferred
+                    .and_then(|inferred| inferred.date_format)
+                    .filter(|_| column.ty == column.detected_ty());
+                if column.ty == VariantTy::Str && date_format.is_none() {
+                    return None;
+                }
+                let conversion = ColumnConversion {
+                    ty: column.ty,
+                    number_locale: default_locale,
+                    date_format: date_format.map(|format| format.to_string()),
+                    transforms: vec![],
+                };
+                Some((column.col_uid, conversion))
+            }))
+            .collect()
+    }
+
+    /// Load one sheet of a spreadsheet file, mapping its columns the same way as for CSV files.
+    pub fn load_sheet(&mut self, path: PathBuf, sheet: &str, backend: &mut VariantBackend) {
+        profile_function!();
+        trace!("CsvImporter: loading sheet {sheet} of: {path:?}");
+
+        let range = match xls::read_sheet(&path, sheet) {
+            Ok(range) => range,
+            Err(e) => {
+                self.state.status = IoStatus::SpreadsheetError(e);
+                self.discard_loaded(backend);
+                return;
+            }
+        };
+        let all_rows: Vec<Vec<String>> = range
+            .rows()
+            .map(|row| row.iter().map(xls::cell_to_string).collect())
+            .collect();
+        // Numeric cells are already typed in spreadsheets, only explicit per column overrides are applied
+        self.load_rows(path, all_rows, true, NumberLocale::Plain, backend);
+    }
+
+    /// Load rows already split into cells, e.g. from a spreadsheet or a fixed width text file,
+    /// mapping their columns the same way as for CSV files.
+    /// Without a header row, columns are named "Column 1", "Column 2" and so on.
+    pub fn load_rows(
+        &mut self,
+        path: PathBuf,
+        all_rows: Vec<Vec<String>>,
+        has_headers: bool,
+        default_locale: NumberLocale,
+        backend: &mut VariantBackend,
+    ) {
+        self.state.job = None;
+        if self.state.loaded_path.as_ref() != Some(&path) {
+            self.state.row_keys.clear();
+        }
+        let data_start = self.config.skip_first_rows + usize::from(has_headers);
+        let headers: Option<Vec<String>> = if has_headers {
+            all_rows.get(self.config.skip_first_rows).cloned()
         } else {
-            Variant::Str(value.to_string())
+            let width = all_rows.iter().skip(data_start).map(|row| row.len()).max();
+            width.map(|width| (1..=width).map(|n| format!("Column {n}")).collect())
+        };
+        let Some(headers) = headers else {
+            self.state.status = IoStatus::Empty;
+            self.discard_loaded(backend);
+            return;
+        };
+
+        let mut staging = VariantBackend::new([]);
+        let headers: Vec<&str> = headers.iter().map(|h| h.as_str()).collect();
+        let sample_rows: Vec<Vec<String>> = all_rows
+            .iter()
+            .skip(data_start)
+            .take(INFER_SAMPLE_ROWS)
+            .cloned()
+            .collect();
+        let csv_to_col_uid = self.map_columns(headers, &sample_rows, &mut staging);
+        self.state.status = IoStatus::Loaded(path.clone());
+        let column_types = self.column_types(default_locale);
+        let mut staged_row_keys = HashMap::new();
+        let mut report = ImportReport::default();
+        let data_rows = all_rows
+            .len()
+            .saturating_sub(data_start + self.config.skip_last_rows);
+        for (line, row) in all_rows.iter().enumerate().skip(data_start).take(data_rows) {
+            let staged_uid = insert_row(
+                &mut staging,
+                &mut report,
+                line + 1,
+                row.iter().map(|s| s.as_str()),
+                &csv_to_col_uid,
+                &column_types,
+                CellCleaning::new(&self.config),
+            );
+            staged_row_keys.insert(
+                Self::row_key(line as u64, row.iter().map(|s| s.as_str())),
+                staged_uid,
+            );
+        }
+
+        let row_map = self.merge_staging(path, staging, staged_row_keys, backend);
+        report.remap_rows(&row_map);
+        self.state.report = report;
+        // Cells missing in short rows are only tracked for CSV files
+        self.state.padded_cells.clear();
+    }
+
+    fn merge_staging(
+        &mut self,
+        path: PathBuf,
+        staging: VariantBackend,
+        staged_row_keys: HashMap<u64, RowUid>,
+        backend: &mut VariantBackend,
+    ) -> HashMap<RowUid, RowUid> {
+        let matched_rows = staged_row_keys
+            .iter()
+            .filter_map(|(key, staged_uid)| {
+                self.state
+                    .row_keys
+                    .get(key)
+                    .map(|row_uid| (*staged_uid, *row_uid))
+            })
+            .collect();
+        let row_map = backend.merge_reloaded(staging, &matched_rows);
+        self.state.row_keys = staged_row_keys
+            .into_iter()
+            .filter_map(|(key, staged_uid)| row_map.get(&staged_uid).map(|row_uid| (key, *row_uid)))
+            .collect();
+        self.state.loaded_path = Some(path);
+        backend.one_shot_flags_mut().reloaded = true;
+        row_map
+    }
+
+    /// Row identity across reloads: the same line with the same contents is considered to be the same row.
+    fn row_key<'a>(line: u64, fields: impl IntoIterator<Item = &'a str>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        line.hash(&mut hasher);
+        for field in fields {
+            field.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Forget the loaded file and remove its columns from the backend.
+    pub fn discard_loaded(&mut self, backend: &mut VariantBackend) {
+        self.state.job = None;
+        self.state.report = ImportReport::default();
+        self.state.padded_cells.clear();
+        backend.remove_all_columns();
+        self.state.row_keys.clear();
+        self.state.loaded_path = None;
+    }
+
+    fn reader_builder(&self, separator: u8) -> csv::ReaderBuilder {
+        let mut builder = csv::ReaderBuilder::new();
+        builder
+            .delimiter(separator)
+            .quote(self.config.quote)
+            .escape(self.config.escape)
+            .double_quote(self.config.escape.is_none())
+            .comment(self.config.comment)
+            .has_headers(false) // to be able to ignore first N rows
+            .flexible(true);
+        builder
+    }
+
+    /// First rows after the header found in the sample, used to infer column types.
+    fn sample_rows(
+        &self,
+        sample: &[u8],
+        separator: u8,
+        encoding: &'static Encoding,
+    ) -> Vec<Vec<String>> {
+        if !self.config.infer_types {
+            return Vec::new();
+        }
+        let (text, _) = encoding.decode_with_bom_removal(sample);
+        let mut rows: Vec<Vec<String>> = self
+            .reader_builder(separator)
+            .from_reader(text.as_bytes())
+            .records()
+            .skip(self.config.skip_first_rows + 1)
+            .take(INFER_SAMPLE_ROWS + 1)
+            .map_while(|record| record.ok())
+            .map(|record| record.iter().map(|v| v.to_string()).collect())
+            .collect();
+        // Last row is likely cut in the middle if the file is larger than the sample
+        if sample.len() as u64 == SAMPLE_BYTES || rows.len() > INFER_SAMPLE_ROWS {
+            rows.pop();
+        }
+        rows
+    }
+
+    /// First rows of a CSV file or a sheet as they are, before skipping rows and matching the header.
+    /// Empty if the file can't be read.
+    pub fn preview_rows(
+        &self,
+        path: &PathBuf,
+        sheet: Option<&str>,
+        count: usize,
+    ) -> Vec<Vec<String>> {
+        if let Some(sheet) = sheet {
+            let Ok(range) = xls::read_sheet(path, sheet) else {
+                return Vec::new();
+            };
+            return range
+                .rows()
+                .take(count)
+                .map(|row| row.iter().map(xls::cell_to_string).collect())
+                .collect();
+        }
+        let Ok(sample) = read_sample(path) else {
+            return Vec::new();
+        };
+        let separator = self.determine_separator(&sample);
+        let Some(encoding) = self.determine_encoding(&sample) else {
+            return Vec::new();
+        };
+        let (text, _) = encoding.decode_with_bom_removal(&sample);
+        self.reader_builder(separator)
+            .from_reader(text.as_bytes())
+            .records()
+            .take(count)
+            .map_while(|record| record.ok())
+            .map(|record| record.iter().map(|v| v.to_string()).collect())
+            .collect()
+    }
+
+    /// Encoding set in the config or detected from the sample, None if the configured label is unknown.
+    fn determine_encoding(&self, sample: &[u8]) -> Option<&'static Encoding> {
+        match &self.config.encoding {
+            Some(label) => Encoding::for_label(label.as_bytes()),
+            None => Some(detect_encoding(sample)),
         }
     }
 
-    fn determine_separator(&mut self, path: &PathBuf) -> Option<u8> {
-        Some(match self.separator {
+    fn determine_separator(&self, sample: &[u8]) -> u8 {
+        match self.config.separator {
             Separator::Auto => {
-                let file = match File::open(path) {
-                    Ok(file) => file,
-                    Err(e) => {
-                        self.state.status = IoStatus::IoError(e);
-                        return None;
-                    }
-                };
-                let reader = BufReader::new(file);
                 let mut counts: [(usize, u8); 3] = [(0, b','), (1, b'\t'), (2, b';')];
-                for b in reader.bytes() {
-                    let Ok(b) = b else {
-                        break;
-                    };
+                for b in sample {
                     match b {
                         b',' => counts[0].0 += 1,
                         b'\t' => counts[1].0 += 1,
@@ -186,41 +1132,81 @@ impl CsvImporter {
             Separator::Comma => b',',
             Separator::Tab => b'\t',
             Separator::Semicolon => b';',
-        })
+        }
     }
 
     fn map_columns(
         &mut self,
         csv_columns: Vec<&str>,
+        sample_rows: &[Vec<String>],
         backend: &mut VariantBackend,
     ) -> HashMap<usize, ColumnUid> {
         // let mut columns = HashMap::new();
         let mut csv_to_col_uid = HashMap::new();
 
         // Place required columns first, if match is not found in a loaded file - map to empty columns
-        let mapped_columns = self.required_columns.map_columns(&csv_columns);
+        let renamed_columns: Vec<&str> = csv_columns
+            .iter()
+            .map(|c| self.header_renames.get(*c).map(|n| n.as_str()).unwrap_or(c))
+            .collect();
+        let mapped_columns = self.required_columns.map_columns(&renamed_columns);
         let mut next_absent_col_uid = ColumnUid(mapped_columns.len() as u32);
+        self.state.unmapped_required.clear();
+        self.state.matched_headers.clear();
         for ((col_uid, col), csv_col_idx) in mapped_columns {
+            if csv_col_idx.is_none() {
+                self.state.unmapped_required.push(col_uid);
+            }
             if let Some(csv_col_idx) = csv_col_idx {
+                self.state
+                    .matched_headers
+                    .insert(csv_columns[csv_col_idx].to_string(), col.name.clone());
                 if csv_to_col_uid.contains_key(&csv_col_idx) {
                     warn!("Double match for column: {}", col.name);
+                    self.state
+                        .warnings
+                        .push(format!("Double match for column: {}", col.name));
                 }
                 csv_to_col_uid.insert(csv_col_idx, col_uid);
             }
             backend.insert_column(col_uid, col.name.clone(), col.ty, col.default.clone());
+            backend.set_column_renamable(col_uid, false);
+            backend.set_column_required(col_uid, true);
+            backend.set_column_mask(col_uid, col.mask.clone());
+            backend.set_column_hint(col_uid, col.description.clone(), col.example.clone());
+            backend.set_column_synonyms(col_uid, col.synonyms.clone());
         }
 
         // Put all additional columns to the right of required ones
+        self.state.additional_columns.clear();
         for (csv_idx, column) in csv_columns.iter().enumerate() {
             if !csv_to_col_uid.contains_key(&csv_idx) {
-                csv_to_col_uid.insert(csv_idx, next_absent_col_uid);
-                backend.insert_column(
-                    next_absent_col_uid,
-                    column.to_string(),
-                    VariantTy::Str,
-                    None,
-                );
-                next_absent_col_uid = ColumnUid(next_absent_col_uid.0 + 1);
+                let col_uid = next_absent_col_uid;
+                csv_to_col_uid.insert(csv_idx, col_uid);
+                let inferred = self.config.infer_types.then(|| {
+                    let values = sample_rows
+                        .iter()
+                        .map(|row| row.get(csv_idx).map(|v| v.as_str()).unwrap_or_default());
+                    infer_type(values, self.config.number_locale)
+                });
+                let ty = self
+                    .type_overrides
+                    .get(*column)
+                    .copied()
+                    .or(inferred.map(|inferred| inferred.ty))
+                    .unwrap_or(VariantTy::Str);
+                backend.insert_column(col_uid, column.to_string(), ty, None);
+                if let Some(inferred) = inferred {
+                    // Shown when hovering the column header, where the type can be changed
+                    backend.set_column_hint(col_uid, Some(format!("Detected {inferred}")), None);
+                }
+                self.state.additional_columns.push(AdditionalColumn {
+                    col_uid,
+                    header: column.to_string(),
+                    inferred,
+                    ty,
+                });
+                next_absent_col_uid = ColumnUid(col_uid.0 + 1);
             }
         }
 
@@ -230,4 +1216,87 @@ impl CsvImporter {
     pub fn status(&self) -> &IoStatus {
         &self.state.status
     }
+
+    /// Problems found while loading the last file.
+    pub fn report(&self) -> &ImportReport {
+        &self.state.report
+    }
+
+    /// Cells missing in rows shorter than the header in the last loaded file.
+    pub fn padded_cells(&self) -> &[CellCoord] {
+        &self.state.padded_cells
+    }
+
+    /// Header name -> name of the required column it was matched to in the last loaded file.
+    pub fn matched_headers(&self) -> &HashMap<String, String> {
+        &self.state.matched_headers
+    }
+
+    /// Required columns not found in the last loaded file or marked as not used in `backend`.
+    pub fn missing_required(&self, backend: &impl TableBackend) -> Vec<ColumnUid> {
+        let used: HashSet<ColumnUid> = backend.used_columns().collect();
+        self.required_columns
+            .required_columns
+            .iter()
+            .map(|(col_uid, _)| *col_uid)
+            .filter(|col_uid| {
+                self.state.unmapped_required.contains(col_uid) || !used.contains(col_uid)
+            })
+            .collect()
+    }
+
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        core::mem::take(&mut self.state.warnings)
+    }
+
+    pub fn set_status(&mut self, status: IoStatus) {
+        self.state.status = status;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{infer_type, NumberLocale};
+    use rvariant::VariantTy;
+
+    fn infer(values: &[&str]) -> (VariantTy, Option<&'static str>, f32) {
+        let inferred = infer_type(values.iter().copied(), NumberLocale::Plain);
+        (inferred.ty, inferred.date_format, inferred.confidence)
+    }
+
+    #[test]
+    fn infer_numbers_and_bools() {
+        assert_eq!(infer(&["1", "", " 22 "]), (VariantTy::U32, None, 1.0));
+        assert_eq!(infer(&["1", "-5"]), (VariantTy::I64, None, 1.0));
+        assert_eq!(infer(&["1.5", "2"]), (VariantTy::F64, None, 1.0));
+        assert_eq!(infer(&["True", "false"]), (VariantTy::Bool, None, 1.0));
+        assert_eq!(infer(&["inf", "NaN"]), (VariantTy::Str, None, 1.0));
+    }
+
+    #[test]
+    fn infer_dates() {
+        assert_eq!(
+            infer(&["2024-01-31", "2024-02-01"]),
+            (VariantTy::Str, Some("%Y-%m-%d"), 1.0)
+        );
+        assert_eq!(
+            infer(&["31.01.2024", "01.02.2024"]),
+            (VariantTy::Str, Some("%d.%m.%Y"), 1.0)
+        );
+        // 31 is not a month
+        assert_eq!(
+            infer(&["01/31/2024", "02/01/2024"]),
+            (VariantTy::Str, Some("%m/%d/%Y"), 1.0)
+        );
+    }
+
+    #[test]
+    fn confidence_is_share_of_matching_values() {
+        let mut values = vec!["7"; 19];
+        values.push("n/a");
+        assert_eq!(infer(&values), (VariantTy::U32, None, 0.95));
+        values.push("?");
+        assert_eq!(infer(&values).0, VariantTy::Str);
+        assert_eq!(infer(&["", " "]), (VariantTy::Str, None, 0.0));
+    }
 }
@@ -1,76 +1,395 @@
-use super::csv::{CsvImporter, Separator};
+use super::csv::{
+    CsvImporter, CsvImporterConfig, IoStatus, NumberLocale, RaggedRowPolicy, Separator,
+};
+use super::file_picker::{FilePicker, FilePickerState};
+use super::import_job::ImportProgress;
+use super::import_report::{notify_warnings, report_ui, ImportReport, ImportValidation};
+use super::mapping_profile::MappingProfile;
+use super::xls;
+use crate::backend::{CellCoord, ColumnUid, RowUid, TableBackend};
+use crate::backends::duplicates::DuplicateRows;
 use crate::backends::variant::VariantBackend;
+use crate::config_version::{ConfigMigrations, VersionedConfig};
+use crate::table_view::{CardView, Lint, TableViewState, ToastKind, ToolColumn};
+use crate::util::variant_to_string;
 use crate::{RequiredColumns, TableView};
-use egui::{RichText, Slider, Ui};
+use egui::{Button, ProgressBar, RichText, ScrollArea, Stroke, TextEdit, Ui, Widget};
+use encoding_rs::{
+    Encoding, BIG5, EUC_JP, EUC_KR, GBK, ISO_8859_2, KOI8_R, SHIFT_JIS, UTF_16BE, UTF_16LE, UTF_8,
+    WINDOWS_1250, WINDOWS_1251, WINDOWS_1252,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use strum::IntoEnumIterator;
 
+/// Encodings offered in the UI, any other encoding supported by [encoding_rs] can be set through the config.
+const ENCODINGS: &[&Encoding] = &[
+    UTF_8,
+    UTF_16LE,
+    UTF_16BE,
+    WINDOWS_1252,
+    WINDOWS_1251,
+    WINDOWS_1250,
+    ISO_8859_2,
+    KOI8_R,
+    SHIFT_JIS,
+    EUC_JP,
+    EUC_KR,
+    GBK,
+    BIG5,
+];
+
+/// Rows of the file shown when picking the header row.
+const HEADER_PREVIEW_ROWS: usize = 20;
+
+const QUOTES: &[(Option<u8>, &str)] = &[(Some(b'"'), "\""), (Some(b'\''), "'")];
+const ESCAPES: &[(Option<u8>, &str)] = &[(None, "Doubled quote"), (Some(b'\\'), "\\")];
+const COMMENTS: &[(Option<u8>, &str)] = &[
+    (None, "None"),
+    (Some(b'#'), "#"),
+    (Some(b';'), ";"),
+    (Some(b'%'), "%"),
+];
+
 pub struct CsvXlsImporter {
     csv: CsvImporter,
     backend: VariantBackend,
     table_view: TableView,
-    state: PersistentState,
+    config: CsvXlsImporterConfig,
     picked_file: Option<PathBuf>,
+    file_picker: FilePickerState,
+    /// File currently shown in the table view, if any.
+    loaded_file: Option<PathBuf>,
+    /// Sheets of the picked file, if it is a spreadsheet.
+    sheet_names: Vec<String>,
+    /// Header renames of the applied mapping profile, renames made in the table take precedence.
+    profile_renames: HashMap<String, String>,
+    /// Name of the last applied or saved mapping profile.
+    active_profile: Option<String>,
+    /// Name typed in the mapping profiles dropdown.
+    new_profile_name: String,
+    /// Rows repeating an earlier row, according to the config.
+    duplicate_rows: DuplicateRows,
+    /// Cells of each duplicate row marked in the table view and how, to unmark them once resolved.
+    marked_duplicates: HashMap<RowUid, Vec<(CellCoord, Lint)>>,
+    /// All duplicates are found again on the next frame, also done whenever rows are inserted or removed.
+    duplicates_outdated: bool,
+    /// Cells missing in short rows of the loaded file and how they are marked in the table view.
+    marked_padded: Vec<(CellCoord, Lint)>,
+    /// First rows of the picked file are shown to choose the header row from.
+    header_picker_open: bool,
+    /// Rows shown in the header picker, read again after options change.
+    header_preview: Option<Vec<Vec<String>>>,
+}
+
+/// Which rows are considered duplicates of an earlier row of the loaded file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DuplicateKey {
+    #[default]
+    Off,
+    /// All used columns are equal.
+    FullRow,
+    /// Required columns with these names are equal, e.g. a part number.
+    Columns(Vec<String>),
 }
 
+/// Importer options, can be persisted by the host application.
+/// Missing fields take their defaults when deserializing, configs of older versions are upgraded in
+/// [load_config](CsvXlsImporter::load_config).
 // #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Serialize, Deserialize)]
-struct PersistentState {
-    separator: Separator,
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvXlsImporterConfig {
+    /// 0 for configs stored before versioning, see [VersionedConfig].
+    #[serde(default)]
+    version: u32,
+    #[serde(flatten)]
+    csv: CsvImporterConfig,
     has_headers: bool,
-    skip_first_rows: usize,
+    /// Last selected sheet, used when loading spreadsheets.
+    #[serde(default)]
+    sheet: Option<String>,
+    /// Required column name -> synonyms edited by the user.
+    #[serde(default)]
+    synonyms: HashMap<String, Vec<String>>,
+    /// View adjustments made by the user for each of the loaded files.
+    #[serde(default)]
+    per_file: HashMap<PathBuf, TableViewState>,
+    #[serde(default)]
+    file_picker: FilePicker,
+    /// Mappings and options saved by the user for files of different sources.
+    #[serde(default)]
+    mapping_profiles: Vec<MappingProfile>,
+    #[serde(default)]
+    duplicates: DuplicateKey,
 }
 
-impl Default for PersistentState {
+impl Default for CsvXlsImporterConfig {
     fn default() -> Self {
-        PersistentState {
-            separator: Separator::default(),
+        CsvXlsImporterConfig {
+            version: Self::VERSION,
+            csv: CsvImporterConfig::default(),
             has_headers: true,
-            skip_first_rows: 0,
+            sheet: None,
+            synonyms: HashMap::new(),
+            per_file: HashMap::new(),
+            file_picker: FilePicker::default(),
+            mapping_profiles: Vec::new(),
+            duplicates: DuplicateKey::Off,
+        }
+    }
+}
+
+impl VersionedConfig for CsvXlsImporterConfig {
+    const VERSION: u32 = 1;
+
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+
+    fn migrations() -> ConfigMigrations<Self> {
+        ConfigMigrations::new().step(1, |config| {
+            for view_state in config.per_file.values_mut() {
+                view_state.migrate();
+            }
+        })
+    }
+}
+
+impl CsvXlsImporterConfig {
+    pub fn separator(mut self, separator: Separator) -> Self {
+        self.csv.separator = separator;
+        self
+    }
+
+    pub fn has_headers(self, has_headers: bool) -> Self {
+        CsvXlsImporterConfig {
+            has_headers,
+            ..self
+        }
+    }
+
+    pub fn skip_first_rows(mut self, skip_first_rows: usize) -> Self {
+        self.csv.skip_first_rows = skip_first_rows;
+        self
+    }
+
+    /// Leave out trailing rows, e.g. totals or disclaimers at the end of exported reports.
+    pub fn skip_last_rows(mut self, skip_last_rows: usize) -> Self {
+        self.csv.skip_last_rows = skip_last_rows;
+        self
+    }
+
+    /// Use the encoding with the given label (e.g. "windows-1252") instead of detecting it.
+    pub fn encoding(mut self, label: impl AsRef<str>) -> Self {
+        self.csv.encoding = Some(label.as_ref().to_string());
+        self
+    }
+
+    pub fn number_locale(mut self, number_locale: NumberLocale) -> Self {
+        self.csv.number_locale = number_locale;
+        self
+    }
+
+    /// Mark rows repeating an earlier row and offer to skip or remove them.
+    pub fn detect_duplicates(self, duplicates: DuplicateKey) -> Self {
+        CsvXlsImporterConfig { duplicates, ..self }
+    }
+
+    /// Remove leading and trailing whitespace of all cells while loading.
+    pub fn trim(mut self, trim: bool) -> Self {
+        self.csv.trim = trim;
+        self
+    }
+
+    /// Replace runs of whitespace inside cells with a single space while loading.
+    pub fn collapse_whitespace(mut self, collapse_whitespace: bool) -> Self {
+        self.csv.collapse_whitespace = collapse_whitespace;
+        self
+    }
+
+    /// Load empty cells as empty values instead of empty strings.
+    pub fn empty_to_null(mut self, empty_to_null: bool) -> Self {
+        self.csv.empty_to_null = empty_to_null;
+        self
+    }
+
+    /// Handling of rows with more or fewer fields than the header.
+    pub fn ragged_rows(mut self, ragged_rows: RaggedRowPolicy) -> Self {
+        self.csv.ragged_rows = ragged_rows;
+        self
+    }
+
+    pub fn quote(mut self, quote: u8) -> Self {
+        self.csv.quote = quote;
+        self
+    }
+
+    /// Escape character for quotes inside quoted fields, quotes are expected to be doubled by default.
+    pub fn escape(mut self, escape: u8) -> Self {
+        self.csv.escape = Some(escape);
+        self
+    }
+
+    /// Ignore lines starting with the given character.
+    pub fn comment(mut self, comment: u8) -> Self {
+        self.csv.comment = Some(comment);
+        self
+    }
+
+    pub fn file_picker(mut self, file_picker: FilePicker) -> Self {
+        self.file_picker = file_picker;
+        self
+    }
+
+    pub fn sheet(self, sheet: impl AsRef<str>) -> Self {
+        CsvXlsImporterConfig {
+            sheet: Some(sheet.as_ref().to_string()),
+            ..self
         }
     }
 }
 
 impl CsvXlsImporter {
     pub fn new(required_columns: RequiredColumns) -> Self {
-        let backend = VariantBackend::new(
+        let mut backend = VariantBackend::new(
             required_columns
                 .required_columns
                 .iter()
                 .map(|(_, c)| (c.name.clone(), c.ty, c.default.clone())),
         );
+        for (col_uid, c) in &required_columns.required_columns {
+            backend.set_column_synonyms(*col_uid, c.synonyms.clone());
+            backend.set_column_required(*col_uid, true);
+            backend.set_column_mask(*col_uid, c.mask.clone());
+            backend.set_column_hint(*col_uid, c.description.clone(), c.example.clone());
+        }
+        let mut csv = CsvImporter::new(required_columns);
+        csv.load_in_background(true);
         CsvXlsImporter {
-            csv: CsvImporter::new(required_columns),
+            csv,
             backend,
-            table_view: TableView::new(),
-            state: PersistentState::default(),
+            table_view: TableView::builder()
+                .tool_column_position(ToolColumn::Left)
+                .card_view(CardView::BelowWidth(480.))
+                .build(),
+            config: CsvXlsImporterConfig::default(),
             picked_file: None,
+            file_picker: FilePickerState::default(),
+            loaded_file: None,
+            sheet_names: Vec::new(),
+            profile_renames: HashMap::new(),
+            active_profile: None,
+            new_profile_name: String::new(),
+            duplicate_rows: DuplicateRows::default(),
+            marked_duplicates: HashMap::new(),
+            duplicates_outdated: false,
+            marked_padded: vec![],
+            header_picker_open: false,
+            header_preview: None,
+        }
+    }
+
+    /// Parse CSV files on a background thread (default), otherwise UI is blocked while loading.
+    pub fn load_in_background(&mut self, enabled: bool) {
+        self.csv.load_in_background(enabled);
+    }
+
+    /// Rows are no longer loaded in chunks, any chunk size parses files on a background thread,
+    /// None loads them at once, blocking the UI.
+    #[deprecated(note = "use load_in_background instead")]
+    pub fn set_chunk_size(&mut self, rows: Option<usize>) {
+        self.load_in_background(rows.is_some());
+    }
+
+    /// Receive rows parsed on a background thread, called by [show](Self::show).
+    /// Only needed to finish loading while the importer is not shown, returns true once a file is loaded.
+    pub fn poll(&mut self) -> bool {
+        if self.csv.poll(&mut self.backend) {
+            self.finish_load();
+            true
+        } else {
+            false
         }
     }
 
+    /// Progress of a file being loaded on a background thread, None if not loading.
+    pub fn progress(&self) -> Option<&ImportProgress> {
+        self.csv.progress()
+    }
+
     pub fn show(&mut self, ui: &mut Ui) {
         ui.horizontal_wrapped(|ui| {
             ui.label(RichText::new("CSV Options").strong().monospace());
 
+            let mut picked = None;
             if ui.button("Open file…").clicked() {
-                if let Some(path) = rfd::FileDialog::new().pick_file() {
-                    self.picked_file = Some(path);
-                    self.try_load();
-                }
+                let initial = self.picked_file.clone();
+                picked = self.file_picker.open(self.config.file_picker, initial);
+            }
+            if let Some(path) = picked.or_else(|| self.file_picker.show(ui.ctx())) {
+                self.picked_file = Some(path);
+                self.refresh_sheet_names();
+                self.try_load();
             }
             if ui.button("Reload").clicked() {
+                self.refresh_sheet_names();
                 self.try_load();
             }
+            if ui
+                .button("Copy as Rust code")
+                .on_hover_text("Copy current required columns and options as Rust code")
+                .clicked()
+            {
+                let code = self.rust_code();
+                ui.ctx().copy_text(code);
+                self.table_view
+                    .notify(ToastKind::Info, "Importer setup copied as Rust code");
+            }
+            if ui
+                .button("Generate struct")
+                .on_hover_text("Copy a Rust struct for the loaded columns, to read rows with VariantBackend::extract_as")
+                .clicked()
+            {
+                ui.ctx().copy_text(self.backend.struct_code("Row"));
+                self.table_view
+                    .notify(ToastKind::Info, "Row struct copied as Rust code");
+            }
+            self.table_view.layout_presets_ui(ui);
+            self.mapping_profiles_ui(ui);
             ui.separator();
 
+            if self.sheet_names.len() > 1 {
+                let sheet_changed = egui::ComboBox::from_label("Sheet")
+                    .selected_text(self.config.sheet.as_deref().unwrap_or_default())
+                    .show_ui(ui, |ui| {
+                        let mut changed = false;
+                        for name in &self.sheet_names {
+                            changed |= ui
+                                .selectable_value(&mut self.config.sheet, Some(name.clone()), name)
+                                .changed();
+                        }
+                        changed
+                    })
+                    .inner;
+                if let Some(true) = sheet_changed {
+                    self.try_load();
+                }
+                ui.separator();
+            }
+
             let delim_changed = egui::ComboBox::from_label("Separator")
-                .selected_text(format!("{}", self.state.separator))
+                .selected_text(format!("{}", self.config.csv.separator))
                 .show_ui(ui, |ui| {
                     let mut changed = false;
                     for s in Separator::iter() {
                         changed |= ui
-                            .selectable_value(&mut self.state.separator, s, s.to_string())
+                            .selectable_value(&mut self.config.csv.separator, s, s.to_string())
                             .changed();
                     }
                     changed
@@ -79,43 +398,721 @@ impl CsvXlsImporter {
             if let Some(true) = delim_changed {
                 self.try_load();
             }
+            if self.sheet_names.is_empty() {
+                let mut changed = self.encoding_combo_box(ui);
+                let mut quote = Some(self.config.csv.quote);
+                if char_combo_box(ui, "Quote", &mut quote, QUOTES) {
+                    self.config.csv.quote = quote.unwrap_or(b'"');
+                    changed = true;
+                }
+                changed |= char_combo_box(ui, "Escape", &mut self.config.csv.escape, ESCAPES);
+                changed |= char_combo_box(ui, "Comment", &mut self.config.csv.comment, COMMENTS);
+                let locale = egui::ComboBox::from_label("Numbers")
+                    .selected_text(self.config.csv.number_locale.to_string())
+                    .show_ui(ui, |ui| {
+                        let mut changed = false;
+                        for l in NumberLocale::iter() {
+                            changed |= ui
+                                .selectable_value(
+                                    &mut self.config.csv.number_locale,
+                                    l,
+                                    l.to_string(),
+                                )
+                                .changed();
+                        }
+                        changed
+                    });
+                locale
+                    .response
+                    .on_hover_text("Thousands and decimal separators used in numeric columns");
+                changed |= locale.inner.unwrap_or(false);
+                let ragged = egui::ComboBox::from_label("Ragged rows")
+                    .selected_text(self.config.csv.ragged_rows.to_string())
+                    .show_ui(ui, |ui| {
+                        let mut changed = false;
+                        for p in RaggedRowPolicy::iter() {
+                            changed |= ui
+                                .selectable_value(&mut self.config.csv.ragged_rows, p, p.to_string())
+                                .changed();
+                        }
+                        changed
+                    });
+                ragged
+                    .response
+                    .on_hover_text("Rows with more or fewer fields than the header row");
+                changed |= ragged.inner.unwrap_or(false);
+                if changed {
+                    self.try_load();
+                }
+            }
+            if ui
+                .checkbox(&mut self.config.has_headers, "Has header row")
+                .changed()
+            {
+                self.try_load();
+            }
             if ui
-                .checkbox(&mut self.state.has_headers, "Has header row")
+                .checkbox(&mut self.config.csv.infer_types, "Detect types")
+                .on_hover_text("Detect types of additional columns from the first rows")
                 .changed()
             {
                 self.try_load();
             }
+            let mut cleaning_changed = ui
+                .checkbox(&mut self.config.csv.trim, "Trim")
+                .on_hover_text("Remove whitespace around cell values")
+                .changed();
+            cleaning_changed |= ui
+                .checkbox(&mut self.config.csv.collapse_whitespace, "Collapse spaces")
+                .on_hover_text("Replace repeated whitespace inside cells with a single space")
+                .changed();
+            cleaning_changed |= ui
+                .checkbox(&mut self.config.csv.empty_to_null, "Empty as no value")
+                .on_hover_text("Load empty text cells as empty values instead of empty strings")
+                .changed();
+            if cleaning_changed {
+                self.try_load();
+            }
 
             ui.separator();
-            if ui
-                .add(Slider::new(&mut self.state.skip_first_rows, 0..=10).text("Skip first rows"))
-                .on_hover_text("If file contains additional rows before header row, skip them")
+            let header_row = format!("Header row: {}", self.config.csv.skip_first_rows + 1);
+            ui.toggle_value(&mut self.header_picker_open, header_row)
+                .on_hover_text("Pick the header row from the first rows of the file, rows above it are skipped");
+            ui.label("Skip last rows");
+            if egui::DragValue::new(&mut self.config.csv.skip_last_rows)
+                .range(0..=100)
+                .ui(ui)
+                .on_hover_text("If file contains totals or other rows after the data, skip them")
                 .changed()
             {
                 self.try_load();
             }
             ui.separator();
         });
+        self.show_header_picker(ui);
+        self.poll();
+        if let Some(progress) = self.csv.progress() {
+            let text = format!(
+                "{} rows, {:.0}%",
+                progress.rows_parsed,
+                progress.fraction() * 100.0
+            );
+            let fraction = progress.fraction();
+            ui.horizontal(|ui| {
+                if ui.button("Cancel").clicked() {
+                    self.csv.cancel();
+                }
+                ui.add(ProgressBar::new(fraction).text(text));
+            });
+            ui.ctx().request_repaint();
+        }
         if self.csv.status().is_error() {
             // error_label(csv_table.status(), ui);
             ui.label(format!("{:?}", self.csv.status()));
         }
+        self.show_report(ui);
+        self.update_padded_cells(ui);
+        self.update_duplicates(ui);
+        self.show_duplicates(ui);
         self.table_view.show(&mut self.backend, ui);
     }
 
+    /// Outline cells missing in short rows after each load, see [RaggedRowPolicy::PadEmpty].
+    fn update_padded_cells(&mut self, ui: &Ui) {
+        let flags = self.backend.one_shot_flags();
+        if !flags.reloaded && !flags.cleared {
+            return;
+        }
+        for (coord, lint) in self.marked_padded.drain(..) {
+            self.table_view.remove_cell_lint(coord, &lint);
+        }
+        let lint = Lint::Outline(Stroke::new(1., ui.visuals().warn_fg_color));
+        for coord in self.csv.padded_cells() {
+            self.table_view.add_cell_lint(*coord, lint.clone());
+            self.marked_padded.push((*coord, lint.clone()));
+        }
+    }
+
+    /// Find duplicates again if rows or the config changed, or compare only the edited rows again,
+    /// marking cells of the duplicate rows.
+    fn update_duplicates(&mut self, ui: &Ui) {
+        let flags = self.backend.one_shot_flags();
+        let rows_changed = flags.reloaded || flags.cleared || flags.row_set_updated;
+        let edited_rows: HashSet<RowUid> = flags.cells_updated.iter().map(|c| c.row_uid).collect();
+        let changed = if rows_changed || self.duplicates_outdated {
+            self.duplicates_outdated = false;
+            for (_, marked) in self.marked_duplicates.drain() {
+                for (coord, lint) in marked {
+                    self.table_view.remove_cell_lint(coord, &lint);
+                }
+            }
+            self.duplicate_rows = self.find_duplicates();
+            self.duplicate_rows.rows()
+        } else if !edited_rows.is_empty() {
+            self.backend
+                .update_duplicates(&mut self.duplicate_rows, edited_rows)
+        } else {
+            return;
+        };
+        let lint = Lint::Background(ui.visuals().warn_fg_color.gamma_multiply(0.15));
+        let used_columns: Vec<ColumnUid> = self.backend.used_columns().collect();
+        for row_uid in changed {
+            if let Some(marked) = self.marked_duplicates.remove(&row_uid) {
+                for (coord, lint) in marked {
+                    self.table_view.remove_cell_lint(coord, &lint);
+                }
+            }
+            if !self.duplicate_rows.contains(row_uid) {
+                continue;
+            }
+            let marked = used_columns
+                .iter()
+                .map(|col_uid| {
+                    let coord = CellCoord {
+                        row_uid,
+                        col_uid: *col_uid,
+                    };
+                    self.table_view.add_cell_lint(coord, lint.clone());
+                    (coord, lint.clone())
+                })
+                .collect();
+            self.marked_duplicates.insert(row_uid, marked);
+        }
+    }
+
+    /// All duplicates of the loaded rows according to the config.
+    fn find_duplicates(&self) -> DuplicateRows {
+        match &self.config.duplicates {
+            DuplicateKey::Off => DuplicateRows::default(),
+            DuplicateKey::FullRow => self.backend.find_duplicates(&[]),
+            DuplicateKey::Columns(names) => {
+                let columns: Vec<ColumnUid> = self
+                    .csv
+                    .required_columns()
+                    .required_columns
+                    .iter()
+                    .filter(|(_, c)| names.contains(&c.name))
+                    .map(|(col_uid, _)| *col_uid)
+                    .collect();
+                if columns.is_empty() {
+                    DuplicateRows::default()
+                } else {
+                    self.backend.find_duplicates(&columns)
+                }
+            }
+        }
+    }
+
+    /// Choice of duplicate detection, with the number of duplicates found and buttons to skip or remove them.
+    fn show_duplicates(&mut self, ui: &mut Ui) {
+        ui.horizontal_wrapped(|ui| {
+            let selected_text = match &self.config.duplicates {
+                DuplicateKey::Off => "Off".to_string(),
+                DuplicateKey::FullRow => "Full row".to_string(),
+                DuplicateKey::Columns(names) if names.is_empty() => "Choose columns".to_string(),
+                DuplicateKey::Columns(names) => names.join(", "),
+            };
+            let mut changed = false;
+            egui::ComboBox::from_label("Duplicates")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    changed |= ui
+                        .selectable_value(&mut self.config.duplicates, DuplicateKey::Off, "Off")
+                        .changed();
+                    changed |= ui
+                        .selectable_value(
+                            &mut self.config.duplicates,
+                            DuplicateKey::FullRow,
+                            "Full row",
+                        )
+                        .changed();
+                    ui.separator();
+                    for (_, c) in &self.csv.required_columns().required_columns {
+                        let names = match &self.config.duplicates {
+                            DuplicateKey::Columns(names) => names.clone(),
+                            _ => vec![],
+                        };
+                        let mut is_key = names.contains(&c.name);
+                        if ui.checkbox(&mut is_key, &c.name).changed() {
+                            let mut names: Vec<String> =
+                                names.into_iter().filter(|n| *n != c.name).collect();
+                            if is_key {
+                                names.push(c.name.clone());
+                            }
+                            self.config.duplicates = DuplicateKey::Columns(names);
+                            changed = true;
+                        }
+                    }
+                });
+            if changed {
+                self.duplicates_outdated = true;
+            }
+            let not_skipped: Vec<RowUid> = self
+                .duplicate_rows
+                .rows()
+                .into_iter()
+                .filter(|row_uid| !self.backend.is_row_skipped(*row_uid))
+                .collect();
+            if self.duplicate_rows.is_empty() {
+                return;
+            }
+            let text = match (self.duplicate_rows.len(), not_skipped.len()) {
+                (1, 1) => "1 duplicate row".to_string(),
+                (total, n) if total == n => format!("{total} duplicate rows"),
+                (total, n) => format!("{total} duplicate rows, {n} not skipped"),
+            };
+            ui.colored_label(ui.visuals().warn_fg_color, text);
+            if ui
+                .add_enabled(!not_skipped.is_empty(), Button::new("Skip duplicates"))
+                .on_hover_text("Keep duplicate rows in the table, but leave them out of the import")
+                .clicked()
+            {
+                for row_uid in &not_skipped {
+                    self.backend.skip_row(*row_uid, true);
+                }
+            }
+            ui.menu_button("Remove duplicates…", |ui| {
+                ui.label(format!(
+                    "Delete {} duplicate rows? First occurrences are kept, removal can be undone.",
+                    self.duplicate_rows.len()
+                ));
+                if ui.button("Remove").clicked() {
+                    self.backend.remove_rows(self.duplicate_rows.rows());
+                    self.duplicates_outdated = true;
+                    ui.close_menu();
+                }
+            });
+        });
+    }
+
+    /// Returns true if encoding was changed.
+    fn encoding_combo_box(&mut self, ui: &mut Ui) -> bool {
+        let selected_text = match &self.config.csv.encoding {
+            Some(label) => label.clone(),
+            None => match self.csv.encoding() {
+                Some(detected) => format!("Auto ({})", detected.name()),
+                None => "Auto".to_string(),
+            },
+        };
+        egui::ComboBox::from_label("Encoding")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                let mut changed = ui
+                    .selectable_value(&mut self.config.csv.encoding, None, "Auto")
+                    .changed();
+                for encoding in ENCODINGS {
+                    let name = encoding.name().to_string();
+                    changed |= ui
+                        .selectable_value(&mut self.config.csv.encoding, Some(name.clone()), name)
+                        .changed();
+                }
+                changed
+            })
+            .inner
+            .unwrap_or(false)
+    }
+
+    /// List of problems found while loading, clicking on one selects the offending cell.
+    fn show_report(&mut self, ui: &mut Ui) {
+        report_ui(self.csv.report(), &self.backend, &mut self.table_view, ui);
+    }
+
+    /// First rows of the picked file, clicking one makes it the header row and skips the rows above it.
+    fn show_header_picker(&mut self, ui: &mut Ui) {
+        if !self.header_picker_open {
+            return;
+        }
+        let Some(path) = &self.picked_file else {
+            return;
+        };
+        let sheet = self
+            .config
+            .sheet
+            .as_deref()
+            .filter(|_| !self.sheet_names.is_empty());
+        let rows = self
+            .header_preview
+            .get_or_insert_with(|| self.csv.preview_rows(path, sheet, HEADER_PREVIEW_ROWS));
+        let header_idx = self.config.csv.skip_first_rows;
+        let mut picked = None;
+        egui::Frame::group(ui.style()).show(ui, |ui| {
+            ScrollArea::both()
+                .id_salt("_egui_tabular_header_picker")
+                .max_height(200.)
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    if rows.is_empty() {
+                        ui.label("Nothing to show");
+                    }
+                    for (idx, row) in rows.iter().enumerate() {
+                        let mut text =
+                            RichText::new(format!("{:>3}  {}", idx + 1, row.join(" | ")))
+                                .monospace();
+                        if idx < header_idx {
+                            text = text.weak().strikethrough();
+                        }
+                        if ui.selectable_label(idx == header_idx, text).clicked() {
+                            picked = Some(idx);
+                        }
+                    }
+                });
+        });
+        if let Some(idx) = picked {
+            self.config.csv.skip_first_rows = idx;
+            self.config.has_headers = true;
+            self.header_picker_open = false;
+            self.try_load();
+        }
+    }
+
     fn try_load(&mut self) {
         let Some(path) = self.picked_file.clone() else {
             return;
         };
-        self.csv.set_separator(self.state.separator);
-        self.csv.skip_rows_on_load(self.state.skip_first_rows);
-        self.csv.load(path, &mut self.backend);
+        self.header_preview = None;
+        self.save_view_state();
+        self.sync_synonyms();
+        let mut header_renames = self.profile_renames.clone();
+        header_renames.extend(
+            self.backend
+                .renamed_columns()
+                .map(|(source_name, name)| (source_name.to_string(), name.to_string())),
+        );
+        self.csv.set_header_renames(header_renames);
+        self.csv.set_config(self.config.csv.clone());
+        self.csv.sync_type_overrides(&self.backend);
+        if self.sheet_names.is_empty() {
+            self.csv.load(path.clone(), &mut self.backend);
+        } else {
+            let sheet = self.config.sheet.clone().unwrap_or_default();
+            self.csv.load_sheet(path.clone(), &sheet, &mut self.backend);
+        }
+        if self.csv.progress().is_none() {
+            self.finish_load();
+        }
+    }
+
+    /// Restore view adjustments and show warnings once the picked file is fully loaded.
+    fn finish_load(&mut self) {
+        let Some(path) = self.picked_file.clone() else {
+            return;
+        };
+        if let Some(view_state) = self.config.per_file.get(&path) {
+            self.table_view.restore_view_state(view_state.clone());
+        }
+        notify_warnings(&mut self.csv, &mut self.table_view);
+        self.loaded_file = Some(path);
+    }
+
+    /// Read available sheets if picked file is a spreadsheet and select the previously used one if it exists.
+    fn refresh_sheet_names(&mut self) {
+        self.sheet_names.clear();
+        let Some(path) = &self.picked_file else {
+            return;
+        };
+        if !xls::is_spreadsheet(path) {
+            return;
+        }
+        match xls::sheet_names(path) {
+            Ok(names) => self.sheet_names = names,
+            Err(e) => {
+                self.csv.set_status(IoStatus::SpreadsheetError(e));
+                return;
+            }
+        }
+        let is_known_sheet = self
+            .config
+            .sheet
+            .as_ref()
+            .map(|sheet| self.sheet_names.contains(sheet))
+            .unwrap_or(false);
+        if !is_known_sheet {
+            self.config.sheet = self.sheet_names.first().cloned();
+        }
+    }
+
+    /// Remember view adjustments made to the currently loaded file.
+    fn save_view_state(&mut self) {
+        if let Some(loaded_file) = &self.loaded_file {
+            self.config
+                .per_file
+                .insert(loaded_file.clone(), self.table_view.view_state());
+        }
+    }
+
+    /// Take synonyms edited by the user from the backend, so that they are used on the next load and persisted.
+    fn sync_synonyms(&mut self) {
+        let required_columns = &mut self.csv.required_columns_mut().required_columns;
+        for (col_uid, c) in required_columns.iter_mut() {
+            let Some(synonyms) = self.backend.column_synonyms(*col_uid) else {
+                continue;
+            };
+            if synonyms != c.synonyms.as_slice() {
+                c.synonyms = synonyms.iter().map(|s| s.to_lowercase()).collect();
+                self.config
+                    .synonyms
+                    .insert(c.name.clone(), c.synonyms.clone());
+            }
+        }
+    }
+
+    /// Save how headers of the loaded file map to columns, along with the current read options,
+    /// replacing a profile with the same name.
+    pub fn save_mapping_profile(&mut self, name: impl Into<String>) {
+        let mut header_renames = self.profile_renames.clone();
+        header_renames.extend(
+            self.csv
+                .matched_headers()
+                .iter()
+                .filter(|(header, name)| header != name)
+                .map(|(header, name)| (header.clone(), name.clone())),
+        );
+        header_renames.extend(
+            self.backend
+                .renamed_columns()
+                .map(|(source_name, name)| (source_name.to_string(), name.to_string())),
+        );
+        let profile = MappingProfile {
+            name: name.into(),
+            header_renames,
+            csv: self.config.csv.clone(),
+            has_headers: self.config.has_headers,
+            sheet: self.config.sheet.clone(),
+        };
+        self.active_profile = Some(profile.name.clone());
+        match self
+            .config
+            .mapping_profiles
+            .iter_mut()
+            .find(|p| p.name == profile.name)
+        {
+            Some(existing) => *existing = profile,
+            None => self.config.mapping_profiles.push(profile),
+        }
+    }
+
+    /// Use a saved mapping and options and reload the picked file, returns false if there is no profile with this name.
+    pub fn apply_mapping_profile(&mut self, name: &str) -> bool {
+        let Some(profile) = self.config.mapping_profiles.iter().find(|p| p.name == name) else {
+            return false;
+        };
+        self.profile_renames = profile.header_renames.clone();
+        self.config.csv = profile.csv.clone();
+        self.config.has_headers = profile.has_headers;
+        if profile.sheet.is_some() {
+            self.config.sheet = profile.sheet.clone();
+        }
+        self.active_profile = Some(name.to_string());
+        self.refresh_sheet_names();
+        self.try_load();
+        true
+    }
+
+    pub fn remove_mapping_profile(&mut self, name: &str) {
+        self.config.mapping_profiles.retain(|p| p.name != name);
+        if self.active_profile.as_deref() == Some(name) {
+            self.active_profile = None;
+        }
+    }
+
+    pub fn mapping_profiles(&self) -> &[MappingProfile] {
+        &self.config.mapping_profiles
+    }
+
+    /// Dropdown applying saved mapping profiles, with saving the current mapping under a new name.
+    fn mapping_profiles_ui(&mut self, ui: &mut Ui) {
+        let title = match &self.active_profile {
+            Some(name) => format!("Mapping: {name}"),
+            None => "Mapping".to_string(),
+        };
+        ui.menu_button(title, |ui| {
+            let mut apply = None;
+            let mut remove = None;
+            for profile in &self.config.mapping_profiles {
+                ui.horizontal(|ui| {
+                    let is_active = self.active_profile.as_deref() == Some(profile.name.as_str());
+                    if ui.selectable_label(is_active, &profile.name).clicked() {
+                        apply = Some(profile.name.clone());
+                        ui.close_menu();
+                    }
+                    if ui
+                        .small_button("🗑")
+                        .on_hover_text("Remove profile")
+                        .clicked()
+                    {
+                        remove = Some(profile.name.clone());
+                    }
+                });
+            }
+            if !self.config.mapping_profiles.is_empty() {
+                ui.separator();
+            }
+            ui.horizontal(|ui| {
+                TextEdit::singleline(&mut self.new_profile_name)
+                    .hint_text("Profile name")
+                    .desired_width(120.)
+                    .ui(ui);
+                let name = self.new_profile_name.trim().to_string();
+                if ui
+                    .add_enabled(!name.is_empty(), Button::new("Save current"))
+                    .on_hover_text("Save header mapping, separator and skipped rows")
+                    .clicked()
+                {
+                    self.save_mapping_profile(name);
+                    self.new_profile_name.clear();
+                    ui.close_menu();
+                }
+            });
+            if let Some(name) = apply {
+                self.apply_mapping_profile(&name);
+            }
+            if let Some(name) = remove {
+                self.remove_mapping_profile(&name);
+            }
+        });
+    }
+
+    /// Returns Rust code that creates an importer with the current required columns and options.
+    pub fn rust_code(&mut self) -> String {
+        self.sync_synonyms();
+        let mut code = String::from("let required_columns = RequiredColumns::new([\n");
+        for (_, c) in &self.csv.required_columns().required_columns {
+            code += &format!(
+                "    RequiredColumn::new({:?}, VariantTy::{:?})",
+                c.name, c.ty
+            );
+            if !c.synonyms.is_empty() {
+                code += &format!(".synonyms({:?})", c.synonyms);
+            }
+            if let Some(date_format) = &c.date_format {
+                code += &format!(".date_format({date_format:?})");
+            }
+            if let Some(locale) = c.number_locale {
+                code += &format!(".number_locale(NumberLocale::{locale:?})");
+            }
+            if let Some(mask) = &c.mask {
+                code += &format!(".mask({:?})", mask.pattern());
+            }
+            for transform in c.transforms.iter().filter_map(|t| t.rust_code()) {
+                code += &format!(".transform({transform})");
+            }
+            if let Some(default) = &c.default {
+                code += &format!(
+                    ".default(Variant::from_str({:?}, VariantTy::{:?}))",
+                    variant_to_string(default),
+                    c.ty
+                );
+            }
+            code += ",\n";
+        }
+        code += "]);\n";
+        code += "let mut importer = CsvXlsImporter::new(required_columns);\n";
+        code += "importer.load_config(\n    CsvXlsImporterConfig::default()\n";
+        code += &format!(
+            "        .separator(Separator::{})\n",
+            self.config.csv.separator
+        );
+        code += &format!("        .has_headers({})\n", self.config.has_headers);
+        code += &format!(
+            "        .skip_first_rows({})",
+            self.config.csv.skip_first_rows
+        );
+        if self.config.csv.skip_last_rows != 0 {
+            code += &format!(
+                "\n        .skip_last_rows({})",
+                self.config.csv.skip_last_rows
+            );
+        }
+        if self.config.csv.number_locale != NumberLocale::Plain {
+            code += &format!(
+                "\n        .number_locale(NumberLocale::{:?})",
+                self.config.csv.number_locale
+            );
+        }
+        if let Some(encoding) = &self.config.csv.encoding {
+            code += &format!("\n        .encoding({encoding:?})");
+        }
+        if self.config.csv.quote != b'"' {
+            code += &format!("\n        .quote(b{:?})", self.config.csv.quote as char);
+        }
+        if let Some(escape) = self.config.csv.escape {
+            code += &format!("\n        .escape(b{:?})", escape as char);
+        }
+        if let Some(comment) = self.config.csv.comment {
+            code += &format!("\n        .comment(b{:?})", comment as char);
+        }
+        if self.config.csv.trim {
+            code += "\n        .trim(true)";
+        }
+        if self.config.csv.collapse_whitespace {
+            code += "\n        .collapse_whitespace(true)";
+        }
+        if self.config.csv.empty_to_null {
+            code += "\n        .empty_to_null(true)";
+        }
+        if self.config.csv.ragged_rows != RaggedRowPolicy::PadEmpty {
+            code += &format!(
+                "\n        .ragged_rows(RaggedRowPolicy::{:?})",
+                self.config.csv.ragged_rows
+            );
+        }
+        if let (false, Some(sheet)) = (self.sheet_names.is_empty(), &self.config.sheet) {
+            code += &format!("\n        .sheet({sheet:?})");
+        }
+        code += ",\n);\n";
+        code
+    }
+
+    /// Returns importer options, including per file view adjustments, for persisting.
+    pub fn save_config(&mut self) -> CsvXlsImporterConfig {
+        self.save_view_state();
+        self.sync_synonyms();
+        self.config.clone()
+    }
+
+    /// Use previously saved options, configs saved by older versions of the crate are upgraded.
+    pub fn load_config(&mut self, mut config: CsvXlsImporterConfig) {
+        config.migrate();
+        self.config = config;
+        let required_columns = &mut self.csv.required_columns_mut().required_columns;
+        for (col_uid, c) in required_columns.iter_mut() {
+            if let Some(synonyms) = self.config.synonyms.get(&c.name) {
+                c.synonyms = synonyms.clone();
+                self.backend.set_column_synonyms(*col_uid, synonyms.clone());
+            }
+        }
     }
 
     pub fn has_warnings(&self) -> bool {
         false
     }
 
+    /// Check that all required columns are present and their cells are filled and converted,
+    /// e.g. to only enable importing once the user fixed the data.
+    pub fn validation_summary(&self) -> ImportValidation {
+        if self.loaded_file.is_none() {
+            return ImportValidation {
+                missing_required: self
+                    .csv
+                    .required_columns()
+                    .required_columns
+                    .iter()
+                    .map(|(col_uid, _)| *col_uid)
+                    .collect(),
+                bad_cells: 0,
+            };
+        }
+        ImportValidation {
+            missing_required: self.csv.missing_required(&self.backend),
+            bad_cells: self.backend.incomplete_cell_count(),
+        }
+    }
+
+    /// Problems found while loading the current file.
+    pub fn report(&self) -> &ImportReport {
+        self.csv.report()
+    }
+
     pub fn picked_file(&self) -> Option<PathBuf> {
         self.picked_file.clone()
     }
@@ -128,3 +1125,28 @@ impl CsvXlsImporter {
         &mut self.backend
     }
 }
+
+/// Combo box choosing one of the `choices`, returns true if the value was changed.
+fn char_combo_box(
+    ui: &mut Ui,
+    label: &str,
+    value: &mut Option<u8>,
+    choices: &[(Option<u8>, &str)],
+) -> bool {
+    let selected_text = choices
+        .iter()
+        .find(|(c, _)| c == value)
+        .map(|(_, text)| text.to_string())
+        .unwrap_or_else(|| value.map(|c| (c as char).to_string()).unwrap_or_default());
+    egui::ComboBox::from_label(label)
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            let mut changed = false;
+            for (c, text) in choices {
+                changed |= ui.selectable_value(value, *c, *text).changed();
+            }
+            changed
+        })
+        .inner
+        .unwrap_or(false)
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// How the importer asks the user for a file.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FilePicker {
+    /// Native dialog, requires the `rfd` feature (enabled by default).
+    #[default]
+    Native,
+    /// Dialog drawn with egui, requires the `egui_file` feature. Works where native dialogs are unavailable,
+    /// e.g. in kiosk apps or on Linux setups without a portal.
+    InUi,
+}
+
+/// File dialog in progress, only the in-UI one spans several frames.
+#[derive(Default)]
+pub(crate) struct FilePickerState {
+    #[cfg(feature = "egui_file")]
+    dialog: Option<egui_file::FileDialog>,
+}
+
+impl FilePickerState {
+    /// Open a file dialog, a native one returns the picked file right away, an in-UI one through [show](Self::show).
+    /// Falls back to the other kind if the preferred one is not enabled.
+    pub(crate) fn open(&mut self, picker: FilePicker, initial: Option<PathBuf>) -> Option<PathBuf> {
+        #[cfg(feature = "egui_file")]
+        if picker == FilePicker::InUi || !cfg!(feature = "rfd") {
+            let mut dialog = egui_file::FileDialog::open_file(initial);
+            dialog.open();
+            self.dialog = Some(dialog);
+            return None;
+        }
+        let _ = (picker, initial);
+        #[cfg(feature = "rfd")]
+        return rfd::FileDialog::new().pick_file();
+        #[cfg(not(feature = "rfd"))]
+        {
+            log::warn!("No file picker available, enable the rfd or egui_file feature");
+            None
+        }
+    }
+
+    /// Show the in-UI dialog if it is open, returns the picked file once.
+    pub(crate) fn show(&mut self, ctx: &egui::Context) -> Option<PathBuf> {
+        #[cfg(feature = "egui_file")]
+        if let Some(dialog) = &mut self.dialog {
+            if dialog.show(ctx).selected() {
+                let path = dialog.path().map(|p| p.to_path_buf());
+                self.dialog = None;
+                return path;
+            }
+            if !dialog.visible() {
+                self.dialog = None;
+            }
+        }
+        let _ = ctx;
+        None
+    }
+}
@@ -0,0 +1,582 @@
+use super::csv::{CsvImporter, CsvImporterConfig, IoStatus, NumberLocale};
+use super::file_picker::{FilePicker, FilePickerState};
+use super::import_job::ImportJob;
+use super::import_report::{notify_warnings, report_ui, ImportReport};
+use crate::backend::TableBackend;
+use crate::backends::variant::VariantBackend;
+use crate::config_version::{ConfigMigrations, VersionedConfig};
+use crate::table_view::{CardView, ToolColumn};
+use crate::util::detect_encoding;
+use crate::{RequiredColumns, TableView};
+use egui::{
+    Align2, CursorIcon, DragValue, RichText, ScrollArea, Sense, Stroke, TextStyle, Ui, Vec2, Widget,
+};
+use encoding_rs::Encoding;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use strum::IntoEnumIterator;
+
+/// Lines of the file shown above the table to place column boundaries on.
+const PREVIEW_LINES: usize = 20;
+/// How close to a boundary marker the pointer has to be to grab it, in points.
+const GRAB_DISTANCE: f32 = 4.;
+/// Ruler numbers are shown every this many characters.
+const RULER_STEP: usize = 10;
+
+/// Importer for text files with columns at fixed character positions, e.g. reports of older systems.
+/// Lines are split at column boundaries placed by the user over a preview of the file,
+/// and columns are matched to required columns the same way as in [CsvXlsImporter](super::csv_xls_importer::CsvXlsImporter).
+pub struct FixedWidthImporter {
+    csv: CsvImporter,
+    backend: VariantBackend,
+    table_view: TableView,
+    config: FixedWidthImporterConfig,
+    picked_file: Option<PathBuf>,
+    file_picker: FilePickerState,
+    /// Decoded text of the picked file, split again when the options change.
+    text: Option<String>,
+    /// Picked file being read on a background thread.
+    reading: Option<ImportJob<std::io::Result<String>>>,
+    /// First lines of the picked file.
+    preview: Vec<String>,
+    /// Index of the boundary being dragged in the preview.
+    dragged: Option<usize>,
+}
+
+/// Fixed width importer options, can be persisted by the host application.
+/// Missing fields take their defaults when deserializing, configs of older versions are upgraded in
+/// [load_config](FixedWidthImporter::load_config).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FixedWidthImporterConfig {
+    /// 0 for configs stored before versioning, see [VersionedConfig].
+    #[serde(default)]
+    version: u32,
+    /// Character positions at which lines are split into columns, in increasing order.
+    boundaries: Vec<usize>,
+    /// First line after the skipped ones holds column names.
+    has_headers: bool,
+    /// Lines to skip before the header line.
+    skip_first_rows: usize,
+    /// Lines to skip at the end of the file, e.g. totals.
+    skip_last_rows: usize,
+    /// Encoding label, e.g. "windows-1252", None to detect it automatically.
+    encoding: Option<String>,
+    number_locale: NumberLocale,
+    /// Detect types of columns not matching any required column from the first rows, otherwise they are strings.
+    infer_types: bool,
+    file_picker: FilePicker,
+}
+
+impl Default for FixedWidthImporterConfig {
+    fn default() -> Self {
+        FixedWidthImporterConfig {
+            version: Self::VERSION,
+            boundaries: Vec::new(),
+            has_headers: true,
+            skip_first_rows: 0,
+            skip_last_rows: 0,
+            encoding: None,
+            number_locale: NumberLocale::default(),
+            infer_types: true,
+            file_picker: FilePicker::default(),
+        }
+    }
+}
+
+impl VersionedConfig for FixedWidthImporterConfig {
+    const VERSION: u32 = 1;
+
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+
+    fn migrations() -> ConfigMigrations<Self> {
+        ConfigMigrations::new()
+    }
+}
+
+impl FixedWidthImporterConfig {
+    /// Split lines at these character positions.
+    pub fn boundaries(self, boundaries: impl IntoIterator<Item = usize>) -> Self {
+        let mut boundaries: Vec<usize> = boundaries.into_iter().filter(|b| *b > 0).collect();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        FixedWidthImporterConfig { boundaries, ..self }
+    }
+
+    pub fn has_headers(self, has_headers: bool) -> Self {
+        FixedWidthImporterConfig {
+            has_headers,
+            ..self
+        }
+    }
+
+    pub fn skip_first_rows(self, skip_first_rows: usize) -> Self {
+        FixedWidthImporterConfig {
+            skip_first_rows,
+            ..self
+        }
+    }
+
+    pub fn skip_last_rows(self, skip_last_rows: usize) -> Self {
+        FixedWidthImporterConfig {
+            skip_last_rows,
+            ..self
+        }
+    }
+
+    /// Use the encoding with the given label (e.g. "windows-1252") instead of detecting it.
+    pub fn encoding(self, label: impl AsRef<str>) -> Self {
+        FixedWidthImporterConfig {
+            encoding: Some(label.as_ref().to_string()),
+            ..self
+        }
+    }
+
+    pub fn number_locale(self, number_locale: NumberLocale) -> Self {
+        FixedWidthImporterConfig {
+            number_locale,
+            ..self
+        }
+    }
+
+    pub fn infer_types(self, infer_types: bool) -> Self {
+        FixedWidthImporterConfig {
+            infer_types,
+            ..self
+        }
+    }
+
+    pub fn file_picker(self, file_picker: FilePicker) -> Self {
+        FixedWidthImporterConfig {
+            file_picker,
+            ..self
+        }
+    }
+
+    /// Options of the shared column mapping and conversion.
+    fn csv_config(&self) -> CsvImporterConfig {
+        CsvImporterConfig {
+            skip_first_rows: self.skip_first_rows,
+            skip_last_rows: self.skip_last_rows,
+            number_locale: self.number_locale,
+            infer_types: self.infer_types,
+            ..CsvImporterConfig::default()
+        }
+    }
+}
+
+impl FixedWidthImporter {
+    pub fn new(required_columns: RequiredColumns) -> Self {
+        let mut backend = VariantBackend::new(
+            required_columns
+                .required_columns
+                .iter()
+                .map(|(_, c)| (c.name.clone(), c.ty, c.default.clone())),
+        );
+        for (col_uid, c) in &required_columns.required_columns {
+            backend.set_column_synonyms(*col_uid, c.synonyms.clone());
+            backend.set_column_required(*col_uid, true);
+            backend.set_column_mask(*col_uid, c.mask.clone());
+            backend.set_column_hint(*col_uid, c.description.clone(), c.example.clone());
+        }
+        FixedWidthImporter {
+            csv: CsvImporter::new(required_columns),
+            backend,
+            table_view: TableView::builder()
+                .tool_column_position(ToolColumn::Left)
+                .card_view(CardView::BelowWidth(480.))
+                .build(),
+            config: FixedWidthImporterConfig::default(),
+            picked_file: None,
+            file_picker: FilePickerState::default(),
+            text: None,
+            reading: None,
+            preview: Vec::new(),
+            dragged: None,
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut Ui) {
+        let mut reread = false;
+        let mut reload = false;
+        ui.horizontal_wrapped(|ui| {
+            ui.label(RichText::new("Fixed width options").strong().monospace());
+
+            let mut picked = None;
+            if ui.button("Open file…").clicked() {
+                let initial = self.picked_file.clone();
+                picked = self.file_picker.open(self.config.file_picker, initial);
+            }
+            if let Some(path) = picked.or_else(|| self.file_picker.show(ui.ctx())) {
+                self.picked_file = Some(path);
+                reread = true;
+            }
+            reread |= ui.button("Reload").clicked();
+            ui.separator();
+
+            if ui
+                .button("Detect columns")
+                .on_hover_text("Place boundaries where all shown lines have spaces")
+                .clicked()
+            {
+                let lines = self
+                    .preview
+                    .get(self.config.skip_first_rows..)
+                    .unwrap_or_default();
+                self.config.boundaries = detect_boundaries(lines);
+                reload = true;
+            }
+            if ui.button("Clear columns").clicked() {
+                self.config.boundaries.clear();
+                reload = true;
+            }
+            ui.separator();
+
+            reload |= ui
+                .checkbox(&mut self.config.has_headers, "Has header row")
+                .changed();
+            reload |= ui
+                .checkbox(&mut self.config.infer_types, "Detect types")
+                .on_hover_text("Detect types of additional columns from the first rows")
+                .changed();
+            egui::ComboBox::from_label("Numbers")
+                .selected_text(self.config.number_locale.to_string())
+                .show_ui(ui, |ui| {
+                    for l in NumberLocale::iter() {
+                        reload |= ui
+                            .selectable_value(&mut self.config.number_locale, l, l.to_string())
+                            .changed();
+                    }
+                });
+            ui.label("Skip first rows");
+            reload |= DragValue::new(&mut self.config.skip_first_rows)
+                .range(0..=100)
+                .ui(ui)
+                .changed();
+            ui.label("Skip last rows");
+            reload |= DragValue::new(&mut self.config.skip_last_rows)
+                .range(0..=100)
+                .ui(ui)
+                .changed();
+        });
+        reload |= self.boundaries_ui(ui);
+        if reread {
+            self.read_file();
+        } else if reload {
+            self.split_and_load();
+        }
+        self.poll();
+        if self.reading.is_some() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Reading file…");
+            });
+            ui.ctx().request_repaint();
+        }
+        if self.csv.status().is_error() {
+            ui.label(format!("{:?}", self.csv.status()));
+        }
+        report_ui(self.csv.report(), &self.backend, &mut self.table_view, ui);
+        self.table_view.show(&mut self.backend, ui);
+    }
+
+    /// Read a text file on a background thread, then split its lines at the configured boundaries
+    /// and load them into the backend.
+    pub fn load(&mut self, path: PathBuf) {
+        self.picked_file = Some(path);
+        self.read_file();
+    }
+
+    /// Receive the file read on a background thread, called by [show](Self::show).
+    /// Only needed to finish loading while the importer is not shown, returns true once a file is loaded.
+    pub fn poll(&mut self) -> bool {
+        let Some(result) = self.reading.as_mut().and_then(|job| job.poll()) else {
+            return false;
+        };
+        self.reading = None;
+        match result {
+            Ok(text) => {
+                self.text = Some(text);
+                self.split_and_load();
+                true
+            }
+            Err(e) => {
+                self.text = None;
+                self.preview.clear();
+                self.csv.set_status(IoStatus::IoError(e));
+                self.csv.discard_loaded(&mut self.backend);
+                false
+            }
+        }
+    }
+
+    /// Start reading the picked file, the file read before stays split until it is finished.
+    fn read_file(&mut self) {
+        let Some(path) = self.picked_file.clone() else {
+            return;
+        };
+        let encoding = self.config.encoding.clone();
+        let total_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        self.reading = Some(ImportJob::spawn(total_bytes, move |_| {
+            read_text(&path, encoding.as_deref())
+        }));
+    }
+
+    /// Split the lines of the read file at the configured boundaries and load them into the backend.
+    fn split_and_load(&mut self) {
+        let (Some(path), Some(text)) = (self.picked_file.clone(), self.text.as_ref()) else {
+            return;
+        };
+        let mut lines: Vec<&str> = text.lines().collect();
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+        self.preview = lines
+            .iter()
+            .take(PREVIEW_LINES)
+            .map(|line| line.to_string())
+            .collect();
+        let rows = lines
+            .iter()
+            .map(|line| split_line(line, &self.config.boundaries))
+            .collect();
+        self.csv.set_config(self.config.csv_config());
+        self.csv.sync_type_overrides(&self.backend);
+        self.csv.load_rows(
+            path,
+            rows,
+            self.config.has_headers,
+            self.config.number_locale,
+            &mut self.backend,
+        );
+        notify_warnings(&mut self.csv, &mut self.table_view);
+    }
+
+    /// First lines of the file in a monospaced font with column boundaries over them.
+    /// Boundaries are dragged to move them, clicking elsewhere adds one and double clicking one removes it.
+    /// Returns true once boundaries are changed.
+    fn boundaries_ui(&mut self, ui: &mut Ui) -> bool {
+        if self.preview.is_empty() {
+            return false;
+        }
+        let font = TextStyle::Monospace.resolve(ui.style());
+        let char_width = ui.fonts(|f| f.glyph_width(&font, 'M'));
+        let line_height = ui.text_style_height(&TextStyle::Monospace);
+        let width = self
+            .preview
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let size = Vec2::new(
+            width as f32 * char_width,
+            (self.preview.len() + 1) as f32 * line_height,
+        );
+        let mut changed = false;
+        ScrollArea::horizontal()
+            .id_salt("_egui_tabular_fixed_width_preview")
+            .show(ui, |ui| {
+                let (rect, resp) = ui.allocate_exact_size(size, Sense::click_and_drag());
+                let painter = ui.painter_at(rect);
+                let visuals = ui.visuals();
+                let x_of = |offset: usize| rect.min.x + offset as f32 * char_width;
+                let offset_at =
+                    |x: f32| (((x - rect.min.x) / char_width).round().max(1.) as usize).min(width);
+                let marker_near = |x: f32, boundaries: &[usize]| {
+                    boundaries
+                        .iter()
+                        .position(|b| (x_of(*b) - x).abs() <= GRAB_DISTANCE)
+                };
+
+                for offset in (0..width).step_by(RULER_STEP) {
+                    painter.text(
+                        egui::pos2(x_of(offset), rect.min.y),
+                        Align2::LEFT_TOP,
+                        offset.to_string(),
+                        TextStyle::Small.resolve(ui.style()),
+                        visuals.weak_text_color(),
+                    );
+                }
+                for (idx, line) in self.preview.iter().enumerate() {
+                    let color = if idx < self.config.skip_first_rows {
+                        visuals.weak_text_color()
+                    } else {
+                        visuals.text_color()
+                    };
+                    painter.text(
+                        egui::pos2(rect.min.x, rect.min.y + (idx + 1) as f32 * line_height),
+                        Align2::LEFT_TOP,
+                        line,
+                        font.clone(),
+                        color,
+                    );
+                }
+
+                let pointer_x = resp.interact_pointer_pos().map(|pos| pos.x);
+                if resp.drag_started() {
+                    // Drag starts a few points away from where the button was pressed
+                    let press_x = ui.input(|i| i.pointer.press_origin()).map(|pos| pos.x);
+                    self.dragged = press_x.and_then(|x| marker_near(x, &self.config.boundaries));
+                }
+                if let (Some(idx), Some(x)) = (self.dragged, pointer_x) {
+                    if let Some(boundary) = self.config.boundaries.get_mut(idx) {
+                        *boundary = offset_at(x);
+                    }
+                }
+                if resp.drag_stopped() && self.dragged.take().is_some() {
+                    changed = true;
+                }
+                if let Some(x) = pointer_x {
+                    match marker_near(x, &self.config.boundaries) {
+                        Some(idx) if resp.double_clicked() => {
+                            self.config.boundaries.remove(idx);
+                            changed = true;
+                        }
+                        None if resp.clicked() => {
+                            self.config.boundaries.push(offset_at(x));
+                            changed = true;
+                        }
+                        _ => {}
+                    }
+                }
+                let hovered_marker = resp
+                    .hover_pos()
+                    .and_then(|pos| marker_near(pos.x, &self.config.boundaries));
+                if hovered_marker.is_some() || self.dragged.is_some() {
+                    ui.ctx().set_cursor_icon(CursorIcon::ResizeHorizontal);
+                }
+
+                for (idx, boundary) in self.config.boundaries.iter().enumerate() {
+                    let active = self.dragged == Some(idx) || hovered_marker == Some(idx);
+                    let stroke_width = if active { 2. } else { 1. };
+                    painter.vline(
+                        x_of(*boundary),
+                        rect.y_range(),
+                        Stroke::new(stroke_width, visuals.selection.stroke.color),
+                    );
+                }
+            });
+        if changed {
+            self.config.boundaries.sort_unstable();
+            self.config.boundaries.dedup();
+        }
+        changed
+    }
+
+    /// Returns importer options for persisting.
+    pub fn save_config(&self) -> FixedWidthImporterConfig {
+        self.config.clone()
+    }
+
+    /// Use previously saved options, the picked file is loaded again with them.
+    /// Configs saved by older versions of the crate are upgraded.
+    pub fn load_config(&mut self, mut config: FixedWidthImporterConfig) {
+        config.migrate();
+        let encoding_changed = config.encoding != self.config.encoding;
+        self.config = config;
+        if encoding_changed || self.text.is_none() {
+            self.read_file();
+        } else {
+            self.split_and_load();
+        }
+    }
+
+    /// Problems found while loading the current file.
+    pub fn report(&self) -> &ImportReport {
+        self.csv.report()
+    }
+
+    pub fn picked_file(&self) -> Option<PathBuf> {
+        self.picked_file.clone()
+    }
+
+    pub fn backend(&self) -> &VariantBackend {
+        &self.backend
+    }
+
+    pub fn backend_mut(&mut self) -> &mut VariantBackend {
+        &mut self.backend
+    }
+}
+
+fn read_text(path: &Path, encoding: Option<&str>) -> std::io::Result<String> {
+    let bytes = std::fs::read(path)?;
+    let encoding = match encoding {
+        Some(label) => Encoding::for_label(label.as_bytes()).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("unknown encoding: {label}"),
+            )
+        })?,
+        None => detect_encoding(&bytes),
+    };
+    let (text, _) = encoding.decode_with_bom_removal(&bytes);
+    Ok(text.into_owned())
+}
+
+/// Cells of a line split at character positions, with the padding around values removed.
+fn split_line(line: &str, boundaries: &[usize]) -> Vec<String> {
+    let byte_offsets: Vec<usize> = line.char_indices().map(|(i, _)| i).collect();
+    let byte_at = |chars: usize| byte_offsets.get(chars).copied().unwrap_or(line.len());
+    let mut start = 0;
+    boundaries
+        .iter()
+        .copied()
+        .chain([usize::MAX])
+        .map(|end| {
+            let cell = line[byte_at(start)..byte_at(end.max(start))]
+                .trim()
+                .to_string();
+            start = end;
+            cell
+        })
+        .collect()
+}
+
+/// Positions where a column starts after a run of positions that are blank in all lines.
+fn detect_boundaries(lines: &[String]) -> Vec<usize> {
+    let lines: Vec<Vec<char>> = lines.iter().map(|line| line.chars().collect()).collect();
+    let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+    let blank: Vec<bool> = (0..width)
+        .map(|i| {
+            lines
+                .iter()
+                .all(|line| line.get(i).map(|c| c.is_whitespace()).unwrap_or(true))
+        })
+        .collect();
+    (1..width).filter(|i| blank[i - 1] && !blank[*i]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_boundaries, split_line};
+
+    #[test]
+    fn split_line_at_char_positions() {
+        assert_eq!(
+            split_line("R1   10k  Résistor", &[5, 10]),
+            vec!["R1", "10k", "Résistor"]
+        );
+        // Short lines give empty cells, everything after the last boundary is one cell
+        assert_eq!(split_line("C1", &[5, 10]), vec!["C1", "", ""]);
+        assert_eq!(split_line("a b c", &[]), vec!["a b c"]);
+    }
+
+    #[test]
+    fn detect_boundaries_at_blank_runs() {
+        let lines = [
+            "Name Value  Qty".to_string(),
+            "R1   10k    2".to_string(),
+            "C12  100n   10".to_string(),
+        ];
+        assert_eq!(detect_boundaries(&lines), vec![5, 12]);
+        assert_eq!(detect_boundaries(&[]), Vec::<usize>::new());
+    }
+}
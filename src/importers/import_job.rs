@@ -0,0 +1,128 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+
+/// Work running on a spawned thread, reporting its progress over a channel.
+/// Call [poll](Self::poll) periodically, e.g. each frame, to receive progress updates and the result.
+pub struct ImportJob<T> {
+    rx: Receiver<Message<T>>,
+    cancel: Arc<AtomicBool>,
+    progress: ImportProgress,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ImportProgress {
+    pub bytes_read: u64,
+    /// Total size of the input, 0 if unknown.
+    pub total_bytes: u64,
+    pub rows_parsed: usize,
+    /// Problems reported so far, parsing might still continue after them.
+    pub errors: Vec<String>,
+}
+
+impl ImportProgress {
+    /// Fraction of the input processed, from 0.0 to 1.0.
+    pub fn fraction(&self) -> f32 {
+        if self.total_bytes == 0 {
+            0.0
+        } else {
+            self.bytes_read as f32 / self.total_bytes as f32
+        }
+    }
+}
+
+enum Message<T> {
+    Progress { bytes_read: u64, rows_parsed: usize },
+    Error(String),
+    Finished(T),
+}
+
+/// Handle given to the job's closure to report progress and check for cancellation.
+pub struct JobContext<T> {
+    tx: Sender<Message<T>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl<T> JobContext<T> {
+    pub fn report_progress(&self, bytes_read: u64, rows_parsed: usize) {
+        let _ = self.tx.send(Message::Progress {
+            bytes_read,
+            rows_parsed,
+        });
+    }
+
+    pub fn report_error(&self, error: impl Into<String>) {
+        let _ = self.tx.send(Message::Error(error.into()));
+    }
+
+    /// Job should return as soon as possible once cancelled, its result is discarded.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+}
+
+impl<T: Send + 'static> ImportJob<T> {
+    /// Run `work` on a new thread, `total_bytes` is used to calculate progress fraction.
+    pub fn spawn(
+        total_bytes: u64,
+        work: impl FnOnce(&JobContext<T>) -> T + Send + 'static,
+    ) -> Self {
+        let (tx, rx) = channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let cx = JobContext {
+            tx,
+            cancel: cancel.clone(),
+        };
+        thread::spawn(move || {
+            let result = work(&cx);
+            if !cx.is_cancelled() {
+                let _ = cx.tx.send(Message::Finished(result));
+            }
+        });
+        ImportJob {
+            rx,
+            cancel,
+            progress: ImportProgress {
+                total_bytes,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Drain progress updates, returns the result once the job is finished.
+    pub fn poll(&mut self) -> Option<T> {
+        while let Ok(message) = self.rx.try_recv() {
+            match message {
+                Message::Progress {
+                    bytes_read,
+                    rows_parsed,
+                } => {
+                    self.progress.bytes_read = bytes_read;
+                    self.progress.rows_parsed = rows_parsed;
+                }
+                Message::Error(e) => self.progress.errors.push(e),
+                Message::Finished(result) => {
+                    self.progress.bytes_read = self.progress.total_bytes;
+                    return Some(result);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn progress(&self) -> &ImportProgress {
+        &self.progress
+    }
+
+    /// Ask the job to stop, it is also cancelled when dropped.
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+impl<T> Drop for ImportJob<T> {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
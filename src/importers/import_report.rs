@@ -0,0 +1,151 @@
+use crate::backend::{ColumnUid, RowUid};
+#[cfg(feature = "gui")]
+use crate::backend::{TableBackend, VisualRowIdx};
+#[cfg(feature = "gui")]
+use crate::backends::variant::VariantBackend;
+#[cfg(feature = "gui")]
+use crate::importers::csv::CsvImporter;
+#[cfg(feature = "gui")]
+use crate::table_view::ToastKind;
+#[cfg(feature = "gui")]
+use crate::TableView;
+#[cfg(feature = "gui")]
+use egui::{RichText, ScrollArea, Ui};
+use std::collections::HashMap;
+
+/// Only this many issues are kept, the rest are only counted.
+const MAX_ISSUES: usize = 1000;
+
+/// Problem with one row or cell of the imported file.
+#[derive(Clone, Debug)]
+pub struct ImportIssue {
+    /// Line in the file, starting from 1.
+    pub line: usize,
+    /// Row in the backend, None if the row was not loaded.
+    pub row_uid: Option<RowUid>,
+    /// Column in the backend, None if the whole row is affected.
+    pub col_uid: Option<ColumnUid>,
+    pub message: String,
+}
+
+/// Whether loaded data is ready to be imported, see
+/// [CsvXlsImporter::validation_summary](super::csv_xls_importer::CsvXlsImporter::validation_summary).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportValidation {
+    /// Required columns not found in the file or not used, all of them if no file is loaded.
+    pub missing_required: Vec<ColumnUid>,
+    /// Empty or not converted cells of required columns in rows that are not skipped.
+    pub bad_cells: usize,
+}
+
+impl ImportValidation {
+    pub fn is_clean(&self) -> bool {
+        self.missing_required.is_empty() && self.bad_cells == 0
+    }
+}
+
+/// Problems found while loading a file.
+#[derive(Clone, Debug, Default)]
+pub struct ImportReport {
+    issues: Vec<ImportIssue>,
+    total: usize,
+}
+
+impl ImportReport {
+    pub fn push(&mut self, issue: ImportIssue) {
+        if self.issues.len() < MAX_ISSUES {
+            self.issues.push(issue);
+        }
+        self.total += 1;
+    }
+
+    /// First issues found, see [total](Self::total) for the overall count.
+    pub fn issues(&self) -> &[ImportIssue] {
+        &self.issues
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    /// Replace row uids of a staging backend with the ones after merging.
+    pub(crate) fn remap_rows(&mut self, row_map: &HashMap<RowUid, RowUid>) {
+        for issue in &mut self.issues {
+            issue.row_uid = issue
+                .row_uid
+                .and_then(|row_uid| row_map.get(&row_uid).copied());
+        }
+    }
+}
+
+/// List of problems found while loading, clicking on one selects the offending cell in the table view.
+#[cfg(feature = "gui")]
+pub(crate) fn report_ui(
+    report: &ImportReport,
+    backend: &VariantBackend,
+    table_view: &mut TableView,
+    ui: &mut Ui,
+) {
+    if report.is_empty() {
+        return;
+    }
+    let mut go_to = None;
+    egui::CollapsingHeader::new(
+        RichText::new(format!("{} problems found", report.total()))
+            .color(ui.visuals().warn_fg_color),
+    )
+    .id_salt("_egui_tabular_import_report")
+    .show(ui, |ui| {
+        ScrollArea::vertical()
+            .max_height(150.)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                for issue in report.issues() {
+                    let column = issue
+                        .col_uid
+                        .and_then(|col_uid| backend.column_info(col_uid))
+                        .map(|c| format!(" [{}]", c.name))
+                        .unwrap_or_default();
+                    let text = format!("Line {}{column}: {}", issue.line, issue.message);
+                    if let Some(row_uid) = issue.row_uid {
+                        if ui.link(text).clicked() {
+                            go_to = Some((row_uid, issue.col_uid));
+                        }
+                    } else {
+                        ui.label(text);
+                    }
+                }
+                let not_shown = report.total() - report.issues().len();
+                if not_shown > 0 {
+                    ui.label(format!("…and {not_shown} more"));
+                }
+            });
+    });
+    if let Some((row_uid, col_uid)) = go_to {
+        let row_idx = (0..backend.row_count())
+            .map(VisualRowIdx)
+            .find(|row_idx| backend.row_uid(*row_idx) == Some(row_uid));
+        if let Some(row_idx) = row_idx {
+            table_view.select_cell(row_idx, col_uid);
+        }
+    }
+}
+
+/// Show warnings of the last load and the number of problems in its report as toasts over the table.
+#[cfg(feature = "gui")]
+pub(crate) fn notify_warnings(csv: &mut CsvImporter, table_view: &mut TableView) {
+    for warning in csv.take_warnings() {
+        table_view.notify(ToastKind::Warning, warning);
+    }
+    let report = csv.report();
+    if !report.is_empty() {
+        table_view.notify(
+            ToastKind::Warning,
+            format!("{} problems found while loading", report.total()),
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use super::csv::CsvImporterConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Header to column mapping and read options for files from one source, e.g. a particular supplier,
+/// see [CsvXlsImporter::save_mapping_profile](super::csv_xls_importer::CsvXlsImporter::save_mapping_profile).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MappingProfile {
+    pub name: String,
+    /// Header name in the file -> name of the required column it is loaded into, or the name given by the user.
+    #[serde(default)]
+    pub header_renames: HashMap<String, String>,
+    #[serde(default)]
+    pub csv: CsvImporterConfig,
+    #[serde(default = "default_has_headers")]
+    pub has_headers: bool,
+    /// Sheet used when loading spreadsheets.
+    #[serde(default)]
+    pub sheet: Option<String>,
+}
+
+fn default_has_headers() -> bool {
+    true
+}
@@ -1,3 +1,16 @@
-mod csv;
+// Parts of the loader are only used by the importer UI
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub(crate) mod csv;
+#[cfg(feature = "gui")]
 pub mod csv_xls_importer;
+#[cfg(feature = "gui")]
+pub mod file_picker;
+#[cfg(feature = "gui")]
+pub mod fixed_width;
+pub mod import_job;
+pub mod import_report;
+pub mod mapping_profile;
 pub mod required_column;
+pub mod validate;
+pub mod value_transform;
+mod xls;
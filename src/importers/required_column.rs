@@ -1,13 +1,30 @@
+use super::csv::NumberLocale;
+use super::value_transform::ValueTransform;
 use crate::backend::ColumnUid;
+use crate::input_mask::InputMask;
 use rvariant::{Variant, VariantTy};
 
+#[derive(Clone)]
 pub struct RequiredColumn {
     pub name: String,
     pub synonyms: Vec<String>,
     pub ty: VariantTy,
     pub default: Option<Variant>,
+    /// Overrides the number locale from the importer config for this column.
+    pub number_locale: Option<NumberLocale>,
+    /// [chrono](https://docs.rs/chrono/latest/chrono/format/strftime/index.html) format of dates in this column, e.g. "%d.%m.%Y".
+    pub date_format: Option<String>,
+    /// Pattern enforced in the cell editor of a string column, see [InputMask].
+    pub mask: Option<InputMask>,
+    /// What the column is for, shown when hovering the example row of an empty table.
+    pub description: Option<String>,
+    /// Value shown in the example row of an empty table, the default value is shown if None.
+    pub example: Option<Variant>,
+    /// Cleanup applied to cell text before it is converted to the column type.
+    pub transforms: Vec<ValueTransform>,
 }
 
+#[derive(Clone)]
 pub struct RequiredColumns {
     pub(crate) required_columns: Vec<(ColumnUid, RequiredColumn)>,
 }
@@ -19,6 +36,12 @@ impl RequiredColumn {
             synonyms: vec![],
             ty,
             default: None,
+            number_locale: None,
+            date_format: None,
+            mask: None,
+            description: None,
+            example: None,
+            transforms: vec![],
         }
     }
 
@@ -28,6 +51,12 @@ impl RequiredColumn {
             synonyms: vec![],
             ty: VariantTy::Str,
             default: None,
+            number_locale: None,
+            date_format: None,
+            mask: None,
+            description: None,
+            example: None,
+            transforms: vec![],
         }
     }
 
@@ -37,6 +66,12 @@ impl RequiredColumn {
             synonyms: vec![],
             ty: VariantTy::U32,
             default: None,
+            number_locale: None,
+            date_format: None,
+            mask: None,
+            description: None,
+            example: None,
+            transforms: vec![],
         }
     }
 
@@ -46,6 +81,12 @@ impl RequiredColumn {
             synonyms: synonyms.into_iter().map(|s| s.to_lowercase()).collect(),
             ty: self.ty,
             default: self.default,
+            number_locale: self.number_locale,
+            date_format: self.date_format,
+            mask: self.mask,
+            description: self.description,
+            example: self.example,
+            transforms: self.transforms,
         }
     }
 
@@ -55,9 +96,62 @@ impl RequiredColumn {
             synonyms: self.synonyms,
             ty: self.ty,
             default: Some(default),
+            number_locale: self.number_locale,
+            date_format: self.date_format,
+            mask: self.mask,
+            description: self.description,
+            example: self.example,
+            transforms: self.transforms,
         }
     }
 
+    /// Parse numbers in this column according to the locale, regardless of the importer config.
+    pub fn number_locale(self, number_locale: NumberLocale) -> Self {
+        RequiredColumn {
+            number_locale: Some(number_locale),
+            ..self
+        }
+    }
+
+    /// Parse dates in this column with the given format, e.g. "%d.%m.%Y" or "%d/%m/%Y %H:%M".
+    pub fn date_format(self, date_format: impl AsRef<str>) -> Self {
+        RequiredColumn {
+            date_format: Some(date_format.as_ref().to_string()),
+            ..self
+        }
+    }
+
+    /// Enforce a pattern such as `AA-####` while editing cells of this column, see [InputMask].
+    pub fn mask(self, pattern: impl AsRef<str>) -> Self {
+        RequiredColumn {
+            mask: Some(InputMask::new(pattern)),
+            ..self
+        }
+    }
+
+    /// Explain what the column is for, shown in the example row of an empty table.
+    pub fn description(self, description: impl AsRef<str>) -> Self {
+        RequiredColumn {
+            description: Some(description.as_ref().to_string()),
+            ..self
+        }
+    }
+
+    /// Value shown in the example row of an empty table, so that users know what to type.
+    pub fn example(self, example: Variant) -> Self {
+        RequiredColumn {
+            example: Some(example),
+            ..self
+        }
+    }
+
+    /// Clean cell text before it is converted to the column type, e.g. trim it or strip currency symbols.
+    /// Several transforms are applied in the order they were added.
+    pub fn transform(mut self, transform: ValueTransform) -> Self {
+        self.transforms.push(transform);
+        self
+    }
+
     fn contains_in_synonyms(&self, name: &str) -> bool {
         self.synonyms.iter().find(|s| s.as_str() == name).is_some()
     }
@@ -84,7 +178,10 @@ impl RequiredColumns {
             if let Some(idx) = column_names
                 .iter()
                 .enumerate()
-                .find(|(_, n)| **n == col_name_lower.as_str() || col.contains_in_synonyms(**n))
+                .find(|(_, n)| {
+                    let n = n.to_lowercase();
+                    n == col_name_lower || col.contains_in_synonyms(&n)
+                })
                 .map(|(idx, _)| idx)
             {
                 map.push(((*col_uid, col), Some(idx)));
@@ -0,0 +1,163 @@
+use super::csv::{CsvImporter, CsvImporterConfig};
+use super::import_report::{ImportReport, ImportValidation};
+use super::required_column::RequiredColumns;
+use super::xls;
+use crate::backend::{CellCoord, TableBackend};
+use crate::backends::variant::VariantBackend;
+use crate::validation::ColumnValidator;
+use rvariant::Variant;
+use std::path::PathBuf;
+
+/// How files are read and checked by [validate_file], beyond the required columns.
+#[derive(Default)]
+pub struct ValidationRules {
+    csv: CsvImporterConfig,
+    /// Sheet of spreadsheet files, the first one if None.
+    sheet: Option<String>,
+    /// Column name and its check, for required and additional columns alike.
+    validators: Vec<(String, ColumnValidator)>,
+}
+
+/// Value failing one of the [ValidationRules] validators.
+#[derive(Clone, Debug)]
+pub struct InvalidCell {
+    /// Row of the file in the order it was loaded, starting from 1, not counting the header.
+    pub row: usize,
+    pub column: String,
+    pub message: String,
+}
+
+/// Outcome of [validate_file].
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    /// File could not be read, other fields are empty.
+    pub error: Option<String>,
+    pub rows: usize,
+    /// Missing required columns and their empty or not converted cells.
+    pub summary: ImportValidation,
+    /// Cells that could not be converted to their column types.
+    pub conversion: ImportReport,
+    pub invalid_cells: Vec<InvalidCell>,
+    /// Columns with validators that are not in the file, their values could not be checked.
+    pub missing_validated: Vec<String>,
+}
+
+impl ValidationRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Same options as used by [CsvXlsImporter](super::csv_xls_importer::CsvXlsImporter).
+    pub fn csv(self, csv: CsvImporterConfig) -> Self {
+        ValidationRules { csv, ..self }
+    }
+
+    pub fn sheet(self, sheet: impl AsRef<str>) -> Self {
+        ValidationRules {
+            sheet: Some(sheet.as_ref().to_string()),
+            ..self
+        }
+    }
+
+    /// Check values of the column with the given name, columns can have several validators.
+    pub fn validator(mut self, column: impl AsRef<str>, validator: ColumnValidator) -> Self {
+        self.validators
+            .push((column.as_ref().to_string(), validator));
+        self
+    }
+}
+
+impl ValidationReport {
+    /// File was read, all required columns are present and all values are valid.
+    pub fn is_clean(&self) -> bool {
+        self.error.is_none()
+            && self.summary.is_clean()
+            && self.conversion.is_empty()
+            && self.invalid_cells.is_empty()
+            && self.missing_validated.is_empty()
+    }
+}
+
+/// Load a CSV or spreadsheet file the same way the importer does, without showing it,
+/// e.g. to check files in CI or from a command line tool before they are imported.
+pub fn validate_file(
+    path: impl Into<PathBuf>,
+    required_columns: &RequiredColumns,
+    rules: &ValidationRules,
+) -> ValidationReport {
+    let path = path.into();
+    let mut backend = VariantBackend::new(
+        required_columns
+            .required_columns
+            .iter()
+            .map(|(_, c)| (c.name.clone(), c.ty, c.default.clone())),
+    );
+    let mut csv = CsvImporter::new(required_columns.clone());
+    csv.set_config(rules.csv.clone());
+    if xls::is_spreadsheet(&path) {
+        let sheet = match &rules.sheet {
+            Some(sheet) => sheet.clone(),
+            None => match xls::sheet_names(&path) {
+                Ok(names) => names.into_iter().next().unwrap_or_default(),
+                Err(e) => {
+                    return ValidationReport {
+                        error: Some(format!("{e:?}")),
+                        ..Default::default()
+                    }
+                }
+            },
+        };
+        csv.load_sheet(path, &sheet, &mut backend);
+    } else {
+        csv.load(path, &mut backend);
+    }
+    if csv.status().is_error() {
+        return ValidationReport {
+            error: Some(format!("{:?}", csv.status())),
+            ..Default::default()
+        };
+    }
+
+    let used_columns: Vec<_> = backend.used_columns().collect();
+    let missing_required = csv.missing_required(&backend);
+    let rows = backend.un_skipped_rows();
+    let mut invalid_cells = vec![];
+    let mut missing_validated = vec![];
+    for (column, validator) in &rules.validators {
+        let Some(col_uid) = used_columns
+            .iter()
+            .find(|col_uid| backend.column_info(**col_uid).map(|c| &c.name) == Some(column))
+        else {
+            if !missing_validated.contains(column) {
+                missing_validated.push(column.clone());
+            }
+            continue;
+        };
+        for (idx, row_uid) in rows.iter().enumerate() {
+            let coord = CellCoord {
+                row_uid: *row_uid,
+                col_uid: *col_uid,
+            };
+            let value = backend.get(coord).unwrap_or(Variant::Empty);
+            if let Err(message) = validator.check(&value) {
+                invalid_cells.push(InvalidCell {
+                    row: idx + 1,
+                    column: column.clone(),
+                    message,
+                });
+            }
+        }
+    }
+    invalid_cells.sort_by_key(|c| c.row);
+    ValidationReport {
+        error: None,
+        rows: rows.len(),
+        summary: ImportValidation {
+            missing_required,
+            bad_cells: backend.incomplete_cell_count(),
+        },
+        conversion: csv.report().clone(),
+        invalid_cells,
+        missing_validated,
+    }
+}
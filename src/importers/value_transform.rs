@@ -0,0 +1,103 @@
+use regex::Regex;
+use rvariant::Variant;
+use std::borrow::Cow;
+
+/// Cleanup of cell text before it is converted to the column type, see [RequiredColumn::transform](super::required_column::RequiredColumn::transform).
+/// Transforms are applied in the order they were added.
+#[derive(Clone, Debug)]
+pub enum ValueTransform {
+    Trim,
+    Uppercase,
+    Lowercase,
+    /// Remove currency symbols and ISO codes, e.g. "$ 1,200.50" or "1200 EUR".
+    StripCurrency,
+    /// Keep the first capture group, or the whole match if the pattern has no groups.
+    /// Values not matching the pattern are left as they are.
+    RegexCapture(Regex),
+    /// Produce the value directly, type conversion and the transforms after this one are skipped.
+    Custom(fn(&str) -> Variant),
+}
+
+/// Result of [apply_transforms].
+pub(crate) enum Transformed<'a> {
+    Text(Cow<'a, str>),
+    /// Produced by [ValueTransform::Custom].
+    Value(Variant),
+}
+
+/// Symbols removed by [ValueTransform::StripCurrency], ISO codes are three upper case letters around the number.
+const CURRENCY_SYMBOLS: &[char] = &['$', '€', '£', '¥', '₽', '₴', '₹', '₩', '₪', '₺', '¢'];
+
+impl ValueTransform {
+    pub fn regex_capture(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(ValueTransform::RegexCapture(Regex::new(pattern)?))
+    }
+
+    fn apply(&self, value: &str) -> String {
+        match self {
+            ValueTransform::Trim => value.trim().to_string(),
+            ValueTransform::Uppercase => value.to_uppercase(),
+            ValueTransform::Lowercase => value.to_lowercase(),
+            ValueTransform::StripCurrency => {
+                let without_symbols: String = value
+                    .chars()
+                    .filter(|c| !CURRENCY_SYMBOLS.contains(c))
+                    .collect();
+                let trimmed = without_symbols.trim();
+                let is_code = |s: &str| s.len() == 3 && s.chars().all(|c| c.is_ascii_uppercase());
+                let trimmed = match trimmed.split_once(char::is_whitespace) {
+                    Some((code, rest)) if is_code(code) => rest,
+                    _ => trimmed,
+                };
+                let trimmed = match trimmed.rsplit_once(char::is_whitespace) {
+                    Some((rest, code)) if is_code(code) => rest,
+                    _ => trimmed,
+                };
+                trimmed.trim().to_string()
+            }
+            ValueTransform::RegexCapture(regex) => match regex.captures(value) {
+                Some(captures) => captures
+                    .get(1)
+                    .or_else(|| captures.get(0))
+                    .map(|m| m.as_str().to_string())
+                    .unwrap_or_default(),
+                None => value.to_string(),
+            },
+            ValueTransform::Custom(_) => value.to_string(),
+        }
+    }
+
+    /// Code creating this transform, None for custom ones.
+    #[cfg(feature = "gui")]
+    pub(crate) fn rust_code(&self) -> Option<String> {
+        let code = match self {
+            ValueTransform::Trim => "ValueTransform::Trim".to_string(),
+            ValueTransform::Uppercase => "ValueTransform::Uppercase".to_string(),
+            ValueTransform::Lowercase => "ValueTransform::Lowercase".to_string(),
+            ValueTransform::StripCurrency => "ValueTransform::StripCurrency".to_string(),
+            ValueTransform::RegexCapture(regex) => {
+                format!(
+                    "ValueTransform::regex_capture({:?}).unwrap()",
+                    regex.as_str()
+                )
+            }
+            ValueTransform::Custom(_) => return None,
+        };
+        Some(code)
+    }
+}
+
+/// Run the transforms in order, borrowing the value if there are none.
+pub(crate) fn apply_transforms<'a>(
+    transforms: &[ValueTransform],
+    value: &'a str,
+) -> Transformed<'a> {
+    let mut text = Cow::Borrowed(value);
+    for transform in transforms {
+        if let ValueTransform::Custom(f) = transform {
+            return Transformed::Value(f(&text));
+        }
+        text = Cow::Owned(transform.apply(&text));
+    }
+    Transformed::Text(text)
+}
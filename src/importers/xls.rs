@@ -0,0 +1,31 @@
+use calamine::{open_workbook_auto, Data, DataType, Range, Reader};
+use std::path::Path;
+
+/// Files with these extensions are loaded as spreadsheets instead of CSV.
+const SPREADSHEET_EXTENSIONS: &[&str] = &["xlsx", "xlsm", "xlsb", "xls", "ods"];
+
+pub(crate) fn is_spreadsheet(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| SPREADSHEET_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+pub(crate) fn sheet_names(path: &Path) -> Result<Vec<String>, calamine::Error> {
+    Ok(open_workbook_auto(path)?.sheet_names())
+}
+
+/// Cell text as shown in the spreadsheet, except dates which are written in ISO 8601 format.
+pub(crate) fn cell_to_string(cell: &Data) -> String {
+    match cell {
+        Data::DateTime(_) | Data::DateTimeIso(_) => cell
+            .as_datetime()
+            .map(|dt| dt.format(super::csv::ISO_DATE_TIME).to_string())
+            .unwrap_or_else(|| cell.to_string()),
+        _ => cell.to_string(),
+    }
+}
+
+pub(crate) fn read_sheet(path: &Path, sheet: &str) -> Result<Range<Data>, calamine::Error> {
+    open_workbook_auto(path)?.worksheet_range(sheet)
+}
@@ -0,0 +1,137 @@
+/// Pattern that string cells must follow, e.g. `AA-####` for two letters, a dash and four digits.
+///
+/// `A` stands for a letter, `#` for a digit, `*` for a letter or a digit, `\` makes the next character literal.
+/// All other characters are literals that are inserted automatically while typing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputMask {
+    pattern: String,
+    slots: Vec<MaskSlot>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum MaskSlot {
+    Letter,
+    Digit,
+    Alphanumeric,
+    Literal(char),
+}
+
+impl MaskSlot {
+    fn accepts(&self, c: char) -> bool {
+        match self {
+            MaskSlot::Letter => c.is_alphabetic(),
+            MaskSlot::Digit => c.is_ascii_digit(),
+            MaskSlot::Alphanumeric => c.is_alphanumeric(),
+            MaskSlot::Literal(l) => *l == c,
+        }
+    }
+}
+
+impl InputMask {
+    pub fn new(pattern: impl AsRef<str>) -> Self {
+        let pattern = pattern.as_ref().to_string();
+        let mut slots = vec![];
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            let slot = match c {
+                'A' => MaskSlot::Letter,
+                '#' => MaskSlot::Digit,
+                '*' => MaskSlot::Alphanumeric,
+                '\\' => match chars.next() {
+                    Some(c) => MaskSlot::Literal(c),
+                    None => break,
+                },
+                c => MaskSlot::Literal(c),
+            };
+            slots.push(slot);
+        }
+        InputMask { pattern, slots }
+    }
+
+    /// Pattern the mask was created from.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Fit `text` into the mask: characters not accepted at their position are dropped, literals are inserted,
+    /// text longer than the mask is cut. Literals following the last typed character are appended
+    /// if `append_literals` is true, pass false when the user is deleting characters.
+    pub fn apply(&self, text: &str, append_literals: bool) -> String {
+        let mut masked = String::new();
+        let mut chars = text.chars().peekable();
+        for slot in &self.slots {
+            match slot {
+                MaskSlot::Literal(l) => {
+                    if chars.peek().is_none() && !append_literals {
+                        break;
+                    }
+                    masked.push(*l);
+                    if chars.peek() == Some(l) {
+                        chars.next();
+                    }
+                }
+                slot => {
+                    // Skip rejected characters until an accepted one is found
+                    let Some(c) = chars.by_ref().find(|c| slot.accepts(*c)) else {
+                        break;
+                    };
+                    masked.push(c);
+                }
+            }
+        }
+        masked
+    }
+
+    /// Same as [apply](Self::apply), also moving a cursor placed before the `cursor`-th character of `text`
+    /// over the dropped and inserted characters, so that it stays where the user typed or deleted.
+    pub fn apply_at(&self, text: &str, cursor: usize, append_literals: bool) -> (String, usize) {
+        let prefix: String = text.chars().take(cursor).collect();
+        let cursor = self.apply(&prefix, append_literals).chars().count();
+        (self.apply(text, append_literals), cursor)
+    }
+
+    /// Whether `text` fills the whole mask.
+    pub fn is_complete(&self, text: &str) -> bool {
+        text.chars().count() == self.slots.len()
+            && text
+                .chars()
+                .zip(&self.slots)
+                .all(|(c, slot)| slot.accepts(c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InputMask;
+
+    #[test]
+    fn apply_inserts_literals_and_drops_rejected() {
+        let mask = InputMask::new("AA-####");
+        assert_eq!(mask.apply("ab1234", true), "ab-1234");
+        assert_eq!(mask.apply("a1b-12345", true), "ab-1234");
+        assert_eq!(mask.apply("ab", true), "ab-");
+        assert_eq!(mask.apply("ab", false), "ab");
+        assert_eq!(InputMask::new("\\A#").apply("5", true), "A5");
+    }
+
+    #[test]
+    fn cursor_moves_over_inserted_literals() {
+        let mask = InputMask::new("AA-####");
+        // "c" typed after "ab" of "ab-12"
+        assert_eq!(mask.apply_at("abc-12", 3, true), ("ab-12".to_string(), 3));
+        // "b" typed at the second position of "a-12", the cursor jumps over the inserted dash
+        assert_eq!(mask.apply_at("ab-12", 2, true), ("ab-12".to_string(), 3));
+        // "9" typed in the middle of the digits
+        assert_eq!(mask.apply_at("ab-192", 5, true), ("ab-192".to_string(), 5));
+        // Deleting the first digit keeps the cursor before the remaining ones
+        assert_eq!(mask.apply_at("ab-23", 3, false), ("ab-23".to_string(), 3));
+    }
+
+    #[test]
+    fn is_complete() {
+        let mask = InputMask::new("AA-####");
+        assert!(mask.is_complete("ab-1234"));
+        assert!(!mask.is_complete("ab-123"));
+        assert!(!mask.is_complete("ab-12c4"));
+    }
+}
@@ -1,15 +1,47 @@
+/// Puffin profiling scope named after the enclosing function, compiled out unless the `puffin` feature is enabled.
+macro_rules! profile_function {
+    () => {
+        #[cfg(feature = "puffin")]
+        puffin::profile_function!();
+    };
+}
+
+/// Named puffin profiling scope until the end of the block, compiled out unless the `puffin` feature is enabled.
+macro_rules! profile_scope {
+    ($name:expr) => {
+        #[cfg(feature = "puffin")]
+        puffin::profile_scope!($name);
+    };
+}
+
 pub mod backend;
 pub mod backends;
+pub mod codegen;
+pub mod config_version;
 // pub mod cell;
 // pub mod column;
 // pub mod filter;
 // pub mod sort;
 
-// #[cfg(feature = "gui")]
 pub mod importers;
-pub use importers::csv_xls_importer::CsvXlsImporter;
+pub mod input_mask;
+pub use importers::csv::{CsvImporterConfig, NumberLocale, RaggedRowPolicy, Separator};
+#[cfg(feature = "gui")]
+pub use importers::csv_xls_importer::{CsvXlsImporter, CsvXlsImporterConfig, DuplicateKey};
+#[cfg(feature = "gui")]
+pub use importers::fixed_width::{FixedWidthImporter, FixedWidthImporterConfig};
+pub use importers::mapping_profile::MappingProfile;
 pub use importers::required_column::{RequiredColumn, RequiredColumns};
+pub use importers::validate::{validate_file, ValidationReport, ValidationRules};
+pub use importers::value_transform::ValueTransform;
+pub mod prelude;
+pub mod query;
+#[cfg(feature = "gui")]
 pub mod table_view;
+pub mod util;
+pub mod validation;
 
+pub use encoding_rs;
 pub use rvariant;
+#[cfg(feature = "gui")]
 pub use table_view::TableView;
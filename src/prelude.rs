@@ -0,0 +1,23 @@
+//! Commonly needed types, `use egui_tabular::prelude::*;`.
+
+pub use crate::backend::{
+    CellCoord, ColumnOrigin, ColumnUid, QueryableBackend, RowAction, RowUid, TableBackend,
+    TableEvent, VisualRowIdx,
+};
+pub use crate::backends::local_query::LocalQuery;
+pub use crate::backends::variant::VariantBackend;
+pub use crate::config_version::VersionedConfig;
+pub use crate::importers::csv::{CsvImporterConfig, NumberLocale, Separator};
+#[cfg(feature = "gui")]
+pub use crate::importers::csv_xls_importer::{CsvXlsImporter, CsvXlsImporterConfig};
+#[cfg(feature = "gui")]
+pub use crate::importers::file_picker::FilePicker;
+pub use crate::importers::required_column::{RequiredColumn, RequiredColumns};
+pub use crate::input_mask::InputMask;
+pub use crate::query::{ColumnFilter, FilterOp, QuerySpec, SortKey};
+#[cfg(feature = "gui")]
+pub use crate::table_view::{
+    CardView, LayoutPreset, Lint, TableView, TableViewBuilder, TableViewConfig, TableViewState,
+    TableViewStyle, ToolColumn,
+};
+pub use rvariant::{Variant, VariantTy};
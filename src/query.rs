@@ -0,0 +1,214 @@
+use crate::backend::{CellCoord, ColumnUid, QueryableBackend, RowUid};
+use crate::util::{variant_to_string, variant_ty};
+use rvariant::Variant;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Sorting, filtering and grouping of rows, can be persisted or translated into a remote query.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuerySpec {
+    /// Rows are sorted by the first key, then by the second one and so on.
+    pub sort: Vec<SortKey>,
+    /// Only rows matching all the filters are shown.
+    pub filters: Vec<ColumnFilter>,
+    /// Rows with equal values in this column are kept together, sorted before any other key.
+    pub group_by: Option<ColumnUid>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortKey {
+    pub col_uid: ColumnUid,
+    pub ascending: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ColumnFilter {
+    pub col_uid: ColumnUid,
+    pub op: FilterOp,
+    /// Compared with the text representation of cell values, ignoring case.
+    pub value: String,
+}
+
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, strum::EnumIter, strum::Display,
+)]
+pub enum FilterOp {
+    Contains,
+    Equals,
+    NotEquals,
+    IsEmpty,
+    IsNotEmpty,
+}
+
+impl QuerySpec {
+    pub fn is_empty(&self) -> bool {
+        self.sort.is_empty() && self.filters.is_empty() && self.group_by.is_none()
+    }
+
+    /// Sort direction of a column, None if it is not sorted by.
+    pub fn sort_direction(&self, col_uid: ColumnUid) -> Option<bool> {
+        self.sort
+            .iter()
+            .find(|k| k.col_uid == col_uid)
+            .map(|k| k.ascending)
+    }
+
+    /// Cycle column sort through ascending, descending and not sorted.
+    /// Other sort keys are removed unless `add` is true.
+    pub fn toggle_sort(&mut self, col_uid: ColumnUid, add: bool) {
+        let direction = self.sort_direction(col_uid);
+        if !add {
+            self.sort.retain(|k| k.col_uid == col_uid);
+        }
+        match direction {
+            None => self.sort.push(SortKey {
+                col_uid,
+                ascending: true,
+            }),
+            Some(true) => {
+                if let Some(key) = self.sort.iter_mut().find(|k| k.col_uid == col_uid) {
+                    key.ascending = false;
+                }
+            }
+            Some(false) => self.sort.retain(|k| k.col_uid != col_uid),
+        }
+    }
+
+    /// Copy of the spec without sort keys and filters the backend cannot handle.
+    pub fn supported_by(&self, backend: &dyn QueryableBackend) -> QuerySpec {
+        QuerySpec {
+            sort: self
+                .sort
+                .iter()
+                .filter(|k| backend.supports_sort(k.col_uid))
+                .copied()
+                .collect(),
+            filters: self
+                .filters
+                .iter()
+                .filter(|f| backend.supports_filter(f.col_uid, f.op))
+                .cloned()
+                .collect(),
+            group_by: self
+                .group_by
+                .filter(|col_uid| backend.supports_sort(*col_uid)),
+        }
+    }
+
+    /// Filter and sort `rows`, using `get` to read cell values.
+    pub fn apply(
+        &self,
+        rows: &[RowUid],
+        get: impl Fn(CellCoord) -> Option<Variant>,
+    ) -> Vec<RowUid> {
+        self.apply_with_mixed(rows, get, |_| false)
+    }
+
+    /// Same as [apply](Self::apply), but values of columns for which `is_mixed` returns true
+    /// are sorted by their type first, then by value.
+    pub fn apply_with_mixed(
+        &self,
+        rows: &[RowUid],
+        get: impl Fn(CellCoord) -> Option<Variant>,
+        is_mixed: impl Fn(ColumnUid) -> bool,
+    ) -> Vec<RowUid> {
+        profile_function!();
+        let keys: Vec<SortKey> = self
+            .group_by
+            .map(|col_uid| SortKey {
+                col_uid,
+                ascending: true,
+            })
+            .into_iter()
+            .chain(self.sort.iter().copied())
+            .collect();
+        let mut rows: Vec<(RowUid, Vec<Option<Variant>>)> = rows
+            .iter()
+            .copied()
+            .filter(|row_uid| {
+                self.filters.iter().all(|f| {
+                    f.matches(get(CellCoord {
+                        row_uid: *row_uid,
+                        col_uid: f.col_uid,
+                    }))
+                })
+            })
+            .map(|row_uid| {
+                let values = keys
+                    .iter()
+                    .map(|k| {
+                        get(CellCoord {
+                            row_uid,
+                            col_uid: k.col_uid,
+                        })
+                    })
+                    .collect();
+                (row_uid, values)
+            })
+            .collect();
+        let mixed: Vec<bool> = keys.iter().map(|k| is_mixed(k.col_uid)).collect();
+        // Stable sort keeps the original order of equal rows
+        rows.sort_by(|(_, a), (_, b)| {
+            keys.iter()
+                .zip(&mixed)
+                .zip(a.iter().zip(b))
+                .map(|((k, is_mixed), (a, b))| {
+                    let ordering = if *is_mixed {
+                        compare_types(a.as_ref(), b.as_ref())
+                            .then_with(|| compare_values(a.as_ref(), b.as_ref()))
+                    } else {
+                        compare_values(a.as_ref(), b.as_ref())
+                    };
+                    if k.ascending {
+                        ordering
+                    } else {
+                        ordering.reverse()
+                    }
+                })
+                .find(|o| o.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        rows.into_iter().map(|(row_uid, _)| row_uid).collect()
+    }
+}
+
+impl ColumnFilter {
+    pub fn matches(&self, value: Option<Variant>) -> bool {
+        let text = value
+            .as_ref()
+            .map(variant_to_string)
+            .unwrap_or_default()
+            .to_lowercase();
+        let filter = self.value.to_lowercase();
+        match self.op {
+            FilterOp::Contains => text.contains(&filter),
+            FilterOp::Equals => text == filter,
+            FilterOp::NotEquals => text != filter,
+            FilterOp::IsEmpty => text.trim().is_empty(),
+            FilterOp::IsNotEmpty => !text.trim().is_empty(),
+        }
+    }
+}
+
+/// Values by their type name, values without a type first.
+fn compare_types(a: Option<&Variant>, b: Option<&Variant>) -> Ordering {
+    let a = a.and_then(variant_ty).map(|ty| ty.to_string());
+    let b = b.and_then(variant_ty).map(|ty| ty.to_string());
+    a.cmp(&b)
+}
+
+/// Empty values first, numbers by value, everything else by text ignoring case.
+fn compare_values(a: Option<&Variant>, b: Option<&Variant>) -> Ordering {
+    let a = a.map(variant_to_string).unwrap_or_default();
+    let b = b.map(variant_to_string).unwrap_or_default();
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Less,
+        (false, true) => return Ordering::Greater,
+        _ => {}
+    }
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        _ => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
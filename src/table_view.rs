@@ -1,10 +1,78 @@
+mod activity;
+mod append_row;
+mod banding;
+mod card_view;
+mod cell_style;
+mod clipboard;
+mod column_chooser;
+mod column_settings;
+mod completeness;
 mod config;
+mod data_entry;
+mod drag_select;
+mod events;
+mod example_row;
+mod extract;
+mod fetch_errors;
+mod fill;
+mod find;
+mod frame_time;
+mod history_scrubber;
+mod layout_presets;
+mod navigation;
+mod paste;
+mod pinned_rows;
+mod placeholder;
+mod redaction;
+mod rejected;
+mod renderers;
+mod row_actions;
+mod row_identity;
+mod row_order;
+mod scroll_to;
+mod selection;
+mod shortcuts;
 mod state;
+mod toasts;
+mod tool_column;
+mod type_to_edit;
+mod units;
+mod validation_panel;
 
-use crate::backend::{CellCoord, ColumnUid, OneShotFlags, TableBackend, VisualRowIdx};
-use crate::table_view::state::SelectedRange;
-use egui::{Key, Label, PointerButton, Response, Rounding, ScrollArea, Sense, Stroke, Ui, Widget};
+pub use cell_style::Lint;
+pub use clipboard::{CopyFormat, CopyValues};
+pub use config::{
+    CardView, TableViewBuilder, TableViewConfig, TableViewState, TableViewStyle, ToolColumn,
+};
+pub use layout_presets::LayoutPreset;
+pub use paste::{PasteConfig, PasteMismatch};
+pub(crate) use renderers::builtin_view;
+pub use renderers::{CellEditFn, CellRenderers, CellViewFn};
+pub use shortcuts::SkipChange;
+pub use toasts::ToastKind;
+
+use crate::backend::{
+    CellCoord, CellState, ColumnOrigin, ColumnUid, OneShotFlags, RowUid, TableBackend, TableEvent,
+    VisualRowIdx,
+};
+use crate::query::{QuerySpec, SortKey};
+use crate::table_view::column_settings::ColumnSettings;
+use crate::table_view::fill::FillDirection;
+use crate::table_view::state::{
+    CellDrag, ColumnRename, DisplayColumn, EditCommitMove, RowDrag, SelectedRange, TablePart,
+};
+use crate::table_view::tool_column::ToolColumnAction;
+use egui::scroll_area::ScrollBarVisibility;
+use egui::{
+    Key, Label, PointerButton, Rect, Response, RichText, Rounding, ScrollArea, Sense, Stroke,
+    TextEdit, Ui, Widget,
+};
 use egui_extras::{Column, TableBody};
+use rvariant::VariantTy;
+use std::collections::HashSet;
+use std::ops::Range;
+use std::time::Instant;
+use strum::IntoEnumIterator;
 use tap::Tap;
 
 pub struct TableView {
@@ -22,18 +90,138 @@ impl TableView {
         }
     }
 
+    /// Configure the view in one chain, e.g. `TableView::builder().tool_column_position(ToolColumn::Left).read_only(true).build()`.
+    pub fn builder() -> TableViewBuilder {
+        TableViewBuilder::default()
+    }
+
     pub fn show(&mut self, backend: &mut impl TableBackend, ui: &mut Ui) -> Response {
-        if backend.one_shot_flags().column_info_updated {
-            println!("Updating col info");
-            self.state.columns = backend.used_columns().collect();
-            self.state.columns.sort();
+        if !self.config.frame_time_overlay {
+            return self.show_parts(backend, ui, true, true);
+        }
+        let started = Instant::now();
+        let resp = self.show_parts(backend, ui, true, true);
+        self.frame_time_overlay(ui, &resp, started.elapsed());
+        resp
+    }
+
+    /// Show only the header row, e.g. in a different panel than the body shown with [show_body_only](Self::show_body_only).
+    /// Column widths adjusted in the header are applied to the body, horizontal scrolling is not synchronized.
+    pub fn show_header(&mut self, backend: &mut impl TableBackend, ui: &mut Ui) -> Response {
+        self.show_parts(backend, ui, true, false)
+    }
+
+    /// Show only the rows, either without a header at all or with the header shown by [show_header](Self::show_header).
+    pub fn show_body_only(&mut self, backend: &mut impl TableBackend, ui: &mut Ui) -> Response {
+        self.show_parts(backend, ui, false, true)
+    }
+
+    fn show_parts(
+        &mut self,
+        backend: &mut impl TableBackend,
+        ui: &mut Ui,
+        show_header: bool,
+        show_body: bool,
+    ) -> Response {
+        profile_function!();
+        let table_rect = ui.max_rect();
+        if show_header {
+            self.state.columns_changed = false;
+        }
+        self.state.is_right_to_left = self
+            .config
+            .right_to_left
+            .unwrap_or_else(|| ui.layout().prefer_right_to_left());
+        // Flags are handled and reset once per frame, by the part shown first if shown separately
+        let pass_nr = ui.ctx().cumulative_pass_nr();
+        let handle_flags = self.state.flags_pass_nr != Some(pass_nr);
+        self.state.flags_pass_nr = Some(pass_nr);
+        let now = ui.input(|i| i.time);
+        if handle_flags {
+            if backend.one_shot_flags().column_info_updated {
+                println!("Updating col info");
+                let previous_columns = core::mem::take(&mut self.state.columns);
+                self.state.columns = backend.available_columns().collect();
+                self.state.columns.sort();
+                Self::apply_column_order(&mut self.state.columns, &previous_columns);
+                let used_columns: HashSet<ColumnUid> = backend.used_columns().collect();
+                self.state.unused_columns = self
+                    .state
+                    .columns
+                    .iter()
+                    .copied()
+                    .filter(|col_uid| !used_columns.contains(col_uid))
+                    .collect();
+                if let Some(view_state) = self.state.restore_view_state.take() {
+                    Self::apply_column_order(&mut self.state.columns, &view_state.column_order);
+                    self.state.query = view_state.query;
+                    self.state.query_changed = true;
+                    self.config.hidden_columns = view_state.hidden_columns;
+                    self.state.column_widths = view_state.column_widths;
+                    if !view_state.layout_presets.is_empty() {
+                        self.config.layout_presets = view_state.layout_presets;
+                    }
+                }
+            }
+            self.sync_hidden_columns(backend);
+            if core::mem::take(&mut self.state.query_changed) {
+                if let Some(queryable) = backend.queryable_mut() {
+                    let spec = self.state.query.supported_by(queryable);
+                    queryable.apply(&spec);
+                    backend
+                        .one_shot_flags_mut()
+                        .push_event(TableEvent::QueryChanged(spec));
+                    // Visual indices now point to different rows
+                    self.state.selected_range = None;
+                    self.state.copied_range = None;
+                } else if !self.state.query.is_empty() {
+                    self.notify(
+                        ToastKind::Warning,
+                        "Sorting and filtering are not supported by this backend",
+                    );
+                }
+            }
+            self.handle_row_identity_changes(backend.one_shot_flags());
+            self.invalidate_find_matches(backend.one_shot_flags());
+            self.invalidate_validation_errors(backend.one_shot_flags());
+            self.invalidate_incomplete_rows(backend.one_shot_flags());
+            self.record_activity(&backend.one_shot_flags().cells_updated, now);
+            let flags = backend.one_shot_flags();
+            if flags.column_info_updated
+                || flags.row_set_updated
+                || flags.visible_row_vec_updated
+                || flags.cleared
+            {
+                self.state.row_bands = None;
+            }
         }
         if self.state.columns.is_empty() {
-            return ui.label("No columns");
+            let resp = if self.config.hidden_columns.is_empty() {
+                ui.label("No columns")
+            } else {
+                ui.menu_button("All columns are hidden", |ui| {
+                    self.column_chooser_ui(backend, ui);
+                })
+                .response
+            };
+            self.show_toasts(ui, table_rect);
+            return resp;
+        }
+        if handle_flags {
+            let events = core::mem::take(&mut backend.one_shot_flags_mut().events);
+            self.queue_events(events);
+            *backend.one_shot_flags_mut() = OneShotFlags::default();
         }
-        *backend.one_shot_flags_mut() = OneShotFlags::default();
+        self.update_row_bands(backend);
+        self.resolve_pending_scroll(backend);
 
         let ctx = &ui.ctx().clone();
+        if show_header && show_body && self.is_card_view(ui.available_width()) {
+            self.before_body(backend, ui);
+            let resp = self.card_view_ui(backend, ui);
+            self.after_body(backend, ui, table_rect);
+            return resp;
+        }
         let ui_id = ui.id();
         let style = ui.style().clone();
         let painter = ui.painter().clone();
@@ -44,121 +232,601 @@ impl TableView {
         // Temporarily take out columns Vec, to satisfy borrow checker.
         let columns = core::mem::take(&mut self.state.columns);
         let mut swap_columns = None;
+        let mut use_column = None;
+        let mut change_type = None;
+        let mut materialize = None;
+        let mut finish_rename = None;
         // self.frame_n += 1;
+        if show_body {
+            // Each table part adds to these, read by all of them in the next frame
+            let s = &mut self.state;
+            s.last_hovered_cell = s.hovered_cell.take();
+            s.last_rendered_rows = core::mem::take(&mut s.rendered_rows);
+            self.before_body(backend, ui);
+        }
 
-        ScrollArea::horizontal()
-            .drag_to_scroll(false)
-            .show(ui, |ui| {
-                let mut builder = egui_extras::TableBuilder::new(ui);
-                for _column in &columns {
-                    // Note on clip: At least labels won't try to enlarge cell's area,
-                    // effectively rendering heterogeneous row heights logic useless.
-                    // So disable clipping if heterogeneous row heights are used.
+        let parts = self.table_parts(columns.len());
+        let row_count = backend.row_count();
+        let mut scroll_offset = self.state.pinned_scroll_offset;
+        let is_right_to_left = self.state.is_right_to_left;
+        // Shows one table with a subset of columns, returns its vertical scroll offset if the body was shown.
+        let mut show_table = |ui: &mut Ui, part: &TablePart, scroll_offset: Option<f32>| {
+            // Without a header pinned rows go above the body, otherwise they are shown right after the header
+            if show_body && !show_header && !backend.pinned_rows().is_empty() {
+                self.pinned_rows_ui(backend, ui, part, &columns);
+                ui.separator();
+            }
+            let mut builder = egui_extras::TableBuilder::new(ui);
+            if self.state.is_right_to_left {
+                builder = builder.cell_layout(egui::Layout::right_to_left(egui::Align::Center));
+            }
+            // Changed after applying a layout preset, so that egui_extras forgets resized widths
+            let id_salt = match (self.config.id_salt, self.state.layout_generation) {
+                (id_salt, 0) => id_salt,
+                (id_salt, generation) => Some(egui::Id::new((id_salt, generation))),
+            };
+            match (id_salt, part.is_pinned) {
+                (id_salt, true) => {
+                    // Scrolled together with the other table, which shows the scroll bar
                     builder = builder
-                        .column(Column::auto().clip(!self.config.use_heterogeneous_row_heights));
-                    // builder = builder.column(
-                    //     Column::initial(column.name.len() as f32 * 8.0)
-                    //         .at_least(36.0)
-                    //         .clip(true),
-                    // );
+                        .id_salt((id_salt, "pinned"))
+                        .scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden);
                 }
-                builder
-                    .drag_to_scroll(false) // Drag is used for selection
-                    .striped(true)
-                    .resizable(true)
-                    .max_scroll_height(f32::MAX)
-                    .sense(Sense::click_and_drag().tap_mut(|s| s.focusable = true))
-                    .header(20., |mut h| {
-                        for column_uid in columns.iter().copied() {
-                            let backend_column = backend.column_info(column_uid).unwrap();
-                            let mut painter = None;
-                            let (_, resp) = h.col(|ui| {
-                                // ui.horizontal_centered(|ui| {
-                                Label::new(backend_column.name.as_str())
-                                    .selectable(false)
+                (Some(id_salt), false) => builder = builder.id_salt(id_salt),
+                (None, false) => {}
+            }
+            if show_body {
+                if let Some(row_idx) = self.state.scroll_to_row.take() {
+                    builder = builder.scroll_to_row(row_idx, Some(egui::Align::Center));
+                } else if let Some(offset) = scroll_offset {
+                    builder = builder.vertical_scroll_offset(offset);
+                }
+            }
+            // Without a header, widths are taken from the last separately shown header, if any
+            let widths = &self.state.column_widths;
+            for col_idx in self.state.cell_order(part) {
+                let width = match col_idx {
+                    DisplayColumn::Data(col_idx) => widths.get(&columns[col_idx]).copied(),
+                    DisplayColumn::Tool => self.state.tool_column_width,
+                    DisplayColumn::Actions => self.state.actions_column_width,
+                };
+                // Note on clip: At least labels won't try to enlarge cell's area,
+                // effectively rendering heterogeneous row heights logic useless.
+                // So disable clipping if heterogeneous row heights are used.
+                let column = match (show_header, width) {
+                    (false, Some(width)) => Column::exact(width),
+                    _ if !matches!(col_idx, DisplayColumn::Data(_)) => {
+                        Column::auto().resizable(false)
+                    }
+                    // Restored widths, egui_extras keeps its own once the table was shown
+                    (true, Some(width)) => Column::initial(width).clip(!self.heterogeneous_rows()),
+                    _ => Column::auto().clip(!self.heterogeneous_rows()),
+                };
+                builder = builder.column(column);
+                // builder = builder.column(
+                //     Column::initial(column.name.len() as f32 * 8.0)
+                //         .at_least(36.0)
+                //         .clip(true),
+                // );
+            }
+            builder = builder
+                .drag_to_scroll(false) // Drag is used for selection
+                .striped(self.config.style.striped && self.band_column().is_none())
+                .resizable(show_header)
+                .max_scroll_height(self.config.max_height)
+                .sense(Sense::click_and_drag().tap_mut(|s| s.focusable = true));
+            if !show_body {
+                builder = builder.min_scrolled_height(0.);
+            }
+            if !show_header {
+                let output = builder.body(|body| {
+                    let resp = self.show_body(
+                        backend,
+                        body,
+                        painter.clone(),
+                        (),
+                        ctx,
+                        &style,
+                        ui_id,
+                        &columns,
+                        part,
+                        None,
+                    );
+                    resp_ret = union_responses(resp_ret.take(), resp);
+                });
+                return Some(output.state.offset.y);
+            }
+            let table = builder
+                .header(self.config.style.header_height, |mut h| {
+                    for col_idx in self.state.cell_order(part) {
+                        let col_idx = match col_idx {
+                            DisplayColumn::Data(col_idx) => col_idx,
+                            DisplayColumn::Tool => {
+                                let (_, resp) = h.col(|_| {});
+                                self.state.tool_column_width = Some(resp.rect.width());
+                                continue;
+                            }
+                            DisplayColumn::Actions => {
+                                let (_, resp) = h.col(|_| {});
+                                self.state.actions_column_width = Some(resp.rect.width());
+                                continue;
+                            }
+                        };
+                        let column_uid = columns[col_idx];
+                        let backend_column = backend.column_info(column_uid).unwrap();
+                        let is_used = !self.state.unused_columns.contains(&column_uid);
+                        let is_sortable = backend_column.is_sortable
+                            && backend
+                                .queryable()
+                                .is_some_and(|q| q.supports_sort(column_uid));
+                        let origin = backend.column_origin(column_uid);
+                        let mut toggle_sort = false;
+                        let mut painter = None;
+                        let (_, resp) = h.col(|ui| {
+                            if !is_used {
+                                ui.multiply_opacity(self.config.style.dimmed_opacity);
+                            }
+                            let rename = self
+                                .state
+                                .renaming_column
+                                .as_mut()
+                                .filter(|r| r.col_uid == column_uid);
+                            if let Some(rename) = rename {
+                                let edit = TextEdit::singleline(&mut rename.name)
+                                    .desired_width(f32::INFINITY)
                                     .ui(ui);
+                                if !rename.focus_requested {
+                                    edit.request_focus();
+                                    rename.focus_requested = true;
+                                }
+                                if edit.lost_focus() {
+                                    let cancel = ui.input(|i| i.key_pressed(Key::Escape));
+                                    finish_rename = Some(!cancel);
+                                }
+                            } else {
+                                // ui.horizontal_centered(|ui| {
+                                Label::new(&backend_column.name).selectable(false).ui(ui);
+                                match origin {
+                                    ColumnOrigin::Stored => {}
+                                    ColumnOrigin::Computed => {
+                                        Label::new(RichText::new("ƒ").strong())
+                                            .selectable(false)
+                                            .ui(ui)
+                                            .on_hover_text(
+                                                "Computed from other columns, not stored",
+                                            );
+                                    }
+                                    ColumnOrigin::Materialized => {
+                                        Label::new(RichText::new("ƒ").weak())
+                                            .selectable(false)
+                                            .ui(ui)
+                                            .on_hover_text(
+                                                "Computed once and stored, no longer updated",
+                                            );
+                                    }
+                                }
+                                if is_sortable {
+                                    let direction = self.state.query.sort_direction(column_uid);
+                                    let text = match direction {
+                                        Some(true) => RichText::new("⏶"),
+                                        Some(false) => RichText::new("⏷"),
+                                        None => RichText::new("⬍").weak(),
+                                    };
+                                    toggle_sort = egui::Button::new(text)
+                                        .small()
+                                        .frame(false)
+                                        .ui(ui)
+                                        .on_hover_text(
+                                            "Sort, Shift+click to sort by several columns",
+                                        )
+                                        .clicked();
+                                }
                                 // });
+                            }
 
-                                if painter.is_none() {
-                                    painter = Some(ui.painter().clone());
-                                }
+                            if let (Some(activity), Some(window)) = (
+                                self.state.column_activity.get(&column_uid),
+                                self.config.column_activity,
+                            ) {
+                                activity::paint_activity(
+                                    activity,
+                                    ui.painter(),
+                                    ui.max_rect(),
+                                    now,
+                                    window.as_secs_f64(),
+                                    visual,
+                                );
+                            }
+
+                            if painter.is_none() {
+                                painter = Some(ui.painter().clone());
+                            }
+                        });
+                        if let Some(activity) = self.state.column_activity.get(&column_uid) {
+                            resp.clone().on_hover_text(format!(
+                                "{} cells updated recently",
+                                activity.count()
+                            ));
+                        } else if let Some(description) = backend.column_description(column_uid) {
+                            resp.clone().on_hover_text(description);
+                        }
+
+                        self.state
+                            .column_widths
+                            .insert(column_uid, resp.rect.width());
+
+                        let can_rename =
+                            !self.config.read_only && backend.can_rename_column(column_uid);
+                        if resp.double_clicked_by(PointerButton::Primary) && can_rename {
+                            self.state.renaming_column = Some(ColumnRename {
+                                col_uid: column_uid,
+                                name: backend_column.name.clone(),
+                                focus_requested: false,
                             });
+                        }
 
-                            // Set drag payload for column reordering.
-                            resp.dnd_set_drag_payload(column_uid);
+                        // Set drag payload for column reordering.
+                        resp.dnd_set_drag_payload(column_uid);
 
-                            if resp.dragged() {
-                                egui::popup::show_tooltip_text(
-                                    ctx,
-                                    ui_layer_id,
-                                    "_egui_tabular_column_move".into(),
-                                    backend_column.name.as_str(),
+                        if resp.dragged() {
+                            egui::popup::show_tooltip_text(
+                                ctx,
+                                ui_layer_id,
+                                "_egui_tabular_column_move".into(),
+                                backend_column.name.as_str(),
+                            );
+                        }
+
+                        if resp.hovered() {
+                            if let Some(p) = &painter {
+                                p.rect_filled(
+                                    resp.rect,
+                                    egui::Rounding::ZERO,
+                                    visual.selection.bg_fill.gamma_multiply(0.2),
                                 );
                             }
+                        }
 
-                            if resp.hovered() && backend_column.is_sortable {
-                                if let Some(p) = &painter {
-                                    p.rect_filled(
-                                        resp.rect,
-                                        egui::Rounding::ZERO,
-                                        visual.selection.bg_fill.gamma_multiply(0.2),
-                                    );
-                                }
+                        if toggle_sort {
+                            let add = ctx.input(|i| i.modifiers.shift);
+                            self.state.query.toggle_sort(column_uid, add);
+                            self.state.query_changed = true;
+                        } else if resp.clicked_by(PointerButton::Primary) {
+                            self.select_column(col_idx, row_count, ctx);
+                        }
+
+                        if resp.dnd_hover_payload::<ColumnUid>().is_some() {
+                            if let Some(p) = &painter {
+                                p.rect_filled(
+                                    resp.rect,
+                                    egui::Rounding::ZERO,
+                                    visual.selection.bg_fill.gamma_multiply(0.5),
+                                );
                             }
+                        }
+
+                        if let Some(payload) = resp.dnd_release_payload::<ColumnUid>() {
+                            swap_columns = Some((column_uid, *payload));
+                        }
 
-                            if backend_column.is_sortable && resp.clicked_by(PointerButton::Primary)
+                        resp.context_menu(|ui| {
+                            let mut is_used = is_used;
+                            if ui.checkbox(&mut is_used, "Used").changed() {
+                                use_column = Some((column_uid, is_used));
+                                ui.close_menu();
+                            }
+                            if can_rename && ui.button("Rename…").clicked() {
+                                self.state.renaming_column = Some(ColumnRename {
+                                    col_uid: column_uid,
+                                    name: backend_column.name.clone(),
+                                    focus_requested: false,
+                                });
+                                ui.close_menu();
+                            }
+                            let type_choices = backend.column_type_choices(column_uid);
+                            if !type_choices.is_empty() && !self.config.read_only {
+                                ui.menu_button("Change type…", |ui| {
+                                    for ty in type_choices {
+                                        if ui.button(ty.to_string()).clicked() {
+                                            change_type = Some((column_uid, ty));
+                                            ui.close_menu();
+                                        }
+                                    }
+                                });
+                            }
+                            if origin == ColumnOrigin::Computed
+                                && !self.config.read_only
+                                && ui
+                                    .button("Store computed values")
+                                    .on_hover_text(
+                                        "Values are exported and kept, but no longer updated",
+                                    )
+                                    .clicked()
                             {
-                                println!("Sort {}", backend_column.name);
+                                materialize = Some(column_uid);
+                                ui.close_menu();
                             }
-
-                            if resp.dnd_hover_payload::<ColumnUid>().is_some() {
-                                if let Some(p) = &painter {
-                                    p.rect_filled(
-                                        resp.rect,
-                                        egui::Rounding::ZERO,
-                                        visual.selection.bg_fill.gamma_multiply(0.5),
-                                    );
+                            if self.config.sensitive_columns.contains(&column_uid) {
+                                let masked = self.is_masked(column_uid);
+                                let text = if masked {
+                                    "Reveal values"
+                                } else {
+                                    "Mask values"
+                                };
+                                if ui.button(text).clicked() {
+                                    self.reveal_column(column_uid, masked);
+                                    ui.close_menu();
                                 }
                             }
-
-                            if let Some(payload) = resp.dnd_release_payload::<ColumnUid>() {
-                                swap_columns = Some((column_uid, *payload));
-                            }
-
-                            resp.context_menu(|ui| {
-                                if ui.button("Hide").clicked() {
+                            if self.config.unit_columns.contains_key(&column_uid) {
+                                let expanded = self.is_unit_column_expanded(column_uid);
+                                let text = if expanded {
+                                    "Combine with units"
+                                } else {
+                                    "Show units separately"
+                                };
+                                if ui.button(text).clicked() {
+                                    self.set_unit_column_expanded(column_uid, !expanded);
                                     ui.close_menu();
                                 }
+                            }
+                            if ui.button("Hide").clicked() {
+                                self.config.hidden_columns.insert(column_uid);
+                                self.state.columns_changed = true;
+                                ui.close_menu();
+                            }
+                            ui.menu_button("Columns…", |ui| {
+                                self.state.columns_changed |= column_chooser::column_list_ui(
+                                    backend,
+                                    &mut self.config.hidden_columns,
+                                    ui,
+                                );
                             });
-                        }
+                            if ui.button("Settings…").clicked() {
+                                self.state.column_settings = Some(ColumnSettings::new(column_uid));
+                                ui.close_menu();
+                            }
+                        });
+                    }
 
-                        // Account for header response to calculate total response.
-                        resp_total = Some(h.response());
-                    })
-                    .tap_mut(|table| {
+                    // Account for header response to calculate total response.
+                    resp_total = Some(h.response());
+                })
+                .tap_mut(|table| {
+                    if show_body {
+                        self.pinned_rows_ui(backend, table.ui_mut(), part, &columns);
                         table.ui_mut().separator();
-                    })
-                    .body(|body| {
-                        resp_ret = self.show_body(
-                            backend,
-                            body,
-                            painter,
-                            (),
-                            ctx,
-                            &style,
-                            ui_id,
-                            &columns,
-                            resp_total,
-                        );
-                    });
+                    }
+                });
+            if !show_body {
+                table.body(|_| {});
+                return None;
+            }
+            let output = table.body(|body| {
+                let resp = self.show_body(
+                    backend,
+                    body,
+                    painter.clone(),
+                    (),
+                    ctx,
+                    &style,
+                    ui_id,
+                    &columns,
+                    part,
+                    resp_total.take(),
+                );
+                resp_ret = union_responses(resp_ret.take(), resp);
+            });
+            Some(output.state.offset.y)
+        };
+
+        if let [part] = parts.as_slice() {
+            ScrollArea::horizontal()
+                .drag_to_scroll(false)
+                .show(ui, |ui| show_table(ui, part, None));
+        } else {
+            // Pinned columns on the leading side, the rest scrolls horizontally
+            let layout = if is_right_to_left {
+                egui::Layout::right_to_left(egui::Align::Min)
+            } else {
+                egui::Layout::left_to_right(egui::Align::Min)
+            };
+            ui.with_layout(layout, |ui| {
+                let offset = Some(scroll_offset);
+                let pinned = show_table(ui, &parts[0], offset);
+                let scrolled = ScrollArea::horizontal()
+                    .drag_to_scroll(false)
+                    .show(ui, |ui| show_table(ui, &parts[1], offset))
+                    .inner;
+                // Whichever table was scrolled by the user moves the other one on the next frame
+                scroll_offset = match (pinned, scrolled) {
+                    (Some(pinned), _) if pinned != scroll_offset => pinned,
+                    (_, Some(scrolled)) => scrolled,
+                    _ => scroll_offset,
+                };
             });
+        }
+        self.state.pinned_scroll_offset = scroll_offset;
 
         self.state.columns = columns.tap_mut(|columns| {
             if let Some((c1, c2)) = swap_columns {
                 Self::swap_columns(columns, c1, c2, &mut self.state.selected_range);
+                self.state.selection.clear();
+                self.state.columns_changed = true;
             }
         });
-        resp_ret.unwrap_or_else(|| ui.label("??"))
+        if let Some((col_uid, is_used)) = use_column {
+            backend.use_column(col_uid, is_used);
+            self.state.columns_changed = true;
+        }
+        if let Some((col_uid, ty)) = change_type {
+            let failed = backend.change_column_type(col_uid, ty);
+            self.notify_type_change(backend, col_uid, ty, &failed);
+        }
+        if let Some(col_uid) = materialize {
+            backend.materialize_column(col_uid);
+        }
+        if let Some(commit) = finish_rename {
+            if let Some(rename) = self.state.renaming_column.take() {
+                if commit && !rename.name.is_empty() {
+                    backend.rename_column(rename.col_uid, rename.name);
+                    self.state.columns_changed = true;
+                }
+            }
+        }
+        if show_header {
+            self.show_column_settings(backend, ui);
+        }
+        if show_body {
+            self.after_body(backend, ui, table_rect);
+        }
+        if !self.state.column_activity.is_empty() {
+            // Let the bars move and fade out without new updates
+            if let Some(window) = self.config.column_activity {
+                ctx.request_repaint_after(window / 10);
+            }
+        }
+        resp_ret.or(resp_total).unwrap_or_else(|| ui.label("??"))
+    }
+
+    /// Panels shown above the rows, the same in table and card layouts.
+    fn before_body(&mut self, backend: &mut impl TableBackend, ui: &mut Ui) {
+        fetch_errors::failed_fetches_ui(backend, ui);
+        self.incomplete_rows_ui(backend, ui);
+        self.handle_find_shortcuts(ui.ctx());
+        self.find_bar_ui(backend, ui);
+    }
+
+    /// Panels shown below the rows and input handled once they are shown, the same in table and card layouts.
+    fn after_body(&mut self, backend: &mut impl TableBackend, ui: &mut Ui, table_rect: Rect) {
+        let ctx = &ui.ctx().clone();
+        self.append_row_ui(backend, ui);
+        self.validation_panel_ui(backend, ui);
+        self.handle_data_entry(backend, ctx);
+        self.handle_selection_moves(backend, ctx);
+        self.handle_type_to_edit(backend, ctx);
+        self.handle_shortcuts(backend, ctx);
+        self.handle_copy(backend, ctx);
+        self.handle_paste(backend, ui);
+        self.show_paste_dialog(backend, ui);
+        self.show_toasts(ui, table_rect);
+        self.report_selected_rows(backend);
+    }
+
+    /// Row heights are measured only if enabled and no columns are pinned, so that rows of both tables line up.
+    fn heterogeneous_rows(&self) -> bool {
+        self.config.use_heterogeneous_row_heights && self.config.pinned_columns == 0
+    }
+
+    /// One table showing all the columns, or pinned and scrolled ones in this order.
+    fn table_parts(&self, column_count: usize) -> Vec<TablePart> {
+        let pinned = self.config.pinned_columns.min(column_count);
+        let has_actions = self.state.row_actions.is_some();
+        if pinned == 0 || pinned == column_count {
+            return vec![TablePart {
+                columns: 0..column_count,
+                tool_column: self.config.tool_column,
+                actions_column: has_actions,
+                is_pinned: false,
+            }];
+        }
+        // Pinned table is on the right in right to left layout
+        let rtl = self.state.is_right_to_left;
+        let (left_pinned, right_pinned) = (!rtl, rtl);
+        let tool_column_pinned = match self.config.tool_column {
+            ToolColumn::Left => left_pinned,
+            ToolColumn::Right => right_pinned,
+            ToolColumn::Hidden => false,
+        };
+        let tool_column = |pinned: bool| {
+            if tool_column_pinned == pinned {
+                self.config.tool_column
+            } else {
+                ToolColumn::Hidden
+            }
+        };
+        vec![
+            TablePart {
+                columns: 0..pinned,
+                tool_column: tool_column(true),
+                actions_column: has_actions && right_pinned,
+                is_pinned: true,
+            },
+            TablePart {
+                columns: pinned..column_count,
+                tool_column: tool_column(false),
+                actions_column: has_actions && left_pinned,
+                is_pinned: false,
+            },
+        ]
+    }
+
+    /// True during the frame in which columns were renamed, reordered or (un)used from the header.
+    pub fn columns_changed(&self) -> bool {
+        self.state.columns_changed
+    }
+
+    /// Current sorting, filtering and grouping.
+    pub fn query_spec(&self) -> &QuerySpec {
+        &self.state.query
+    }
+
+    /// Replace sorting, filtering and grouping, passed to the backend on the next frame.
+    /// Grouping column is also used for row banding, unless [TableViewConfig::band_by_column] is set.
+    pub fn apply_query_spec(&mut self, spec: QuerySpec) {
+        self.state.query = spec;
+        self.state.query_changed = true;
+    }
+
+    /// Tell the user how many cells could not be converted and select the first of them.
+    fn notify_type_change(
+        &mut self,
+        backend: &impl TableBackend,
+        col_uid: ColumnUid,
+        ty: VariantTy,
+        failed: &[CellCoord],
+    ) {
+        let name = backend
+            .column_info(col_uid)
+            .map(|c| c.name.clone())
+            .unwrap_or_default();
+        let Some(first) = failed.first() else {
+            self.notify(ToastKind::Info, format!("Column {name} converted to {ty}"));
+            return;
+        };
+        self.notify(
+            ToastKind::Warning,
+            format!(
+                "Column {name} converted to {ty}, {} cells could not be converted and were left as is",
+                failed.len()
+            ),
+        );
+        let row_idx = (0..backend.row_count())
+            .map(VisualRowIdx)
+            .find(|row_idx| backend.row_uid(*row_idx) == Some(first.row_uid));
+        if let Some(row_idx) = row_idx {
+            self.select_cell(row_idx, Some(col_uid));
+        }
+    }
+
+    /// Select a cell and scroll to it on the next frame, first column is used if `col_uid` is None or not found.
+    pub(crate) fn select_cell(&mut self, row_idx: VisualRowIdx, col_uid: Option<ColumnUid>) {
+        let col_idx = col_uid
+            .and_then(|col_uid| self.state.columns.iter().position(|c| *c == col_uid))
+            .unwrap_or(0);
+        self.state.selected_range = Some(SelectedRange::single(row_idx.0, col_idx));
+        self.state.scroll_to_row = Some(row_idx.0);
+    }
+
+    /// Put columns present in `order` first and in the same order, leaving the rest as is.
+    fn apply_column_order(columns: &mut Vec<ColumnUid>, order: &[ColumnUid]) {
+        let mut ordered: Vec<ColumnUid> = order
+            .iter()
+            .copied()
+            .filter(|col_uid| columns.contains(col_uid))
+            .collect();
+        ordered.extend(columns.iter().filter(|col_uid| !order.contains(col_uid)));
+        *columns = ordered;
     }
 
     fn swap_columns(
@@ -194,25 +862,67 @@ impl TableView {
     fn show_body(
         &mut self,
         backend: &mut impl TableBackend,
-        body: TableBody<'_>,
-        mut _painter: egui::Painter,
+        mut body: TableBody<'_>,
+        painter: egui::Painter,
         _commands: (),
         ctx: &egui::Context,
         style: &egui::Style,
         _ui_id: egui::Id,
         columns: &[ColumnUid],
+        part: &TablePart,
         mut resp_total: Option<Response>,
     ) -> Option<Response> {
+        profile_function!();
         let visual = &style.visuals;
+        let heterogeneous_rows = self.heterogeneous_rows();
+        self.finish_fill_drag(backend, body.ui_mut());
+        self.drag_autoscroll(body.ui_mut());
+        let drag_select_pos = self.drag_select_pos(body.ui_mut());
+        let fill_handle_range = self.fill_handle_range();
+        let fill_preview = self.fill_preview();
+        let rejected_flash = self.rejected_flash(ctx);
+        let folded_units = self.folded_unit_columns();
+        let renderers = &self.config.cell_renderers;
         let s = &mut self.state;
         let row_heights = core::mem::take(&mut s.row_heights);
         let mut row_heights_updates = Vec::new();
         // let pointer_primary_down = ctx.input(|i| i.pointer.button_down(PointerButton::Primary));
         let mut commit_edit = None;
+        let mut cancel_edit = None;
+        let mut copy_as = None;
+        let mut fill = None;
+        let mut move_value = None;
+        let mut move_row = None;
+        let mut hovered_cell = None;
+        let mut tool_action = None;
+        let mut visible_range: Option<Range<usize>> = None;
+        let read_only = self.config.read_only;
+        // Where the copied block would land if pasted into the hovered cell
+        let paste_preview =
+            s.copied_range
+                .zip(s.last_hovered_cell)
+                .map(|(copied, (row_idx, col_idx))| {
+                    SelectedRange::block(row_idx, col_idx, copied.height(), copied.width())
+                });
+        let copy_stroke = Stroke::new(1., visual.selection.stroke.color);
+        let example_row_order =
+            (self.config.example_row && backend.row_count() == 0).then(|| s.cell_order(part));
+        // Rows shown last frame are always drawn, newly visible ones only until the budget is spent
+        let render_deadline = self
+            .config
+            .render_budget
+            .map(|budget| Instant::now() + budget);
+        let previously_rendered = s.last_rendered_rows.clone();
+        let mut rendered_rows = HashSet::new();
 
         let render_fn = |mut row: egui_extras::TableRow| {
             let row_idx = row.index();
+            visible_range = Some(match visible_range.take() {
+                Some(r) => r.start.min(row_idx)..r.end.max(row_idx + 1),
+                None => row_idx..row_idx + 1,
+            });
             let row_uid = backend.row_uid(VisualRowIdx(row_idx)).unwrap();
+            let is_row_skipped = backend.is_row_skipped(row_uid);
             let is_editing_cell_on_this_row = s
                 .selected_range
                 .map(|r| r.is_editing() && r.contains_row(row_idx))
@@ -221,8 +931,101 @@ impl TableView {
                 row.set_selected(true);
             }
 
+            let is_new_row = render_deadline.is_some() && !previously_rendered.contains(&row_uid);
+            let mut row_deferred = false;
+
             let mut next_frame_row_height = self.config.minimum_row_height;
-            for (col_idx, col_uid) in columns.iter().copied().enumerate() {
+            for col_idx in s.cell_order(part) {
+                let col_idx = match col_idx {
+                    DisplayColumn::Data(col_idx) => col_idx,
+                    DisplayColumn::Actions => {
+                        row.col(|ui| {
+                            if let Some(row_actions) = &s.row_actions {
+                                if let Some(action) = row_actions(row_uid, ui) {
+                                    s.events.push(TableEvent::RowAction { row_uid, action });
+                                }
+                            }
+                        });
+                        continue;
+                    }
+                    DisplayColumn::Tool => {
+                        let (_, resp) = row.col(|ui| {
+                            tool_column::tool_column_ui(
+                                ui,
+                                row_idx,
+                                is_row_skipped,
+                                s.incomplete_rows.contains(&row_uid),
+                            );
+                        });
+                        // Select the whole row, or stretch selection to it with Shift
+                        if resp.clicked_by(PointerButton::Primary) && !columns.is_empty() {
+                            let last_col_idx = columns.len() - 1;
+                            let (shift, command) =
+                                ctx.input(|i| (i.modifiers.shift, i.modifiers.command));
+                            match &mut s.selected_range {
+                                Some(r) if shift => {
+                                    r.stretch_to(row_idx, 0);
+                                    r.stretch_to(row_idx, last_col_idx);
+                                }
+                                _ => s.select(
+                                    SelectedRange::block(row_idx, 0, 1, columns.len()),
+                                    command,
+                                ),
+                            }
+                        }
+                        // Drag a row by its number to reorder, sorting would put it back in place
+                        // unless sorted by the order column, which is renumbered
+                        let is_sorted = !s.query.sort.is_empty() || s.query.group_by.is_some();
+                        let sorted_by_order = self
+                            .config
+                            .order_column
+                            .is_some_and(|c| row_order::is_sorted_by_order(&s.query, c));
+                        if !read_only && is_sorted && !sorted_by_order {
+                            if resp.dragged() {
+                                resp.show_tooltip_text("Clear sorting to reorder rows");
+                            }
+                        } else if !read_only {
+                            resp.dnd_set_drag_payload(RowDrag { row_uid });
+                        }
+                        if resp.dnd_hover_payload::<RowDrag>().is_some() {
+                            painter.rect_filled(
+                                resp.rect,
+                                Rounding::ZERO,
+                                visual.selection.bg_fill.gamma_multiply(0.5),
+                            );
+                        }
+                        if let Some(payload) = resp.dnd_release_payload::<RowDrag>() {
+                            if payload.row_uid != row_uid {
+                                move_row = Some((payload.row_uid, row_idx));
+                            }
+                        }
+                        resp.context_menu(|ui| {
+                            // Other selected rows, if the clicked one is among them
+                            let selected_rows: Vec<RowUid> = s
+                                .selected_range
+                                .filter(|r| r.contains_row(row_idx) && r.height() > 1)
+                                .map(|r| {
+                                    (r.row_start()..=r.row_end())
+                                        .filter_map(|idx| backend.row_uid(VisualRowIdx(idx)))
+                                        .collect()
+                                })
+                                .unwrap_or_default();
+                            tool_column::tool_column_context_menu_ui(
+                                ui,
+                                backend,
+                                row_uid,
+                                read_only,
+                                &selected_rows,
+                                &mut tool_action,
+                            );
+                        });
+                        continue;
+                    }
+                };
+                let col_uid = columns[col_idx];
+                let masked = self.config.sensitive_columns.contains(&col_uid)
+                    && !s.reveal_all
+                    && !s.revealed_columns.contains(&col_uid);
                 let current_cell = SelectedRange::single(row_idx, col_idx);
                 let (
                     is_first_row_in_selection,
@@ -240,9 +1043,30 @@ impl TableView {
                         )
                     })
                     .unwrap_or((false, false, false, false));
+                let is_current_cell_in_selection = is_current_cell_in_selection
+                    || s.selection
+                        .ranges()
+                        .iter()
+                        .any(|r| r.contains(row_idx, col_idx));
+                let mut fill_handle_rect = None;
+                let error = backend.cell_error(CellCoord { row_uid, col_uid });
                 let (rect, resp) = row.col(|ui| {
                     let ui_max_rect = ui.max_rect();
+                    if s.unused_columns.contains(&col_uid) || is_row_skipped {
+                        ui.multiply_opacity(self.config.style.dimmed_opacity);
+                    }
 
+                    if s.row_bands
+                        .as_ref()
+                        .map(|b| b.is_banded(row_idx))
+                        .unwrap_or(false)
+                    {
+                        ui.painter().rect_filled(
+                            ui_max_rect,
+                            Rounding::ZERO,
+                            visual.faint_bg_color,
+                        );
+                    }
                     if is_current_cell_in_selection && !is_editing_cell_on_this_row {
                         // Light orange background inside selection
                         ui.painter().rect_filled(
@@ -251,6 +1075,22 @@ impl TableView {
                             visual.warn_fg_color.gamma_multiply(0.2),
                         );
                     }
+                    if error.is_some() {
+                        ui.painter().rect_filled(
+                            ui_max_rect,
+                            Rounding::ZERO,
+                            visual.error_fg_color.gamma_multiply(0.15),
+                        );
+                    }
+                    if let Some(find) = s.find.as_ref().filter(|f| f.is_match(row_idx, col_idx)) {
+                        let stroke = if find.is_current_match(row_idx, col_idx) {
+                            Stroke::new(2., visual.selection.stroke.color)
+                        } else {
+                            Stroke::new(1., visual.selection.stroke.color.gamma_multiply(0.5))
+                        };
+                        ui.painter()
+                            .rect_stroke(ui_max_rect.shrink(1.), Rounding::ZERO, stroke);
+                    }
 
                     // Lines on first and last row of selection
                     let st = Stroke {
@@ -275,40 +1115,264 @@ impl TableView {
 
                     if is_editing_current_cell {
                         let coord = CellCoord { row_uid, col_uid };
-                        let _resp = backend.show_cell_editor(coord, ui);
-                        if ui.input(|i| i.key_pressed(Key::Enter)) {
-                            commit_edit = Some(coord)
+                        let resp = renderers.cell_editor_ui(backend, coord, &mut s.custom_edit, ui);
+                        if let Some(resp) =
+                            resp.filter(|_| core::mem::take(&mut s.focus_cell_editor))
+                        {
+                            type_to_edit::focus_cell_editor(&resp);
+                        }
+                        let direction = ui.input(|i| {
+                            if i.key_pressed(Key::Enter) {
+                                Some(EditCommitMove::Down)
+                            } else if i.key_pressed(Key::Tab) && i.modifiers.shift {
+                                Some(EditCommitMove::Left)
+                            } else if i.key_pressed(Key::Tab) {
+                                Some(EditCommitMove::Right)
+                            } else {
+                                None
+                            }
+                        });
+                        if let Some(direction) = direction {
+                            commit_edit = Some((coord, direction))
                         }
                         if ui.input(|i| i.key_pressed(Key::Escape)) {
+                            cancel_edit = Some(coord);
                             s.selected_range = None;
                         }
                     } else {
-                        ui.add_enabled_ui(false, |ui| {
-                            backend.show_cell_view(CellCoord { row_uid, col_uid }, ui);
-                        });
+                        let coord = CellCoord { row_uid, col_uid };
+                        let deferred =
+                            is_new_row && render_deadline.is_some_and(|d| Instant::now() > d);
+                        row_deferred |= deferred;
+                        match backend.cell_state(coord) {
+                            _ if masked => redaction::masked_value_ui(ui),
+                            _ if deferred => placeholder::loading_placeholder_ui(ui),
+                            CellState::Available => {
+                                let style = s.cell_styles.get(&coord);
+                                cell_style::styled_cell_ui(style, ui, |ui| {
+                                    ui.add_enabled_ui(false, |ui| {
+                                        renderers.cell_view_ui(backend, coord, ui);
+                                    });
+                                });
+                                if let Some(unit_col_uid) = folded_units.get(&col_uid) {
+                                    let coord = CellCoord {
+                                        row_uid,
+                                        col_uid: *unit_col_uid,
+                                    };
+                                    units::unit_suffix_ui(backend, coord, ui);
+                                }
+                            }
+                            CellState::Loading => placeholder::loading_placeholder_ui(ui),
+                        }
+                    }
+                    if let Some(copied) = &s.copied_range {
+                        clipboard::paint_range_edges(
+                            ui.painter(),
+                            ui_max_rect,
+                            copied,
+                            row_idx,
+                            col_idx,
+                            s.is_right_to_left,
+                            copy_stroke,
+                            true,
+                        );
+                    }
+                    if let Some(preview) = &paste_preview {
+                        clipboard::paint_range_edges(
+                            ui.painter(),
+                            ui_max_rect,
+                            preview,
+                            row_idx,
+                            col_idx,
+                            s.is_right_to_left,
+                            copy_stroke,
+                            false,
+                        );
+                    }
+                    if let Some((ranges, opacity)) = &rejected_flash {
+                        let color = visual.error_fg_color.gamma_multiply(*opacity);
+                        for range in ranges.iter().filter(|r| r.contains(row_idx, col_idx)) {
+                            ui.painter().rect_filled(
+                                ui_max_rect,
+                                Rounding::ZERO,
+                                color.gamma_multiply(0.2),
+                            );
+                            clipboard::paint_range_edges(
+                                ui.painter(),
+                                ui_max_rect,
+                                range,
+                                row_idx,
+                                col_idx,
+                                s.is_right_to_left,
+                                Stroke::new(2., color),
+                                false,
+                            );
+                        }
+                    }
+                    if let Some(preview) = &fill_preview {
+                        clipboard::paint_range_edges(
+                            ui.painter(),
+                            ui_max_rect,
+                            preview,
+                            row_idx,
+                            col_idx,
+                            s.is_right_to_left,
+                            copy_stroke,
+                            true,
+                        );
+                    }
+                    if fill_handle_range
+                        .is_some_and(|r| r.row_end() == row_idx && r.col_end() == col_idx)
+                    {
+                        fill_handle_rect = Some(fill::fill_handle_ui(ui, ui_max_rect));
+                    }
+                    if is_row_skipped {
+                        // Cross out skipped rows
+                        ui.painter().hline(
+                            xr,
+                            ui_max_rect.center().y,
+                            Stroke::new(1., visual.strong_text_color()),
+                        );
                     }
                 });
                 next_frame_row_height = rect.height().max(next_frame_row_height);
+                if s.scroll_to_cell == Some(CellCoord { row_uid, col_uid }) {
+                    s.scroll_to_cell = None;
+                    resp.scroll_to_me(Some(egui::Align::Center));
+                }
+                let style_tooltip = s
+                    .cell_styles
+                    .get(&CellCoord { row_uid, col_uid })
+                    .and_then(|style| style.tooltip());
+                let tooltip = [error, style_tooltip]
+                    .into_iter()
+                    .flatten()
+                    .reduce(|a, b| format!("{a}\n{b}"));
+                let resp = match tooltip {
+                    Some(tooltip) if !masked => resp.on_hover_text(tooltip),
+                    _ => resp,
+                };
 
+                if resp.hovered() {
+                    hovered_cell = Some((row_idx, col_idx));
+                }
+                let (shift, command) = ctx.input(|i| (i.modifiers.shift, i.modifiers.command));
                 if resp.clicked_by(PointerButton::Primary) {
-                    if let Some(r) = &mut s.selected_range {
-                        if ctx.input(|i| i.modifiers.shift) {
+                    if command && !shift {
+                        // Ctrl+click adds or removes a cell, keeping other ranges
+                        s.toggle_cell(row_idx, col_idx);
+                    } else if let Some(r) = &mut s.selected_range {
+                        if shift {
                             r.stretch_to(row_idx, col_idx);
                         } else {
                             if *r == current_cell {
-                                r.set_editing(true);
+                                r.set_editing(!read_only && !masked);
+                                if read_only || masked {
+                                    let now = ctx.input(|i| i.time);
+                                    s.flash_rejected([current_cell], now);
+                                }
                             } else {
                                 s.selected_range = Some(current_cell);
                             }
+                            s.selection.clear();
                         }
                     } else {
                         s.selected_range = Some(current_cell);
                     }
                 }
-                if resp.double_clicked_by(PointerButton::Primary) {}
+
+                let press_origin = ctx.input(|i| i.pointer.press_origin());
+                let is_fill_handle_pressed = fill_handle_rect
+                    .zip(press_origin)
+                    .is_some_and(|(rect, pos)| rect.expand(2.).contains(pos));
+                if resp.drag_started_by(PointerButton::Primary) && is_fill_handle_pressed {
+                    s.fill_drag_to = Some(row_idx);
+                } else if resp.drag_started_by(PointerButton::Primary)
+                    && !ctx.input(|i| i.modifiers.alt)
+                    && !is_editing_current_cell
+                {
+                    s.drag_select_anchor = Some((row_idx, col_idx));
+                    s.select(current_cell, command);
+                }
+                if let (Some(_), Some(pos)) = (s.fill_drag_to, drag_select_pos) {
+                    if resp.rect.y_range().contains(pos.y) {
+                        s.fill_drag_to = Some(row_idx);
+                    }
+                }
+                if let Some(((anchor_row, anchor_col), pos)) =
+                    s.drag_select_anchor.zip(drag_select_pos)
+                {
+                    if resp.rect.contains(pos) {
+                        s.selected_range = Some(SelectedRange::spanning(
+                            anchor_row, anchor_col, row_idx, col_idx,
+                        ));
+                    }
+                }
+
+                // Alt + drag moves cell value to another row within the same column.
+                if ctx.input(|i| i.modifiers.alt) && !is_editing_current_cell && !read_only {
+                    resp.dnd_set_drag_payload(CellDrag { col_uid, row_idx });
+                }
+                if let Some(payload) = resp.dnd_hover_payload::<CellDrag>() {
+                    if payload.col_uid == col_uid {
+                        painter.rect_filled(
+                            resp.rect,
+                            Rounding::ZERO,
+                            visual.selection.bg_fill.gamma_multiply(0.5),
+                        );
+                    }
+                }
+                if let Some(payload) = resp.dnd_release_payload::<CellDrag>() {
+                    if payload.col_uid == col_uid {
+                        move_value = Some((col_uid, payload.row_idx, row_idx));
+                    }
+                }
+                if is_current_cell_in_selection {
+                    resp.context_menu(|ui| {
+                        ui.menu_button("Copy as…", |ui| {
+                            ui.checkbox(&mut self.config.copy_with_headers, "Include headers");
+                            ui.radio_value(&mut s.copy_as_values, None, "As configured");
+                            for values in CopyValues::iter() {
+                                ui.radio_value(
+                                    &mut s.copy_as_values,
+                                    Some(values),
+                                    values.to_string(),
+                                );
+                            }
+                            ui.separator();
+                            for format in CopyFormat::iter() {
+                                if ui.button(format.to_string()).clicked() {
+                                    copy_as = Some((format, s.copy_as_values));
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                        let selected = s.selected_range.unwrap_or(current_cell);
+                        ui.add_enabled_ui(!read_only, |ui| {
+                            if ui
+                                .add_enabled(selected.height() > 1, egui::Button::new("Fill down"))
+                                .clicked()
+                            {
+                                fill = Some(FillDirection::Down);
+                                ui.close_menu();
+                            }
+                            if ui
+                                .add_enabled(selected.width() > 1, egui::Button::new("Fill right"))
+                                .clicked()
+                            {
+                                fill = Some(FillDirection::Right);
+                                ui.close_menu();
+                            }
+                        });
+                    });
+                }
             } // for col_uid in used_columns
 
-            if self.config.use_heterogeneous_row_heights {
+            if render_deadline.is_some() && !row_deferred {
+                rendered_rows.insert(row_uid);
+            }
+            // Placeholders are shorter than the cells they stand for, keep the previous height meanwhile
+            if heterogeneous_rows && !row_deferred {
                 if let Some(prev_row_height) = row_heights.get(&row_uid) {
                     if (next_frame_row_height - *prev_row_height).abs() > 0.1 {
                         row_heights_updates.push((row_uid, next_frame_row_height));
@@ -326,7 +1390,16 @@ impl TableView {
             }
         };
 
-        if self.config.use_heterogeneous_row_heights {
+        if let Some(cell_order) = example_row_order {
+            example_row::example_row_ui(
+                backend,
+                &mut body,
+                columns,
+                &cell_order,
+                self.config.minimum_row_height,
+            );
+        }
+        if heterogeneous_rows {
             body.heterogeneous_rows(
                 (0..backend.row_count()).map(|idx| {
                     let row_uid = backend.row_uid(VisualRowIdx(idx)).unwrap();
@@ -350,12 +1423,89 @@ impl TableView {
                 render_fn,
             );
         }
+        s.hovered_cell = s.hovered_cell.or(hovered_cell);
+        s.rendered_rows.extend(rendered_rows);
+        s.visible_rows = visible_range.clone();
+        if let Some(range) = visible_range {
+            backend.hint_visible_range(VisualRowIdx(range.start)..VisualRowIdx(range.end));
+        }
 
-        if let Some(coord) = commit_edit {
-            backend.commit_cell_edit(coord);
+        if let Some(coord) = cancel_edit {
+            s.custom_edit = None;
+            backend.cancel_cell_edit(coord);
+        }
+        if let Some((coord, direction)) = commit_edit {
+            renderers::commit_cell_edit(backend, coord, &mut s.custom_edit);
+            if let Some(committed) = s.selected_range.take() {
+                if direction == EditCommitMove::Down && self.config.data_entry {
+                    // Data entry continues editing below, appending rows as needed
+                    s.committed_cell = Some((committed.row_start(), committed.col_start()));
+                } else {
+                    let next = committed.moved(direction, backend.row_count(), columns.len());
+                    s.scroll_to_row = Some(next.row_start());
+                    s.selected_range = Some(next);
+                }
+            }
+            s.row_bands = None;
+        }
+        if let Some((row_uid, to)) = move_row {
+            match self.config.order_column {
+                Some(order_column) => {
+                    row_order::renumber_rows(backend, order_column, row_uid, to);
+                    // Renumbered rows are put in place by sorting
+                    if !row_order::is_sorted_by_order(&s.query, order_column) {
+                        s.query.sort = vec![SortKey {
+                            col_uid: order_column,
+                            ascending: true,
+                        }];
+                        s.query_changed = true;
+                    }
+                }
+                None => backend.move_row(row_uid, VisualRowIdx(to)),
+            }
             s.selected_range = None;
+            s.copied_range = None;
+            s.row_bands = None;
+        }
+        if let Some((col_uid, from, to)) = move_value {
+            backend.move_cell_value(col_uid, VisualRowIdx(from), VisualRowIdx(to));
+            s.row_bands = None;
+        }
+        match tool_action {
+            Some(ToolColumnAction::SkipRow { row_uid, skip }) => {
+                backend.skip_row(row_uid, skip);
+                s.skip_changes.push(SkipChange::Rows {
+                    row_uids: vec![row_uid],
+                    skip,
+                });
+            }
+            Some(ToolColumnAction::PinRows { row_uids, pin }) => {
+                for row_uid in row_uids {
+                    backend.pin_row(row_uid, pin);
+                }
+            }
+            Some(ToolColumnAction::RemoveRows { row_uids }) => {
+                backend.remove_rows(row_uids);
+                s.selected_range = None;
+                s.copied_range = None;
+                s.row_bands = None;
+            }
+            None => {}
+        }
+        if let Some(direction) = fill {
+            self.fill_selection(backend, direction);
+        }
+        if let Some((format, values)) = copy_as {
+            self.copy_selection(backend, ctx, format, values);
         }
 
         resp_total
     }
 }
+
+fn union_responses(a: Option<Response>, b: Option<Response>) -> Option<Response> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.union(b)),
+        (a, b) => a.or(b),
+    }
+}
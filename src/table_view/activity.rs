@@ -0,0 +1,92 @@
+use crate::backend::CellCoord;
+use egui::{Painter, Rect, Rounding, Vec2, Visuals};
+use std::collections::{HashMap, VecDeque};
+
+/// Activity window is split into this many bars.
+const BARS: usize = 10;
+/// Maximum width of the sparkline in the header cell.
+const MAX_WIDTH: f32 = 30.0;
+
+/// Recent updates of one column, as (input time, number of cells updated), oldest first.
+#[derive(Default)]
+pub(super) struct ColumnActivity {
+    updates: VecDeque<(f64, usize)>,
+}
+
+impl ColumnActivity {
+    /// Number of cells updated during the last `window` seconds.
+    pub(super) fn count(&self) -> usize {
+        self.updates.iter().map(|(_, count)| count).sum()
+    }
+
+    fn bars(&self, now: f64, window: f64) -> [usize; BARS] {
+        let mut bars = [0; BARS];
+        for (time, count) in &self.updates {
+            let age = (now - time).max(0.);
+            let idx = BARS - 1 - ((age / window * BARS as f64) as usize).min(BARS - 1);
+            bars[idx] += count;
+        }
+        bars
+    }
+}
+
+impl super::TableView {
+    /// Remember cells updated by the backend in this frame and forget the ones older than the activity window.
+    pub(super) fn record_activity(&mut self, cells_updated: &[CellCoord], now: f64) {
+        let Some(window) = self.config.column_activity else {
+            return;
+        };
+        let mut counts: HashMap<_, usize> = HashMap::new();
+        for coord in cells_updated {
+            *counts.entry(coord.col_uid).or_default() += 1;
+        }
+        for (col_uid, count) in counts {
+            self.state
+                .column_activity
+                .entry(col_uid)
+                .or_default()
+                .updates
+                .push_back((now, count));
+        }
+        let window = window.as_secs_f64();
+        self.state.column_activity.retain(|_, activity| {
+            while let Some((time, _)) = activity.updates.front() {
+                if now - time > window {
+                    activity.updates.pop_front();
+                } else {
+                    break;
+                }
+            }
+            !activity.updates.is_empty()
+        });
+    }
+}
+
+/// Bars of updated cell counts over time on the right side of a header cell, newest on the right.
+pub(super) fn paint_activity(
+    activity: &ColumnActivity,
+    painter: &Painter,
+    rect: Rect,
+    now: f64,
+    window: f64,
+    visuals: &Visuals,
+) {
+    let bars = activity.bars(now, window);
+    let max = bars.iter().copied().max().unwrap_or(0);
+    if max == 0 {
+        return;
+    }
+    let rect = rect.shrink(2.);
+    let width = MAX_WIDTH.min(rect.width() / 3.);
+    let bar_width = width / BARS as f32;
+    let color = visuals.selection.bg_fill;
+    for (idx, count) in bars.iter().enumerate() {
+        if *count == 0 {
+            continue;
+        }
+        let height = rect.height() * *count as f32 / max as f32;
+        let min = rect.right_bottom() - Vec2::new(width - bar_width * idx as f32, height);
+        let bar = Rect::from_min_size(min, Vec2::new(bar_width * 0.8, height));
+        painter.rect_filled(bar, Rounding::ZERO, color);
+    }
+}
@@ -0,0 +1,52 @@
+use crate::backend::{TableBackend, VisualRowIdx};
+use egui::{Align2, Rounding, Sense, TextStyle, Ui, Vec2};
+
+impl super::TableView {
+    /// Faint "+" row below the table, double-clicking it appends a row with default values
+    /// and starts editing its first editable cell.
+    pub(super) fn append_row_ui(&mut self, backend: &mut impl TableBackend, ui: &mut Ui) {
+        if self.config.read_only || !backend.can_append_rows() {
+            return;
+        }
+        let size = Vec2::new(ui.available_width(), self.config.minimum_row_height);
+        let (rect, resp) = ui.allocate_exact_size(size, Sense::click());
+        let visuals = ui.visuals();
+        if resp.hovered() {
+            ui.painter()
+                .rect_filled(rect, Rounding::ZERO, visuals.faint_bg_color);
+        }
+        ui.painter().text(
+            rect.left_center() + Vec2::new(4., 0.),
+            Align2::LEFT_CENTER,
+            "+",
+            TextStyle::Body.resolve(ui.style()),
+            visuals.weak_text_color(),
+        );
+        if !resp
+            .on_hover_text("Double-click to add a row")
+            .double_clicked()
+        {
+            return;
+        }
+        let Some(row_uid) = backend.append_row() else {
+            return;
+        };
+        // New row may be placed anywhere if rows are sorted
+        let row_idx = (0..backend.row_count())
+            .map(VisualRowIdx)
+            .find(|idx| backend.row_uid(*idx) == Some(row_uid));
+        if let Some(row_idx) = row_idx {
+            let col_uid = self
+                .state
+                .columns
+                .iter()
+                .copied()
+                .find(|col_uid| self.is_column_editable(backend, *col_uid));
+            self.select_cell(row_idx, col_uid);
+            if let (Some(_), Some(r)) = (col_uid, &mut self.state.selected_range) {
+                r.set_editing(true);
+            }
+        }
+        self.state.row_bands = None;
+    }
+}
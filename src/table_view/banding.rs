@@ -0,0 +1,48 @@
+use crate::backend::{CellCoord, ColumnUid, TableBackend, VisualRowIdx};
+
+/// Row backgrounds alternating each time a value in the column changes.
+pub(super) struct RowBands {
+    col_uid: ColumnUid,
+    /// True for rows with the alternative background, indexed by visual row index.
+    bands: Vec<bool>,
+}
+
+impl RowBands {
+    pub(super) fn is_banded(&self, row_idx: usize) -> bool {
+        self.bands.get(row_idx).copied().unwrap_or(false)
+    }
+}
+
+impl super::TableView {
+    /// Column used for banding, either set in the config or grouped by.
+    pub(super) fn band_column(&self) -> Option<ColumnUid> {
+        self.config.band_by_column.or(self.state.query.group_by)
+    }
+
+    /// Recompute row bands if band column was changed or bands were invalidated.
+    pub(super) fn update_row_bands(&mut self, backend: &impl TableBackend) {
+        let Some(col_uid) = self.band_column() else {
+            self.state.row_bands = None;
+            return;
+        };
+        if let Some(row_bands) = &self.state.row_bands {
+            if row_bands.col_uid == col_uid && row_bands.bands.len() == backend.row_count() {
+                return;
+            }
+        }
+        let mut bands = Vec::with_capacity(backend.row_count());
+        let mut is_banded = false;
+        let mut prev_value = None;
+        for row_idx in 0..backend.row_count() {
+            let value = backend
+                .row_uid(VisualRowIdx(row_idx))
+                .and_then(|row_uid| backend.get(CellCoord { row_uid, col_uid }));
+            if row_idx > 0 && value != prev_value {
+                is_banded = !is_banded;
+            }
+            bands.push(is_banded);
+            prev_value = value;
+        }
+        self.state.row_bands = Some(RowBands { col_uid, bands });
+    }
+}
@@ -0,0 +1,219 @@
+use crate::backend::{CellCoord, CellState, ColumnUid, TableBackend, VisualRowIdx};
+use crate::table_view::config::CardView;
+use crate::table_view::state::SelectedRange;
+use crate::table_view::{
+    cell_style, placeholder, redaction, renderers, tool_column, type_to_edit, units,
+};
+use egui::{
+    Frame, Key, Label, Margin, PointerButton, Response, RichText, Rounding, ScrollArea, Sense, Ui,
+    UiBuilder, Vec2,
+};
+
+/// Space between the card border and its lines.
+const CARD_MARGIN: f32 = 6.;
+
+impl super::TableView {
+    /// Whether rows are shown as cards in the given width, see [CardView].
+    pub(super) fn is_card_view(&self, available_width: f32) -> bool {
+        match self.config.card_view {
+            CardView::Never => false,
+            CardView::BelowWidth(width) => available_width < width,
+            CardView::Always => true,
+        }
+    }
+
+    /// Each row as a card of "column: value" lines, with the same selection and editing as in the table.
+    pub(super) fn card_view_ui(
+        &mut self,
+        backend: &mut impl TableBackend,
+        ui: &mut Ui,
+    ) -> Response {
+        let columns: Vec<(usize, ColumnUid)> = self
+            .state
+            .columns
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, col_uid)| !self.state.unused_columns.contains(col_uid))
+            .collect();
+        let spacing = ui.spacing().item_spacing.y;
+        let line_height = self
+            .config
+            .minimum_row_height
+            .max(ui.spacing().interact_size.y);
+        // Row number line and one line per column
+        let content_height = (columns.len() + 1) as f32 * (line_height + spacing) - spacing;
+        let card_height = content_height + 2. * CARD_MARGIN;
+        let label_width = (ui.available_width() * 0.35).min(160.);
+        let read_only = self.config.read_only;
+        let folded_units = self.folded_unit_columns();
+        let mut commit_edit = None;
+        let mut cancel_edit = None;
+        let rejected_flash = self.rejected_flash(ui.ctx());
+
+        let mut scroll_area = ScrollArea::vertical()
+            .id_salt((self.config.id_salt, "cards"))
+            .max_height(self.config.max_height)
+            .auto_shrink([false, true]);
+        if let Some(row_idx) = self.state.scroll_to_row.take() {
+            // Cards show all columns, no horizontal scrolling needed
+            self.state.scroll_to_cell = None;
+            scroll_area =
+                scroll_area.vertical_scroll_offset(row_idx as f32 * (card_height + spacing));
+        }
+        let output = scroll_area.show_rows(ui, card_height, backend.row_count(), |ui, rows| {
+            for row_idx in rows {
+                let Some(row_uid) = backend.row_uid(VisualRowIdx(row_idx)) else {
+                    continue;
+                };
+                let is_row_skipped = backend.is_row_skipped(row_uid);
+                Frame::group(ui.style())
+                    .inner_margin(Margin::same(CARD_MARGIN))
+                    .show(ui, |ui| {
+                        ui.set_height(content_height);
+                        ui.set_width(ui.available_width());
+                        if is_row_skipped {
+                            ui.multiply_opacity(self.config.style.dimmed_opacity);
+                        }
+                        ui.horizontal(|ui| {
+                            ui.set_height(line_height);
+                            tool_column::tool_column_ui(
+                                ui,
+                                row_idx,
+                                is_row_skipped,
+                                self.state.incomplete_rows.contains(&row_uid),
+                            );
+                        });
+                        for (col_idx, col_uid) in &columns {
+                            let (col_idx, col_uid) = (*col_idx, *col_uid);
+                            let coord = CellCoord { row_uid, col_uid };
+                            let name = backend
+                                .column_info(col_uid)
+                                .map(|c| c.name.clone())
+                                .unwrap_or_default();
+                            let masked = self.is_masked(col_uid);
+                            let current_cell = SelectedRange::single(row_idx, col_idx);
+                            let is_editing = self
+                                .state
+                                .selected_range
+                                .map(|r| r.is_editing() && r == current_cell)
+                                .unwrap_or(false);
+                            ui.horizontal(|ui| {
+                                ui.set_height(line_height);
+                                ui.add_sized(
+                                    [label_width, line_height],
+                                    Label::new(RichText::new(name).weak()).truncate(),
+                                );
+                                let (rect, resp) = ui.allocate_exact_size(
+                                    Vec2::new(ui.available_width(), line_height),
+                                    Sense::click(),
+                                );
+                                if self.state.is_selected(row_idx, col_idx) && !is_editing {
+                                    ui.painter().rect_filled(
+                                        rect,
+                                        Rounding::ZERO,
+                                        ui.visuals().warn_fg_color.gamma_multiply(0.2),
+                                    );
+                                }
+                                if let Some((ranges, opacity)) = &rejected_flash {
+                                    if ranges.iter().any(|r| r.contains(row_idx, col_idx)) {
+                                        ui.painter().rect_filled(
+                                            rect,
+                                            Rounding::ZERO,
+                                            ui.visuals()
+                                                .error_fg_color
+                                                .gamma_multiply(*opacity * 0.2),
+                                        );
+                                    }
+                                }
+                                ui.allocate_new_ui(UiBuilder::new().max_rect(rect), |ui| {
+                                    if is_editing {
+                                        let resp = self.config.cell_renderers.cell_editor_ui(
+                                            backend,
+                                            coord,
+                                            &mut self.state.custom_edit,
+                                            ui,
+                                        );
+                                        if let Some(resp) = resp.filter(|_| {
+                                            core::mem::take(&mut self.state.focus_cell_editor)
+                                        }) {
+                                            type_to_edit::focus_cell_editor(&resp);
+                                        }
+                                        if ui.input(|i| i.key_pressed(Key::Enter)) {
+                                            commit_edit = Some(coord);
+                                        }
+                                        if ui.input(|i| i.key_pressed(Key::Escape)) {
+                                            cancel_edit = Some(coord);
+                                            self.state.selected_range = None;
+                                        }
+                                        return;
+                                    }
+                                    match backend.cell_state(coord) {
+                                        _ if masked => redaction::masked_value_ui(ui),
+                                        CellState::Available => {
+                                            let style = self.state.cell_styles.get(&coord);
+                                            cell_style::styled_cell_ui(style, ui, |ui| {
+                                                ui.add_enabled_ui(false, |ui| {
+                                                    self.config
+                                                        .cell_renderers
+                                                        .cell_view_ui(backend, coord, ui);
+                                                });
+                                            });
+                                            if let Some(unit_col_uid) = folded_units.get(&col_uid) {
+                                                let coord = CellCoord {
+                                                    row_uid,
+                                                    col_uid: *unit_col_uid,
+                                                };
+                                                units::unit_suffix_ui(backend, coord, ui);
+                                            }
+                                        }
+                                        CellState::Loading => {
+                                            placeholder::loading_placeholder_ui(ui)
+                                        }
+                                    }
+                                });
+                                if resp.clicked_by(PointerButton::Primary) {
+                                    self.click_card_cell(row_idx, col_idx, read_only || masked, ui);
+                                }
+                            });
+                        }
+                    });
+            }
+        });
+
+        if let Some(coord) = cancel_edit {
+            self.state.custom_edit = None;
+            backend.cancel_cell_edit(coord);
+        }
+        if let Some(coord) = commit_edit {
+            renderers::commit_cell_edit(backend, coord, &mut self.state.custom_edit);
+            self.state.committed_cell = self
+                .state
+                .selected_range
+                .take()
+                .map(|r| (r.row_start(), r.col_start()));
+            self.state.row_bands = None;
+        }
+        ui.interact(output.inner_rect, ui.id().with("cards"), Sense::hover())
+    }
+
+    /// Same as clicking a table cell: select it, start editing on a second click, Shift stretches
+    /// and Ctrl toggles the selection.
+    fn click_card_cell(&mut self, row_idx: usize, col_idx: usize, read_only: bool, ui: &Ui) {
+        let current_cell = SelectedRange::single(row_idx, col_idx);
+        let (shift, command) = ui.input(|i| (i.modifiers.shift, i.modifiers.command));
+        match &mut self.state.selected_range {
+            _ if command && !shift => self.state.toggle_cell(row_idx, col_idx),
+            Some(r) if shift => r.stretch_to(row_idx, col_idx),
+            Some(r) if *r == current_cell => {
+                r.set_editing(!read_only);
+                self.state.selection.clear();
+                if read_only && self.config.flash_rejected_actions {
+                    let now = ui.input(|i| i.time);
+                    self.state.flash_rejected([current_cell], now);
+                }
+            }
+            _ => self.state.select(current_cell, false),
+        }
+    }
+}
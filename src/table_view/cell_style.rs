@@ -0,0 +1,128 @@
+use crate::backend::CellCoord;
+use egui::{Align2, Color32, FontId, Rounding, Stroke, TextFormat, Ui};
+
+/// Decoration added to a cell by the application, on top of what the backend draws.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Lint {
+    /// Tint the cell background, e.g. red for values failing a check.
+    Background(Color32),
+    /// Frame around the cell.
+    Outline(Stroke),
+    /// Small icon in the bottom corner of the cell.
+    AddIcon { color: Color32, icon: &'static str },
+}
+
+/// Styling of one cell, see [TableView::add_cell_lint](super::TableView::add_cell_lint).
+#[derive(Clone, Debug, Default)]
+pub(super) struct CellStyle {
+    lints: Vec<Lint>,
+    tooltips: Vec<String>,
+    text_format: Option<TextFormat>,
+}
+
+impl super::TableView {
+    /// Decorate a cell without changing the backend, the same lint is only added once.
+    /// Styles are keyed by row and column uids, so they follow rows when sorting,
+    /// and are dropped when the backend is cleared or reloaded without keeping row identity.
+    pub fn add_cell_lint(&mut self, coord: CellCoord, lint: Lint) {
+        let lints = &mut self.state.cell_styles.entry(coord).or_default().lints;
+        if !lints.contains(&lint) {
+            lints.push(lint);
+        }
+    }
+
+    /// Remove one lint added with [add_cell_lint](Self::add_cell_lint), other styling of the cell is kept.
+    pub fn remove_cell_lint(&mut self, coord: CellCoord, lint: &Lint) {
+        let Some(style) = self.state.cell_styles.get_mut(&coord) else {
+            return;
+        };
+        style.lints.retain(|l| l != lint);
+        if style.lints.is_empty() && style.tooltips.is_empty() && style.text_format.is_none() {
+            self.state.cell_styles.remove(&coord);
+        }
+    }
+
+    /// Text shown when hovering the cell, several tooltips are shown one per line.
+    pub fn add_cell_tooltip(&mut self, coord: CellCoord, tooltip: impl Into<String>) {
+        self.state
+            .cell_styles
+            .entry(coord)
+            .or_default()
+            .tooltips
+            .push(tooltip.into());
+    }
+
+    /// Font, color and background of the cell text. Backends draw the text themselves,
+    /// so only the font, color and background are used, other fields are ignored.
+    pub fn set_cell_text_format(&mut self, coord: CellCoord, format: TextFormat) {
+        self.state.cell_styles.entry(coord).or_default().text_format = Some(format);
+    }
+
+    /// Remove lints, tooltips and text format of one cell.
+    pub fn clear_cell_lints_for(&mut self, coord: CellCoord) {
+        self.state.cell_styles.remove(&coord);
+    }
+
+    /// Remove lints, tooltips and text formats of all cells.
+    pub fn clear_cell_lints(&mut self) {
+        self.state.cell_styles.clear();
+    }
+}
+
+impl CellStyle {
+    /// Tooltips joined into one text, None if there are none.
+    pub(super) fn tooltip(&self) -> Option<String> {
+        (!self.tooltips.is_empty()).then(|| self.tooltips.join("\n"))
+    }
+}
+
+/// Show cell contents with the application provided style applied around them.
+pub(super) fn styled_cell_ui(
+    style: Option<&CellStyle>,
+    ui: &mut Ui,
+    add_contents: impl FnOnce(&mut Ui),
+) {
+    let Some(style) = style else {
+        add_contents(ui);
+        return;
+    };
+    let rect = ui.max_rect();
+    if let Some(format) = &style.text_format {
+        if format.background != Color32::TRANSPARENT {
+            ui.painter()
+                .rect_filled(rect, Rounding::ZERO, format.background);
+        }
+    }
+    for lint in &style.lints {
+        if let Lint::Background(color) = lint {
+            ui.painter().rect_filled(rect, Rounding::ZERO, *color);
+        }
+    }
+    ui.scope(|ui| {
+        if let Some(format) = &style.text_format {
+            ui.style_mut().override_font_id = Some(format.font_id.clone());
+            ui.visuals_mut().override_text_color = Some(format.color);
+        }
+        add_contents(ui);
+    });
+    let mut icon_pos = rect.right_bottom();
+    for lint in &style.lints {
+        match lint {
+            Lint::Outline(stroke) => {
+                ui.painter()
+                    .rect_stroke(rect.shrink(1.), Rounding::ZERO, *stroke);
+            }
+            Lint::AddIcon { color, icon } => {
+                let icon_rect = ui.painter().text(
+                    icon_pos,
+                    Align2::RIGHT_BOTTOM,
+                    icon,
+                    FontId::proportional(10.),
+                    *color,
+                );
+                icon_pos.x = icon_rect.left() - 1.;
+            }
+            Lint::Background(_) => {}
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use crate::backend::{CellCoord, ColumnUid, TableBackend, VisualRowIdx};
+use crate::table_view::state::SelectedRange;
+use crate::util::variant_to_string;
+use egui::{Event, Id, Key, Painter, Rect, Shape, Stroke};
+use itertools::Itertools;
+use rvariant::Variant;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(strum::EnumIter, strum::Display, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CopyFormat {
+    #[strum(to_string = "TSV")]
+    Tsv,
+    #[strum(to_string = "CSV")]
+    Csv,
+    /// GitHub flavored Markdown table.
+    Markdown,
+    #[strum(to_string = "HTML")]
+    Html,
+}
+
+/// Text copied for each cell.
+#[derive(
+    strum::EnumIter,
+    strum::Display,
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum CopyValues {
+    /// Canonical values, e.g. `1234.5`, for pasting back or importing elsewhere.
+    #[default]
+    #[strum(to_string = "Raw values")]
+    Raw,
+    /// Values as shown in the table, with units, for pasting into documents.
+    #[strum(to_string = "Formatted values")]
+    Formatted,
+}
+
+impl super::TableView {
+    /// Serialize selected cells, returns None if nothing is selected.
+    /// Several ranges are copied as one block of all their rows and columns, cells not selected are left empty.
+    /// Values are raw or formatted according to [copy_values](super::TableViewConfig::copy_values)
+    /// and [column_copy_values](super::TableViewConfig::column_copy_values).
+    pub fn selection_as(&self, backend: &impl TableBackend, format: CopyFormat) -> Option<String> {
+        self.selection_as_with(backend, format, None)
+    }
+
+    /// Same as [selection_as](Self::selection_as), with all columns copied as `values` if provided.
+    pub fn selection_as_with(
+        &self,
+        backend: &impl TableBackend,
+        format: CopyFormat,
+        values: Option<CopyValues>,
+    ) -> Option<String> {
+        self.state.selected_range?;
+        let (columns, cells) = self.copied_cells(backend);
+        let folded_units = self.folded_unit_columns();
+        let headers = self
+            .config
+            .copy_with_headers
+            .then(|| column_names(backend, &columns));
+        let rows = cells
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|coord| {
+                        let Some(coord) = coord else {
+                            return String::new();
+                        };
+                        let values = values.unwrap_or_else(|| {
+                            self.config
+                                .column_copy_values
+                                .get(&coord.col_uid)
+                                .copied()
+                                .unwrap_or(self.config.copy_values)
+                        });
+                        copied_text(backend, coord, values, &folded_units)
+                    })
+                    .collect()
+            })
+            .collect();
+        Some(format_block(format, headers, rows))
+    }
+
+    /// Selected values as they are, in the same block as [selection_as](Self::selection_as) copies.
+    fn selection_values(&self, backend: &impl TableBackend) -> Vec<Vec<Variant>> {
+        let (columns, cells) = self.copied_cells(backend);
+        let headers = self.config.copy_with_headers.then(|| {
+            column_names(backend, &columns)
+                .into_iter()
+                .map(Variant::Str)
+                .collect()
+        });
+        let rows = cells.into_iter().map(|row| {
+            row.into_iter()
+                .map(|coord| coord.and_then(|c| backend.get(c)).unwrap_or(Variant::Empty))
+                .collect()
+        });
+        headers.into_iter().chain(rows).collect()
+    }
+
+    /// Columns of the copied block and its cells by row, None for cells not selected or not copied.
+    #[allow(clippy::type_complexity)]
+    pub(super) fn copied_cells(
+        &self,
+        backend: &impl TableBackend,
+    ) -> (Vec<ColumnUid>, Vec<Vec<Option<CellCoord>>>) {
+        let columns: Vec<(usize, ColumnUid)> = self
+            .state
+            .selected_cols()
+            .iter()
+            .filter_map(|col_idx| Some((*col_idx, *self.state.columns.get(*col_idx)?)))
+            .collect();
+        let cells = self
+            .state
+            .selected_rows()
+            .into_iter()
+            .filter_map(|row_idx| Some((row_idx, backend.row_uid(VisualRowIdx(row_idx))?)))
+            .map(|(row_idx, row_uid)| {
+                columns
+                    .iter()
+                    .map(|(col_idx, col_uid)| {
+                        let is_copied = self.state.is_selected(row_idx, *col_idx)
+                            && (!self.config.sensitive_columns.contains(col_uid)
+                                || self.config.copy_sensitive);
+                        is_copied.then_some(CellCoord {
+                            row_uid,
+                            col_uid: *col_uid,
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        let columns = columns.into_iter().map(|(_, col_uid)| col_uid).collect();
+        (columns, cells)
+    }
+
+    /// Copy selection in the given format, along with its typed values for pasting into another table.
+    pub(super) fn copy_selection(
+        &mut self,
+        backend: &impl TableBackend,
+        ctx: &egui::Context,
+        format: CopyFormat,
+        values: Option<CopyValues>,
+    ) {
+        let Some(text) = self.selection_as_with(backend, format, values) else {
+            return;
+        };
+        TypedClipboard::store(ctx, &text, self.selection_values(backend));
+        ctx.copy_text(text);
+        self.state.copied_range = self.state.selected_range;
+    }
+}
+
+/// Text copied for a cell, formatted values get the unit of a folded unit column appended.
+fn copied_text(
+    backend: &impl TableBackend,
+    coord: CellCoord,
+    values: CopyValues,
+    folded_units: &HashMap<ColumnUid, ColumnUid>,
+) -> String {
+    match values {
+        CopyValues::Raw => backend
+            .get(coord)
+            .map(|value| variant_to_string(&value))
+            .unwrap_or_default(),
+        CopyValues::Formatted => {
+            let text = backend.display_text(coord).unwrap_or_default();
+            let unit = folded_units
+                .get(&coord.col_uid)
+                .and_then(|unit_col_uid| {
+                    backend.get(CellCoord {
+                        row_uid: coord.row_uid,
+                        col_uid: *unit_col_uid,
+                    })
+                })
+                .map(|unit| variant_to_string(&unit))
+                .filter(|unit| !unit.is_empty());
+            match unit {
+                Some(unit) if !text.is_empty() => format!("{text} {unit}"),
+                _ => text,
+            }
+        }
+    }
+}
+
+impl super::TableView {
+    /// Copy selection as TSV on Ctrl+C and remember copied range, Escape forgets it.
+    pub(super) fn handle_copy(&mut self, backend: &impl TableBackend, ctx: &egui::Context) {
+        if self
+            .state
+            .selected_range
+            .filter(|r| !r.is_editing())
+            .is_none()
+        {
+            return;
+        }
+        let (copy, escape) = ctx.input(|i| {
+            (
+                i.events.iter().any(|e| matches!(e, Event::Copy)),
+                i.key_pressed(Key::Escape),
+            )
+        });
+        if copy {
+            self.copy_selection(backend, ctx, CopyFormat::Tsv, None);
+        } else if escape {
+            self.state.copied_range = None;
+        }
+    }
+}
+
+/// Values of the last copy, kept in egui memory next to the copied text, so that pasting
+/// into any table of the same app keeps numbers, enums and lists as they are instead of parsing the text.
+#[derive(Clone)]
+pub(super) struct TypedClipboard {
+    text: String,
+    rows: Arc<Vec<Vec<Variant>>>,
+}
+
+impl TypedClipboard {
+    fn id() -> Id {
+        Id::new("_egui_tabular_typed_clipboard")
+    }
+
+    fn store(ctx: &egui::Context, text: &str, rows: Vec<Vec<Variant>>) {
+        let clipboard = TypedClipboard {
+            text: normalize_line_endings(text),
+            rows: Arc::new(rows),
+        };
+        ctx.data_mut(|d| d.insert_temp(Self::id(), clipboard));
+    }
+
+    /// Values copied along with `text`, None if the clipboard was since overwritten by something else.
+    pub(super) fn load(ctx: &egui::Context, text: &str) -> Option<Vec<Vec<Variant>>> {
+        let clipboard: TypedClipboard = ctx.data(|d| d.get_temp(Self::id()))?;
+        (clipboard.text == normalize_line_endings(text)).then(|| clipboard.rows.to_vec())
+    }
+}
+
+/// Clipboards on some platforms convert line endings.
+fn normalize_line_endings(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .trim_end_matches('\n')
+        .to_string()
+}
+
+fn column_names(backend: &impl TableBackend, columns: &[ColumnUid]) -> Vec<String> {
+    columns
+        .iter()
+        .map(|col_uid| {
+            backend
+                .column_info(*col_uid)
+                .map(|c| c.name.clone())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Draw edges of the cell that lie on the border of the range.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn paint_range_edges(
+    painter: &Painter,
+    rect: Rect,
+    range: &SelectedRange,
+    row_idx: usize,
+    col_idx: usize,
+    is_right_to_left: bool,
+    stroke: Stroke,
+    dashed: bool,
+) {
+    if !range.contains(row_idx, col_idx) {
+        return;
+    }
+    let (left_col, right_col) = if is_right_to_left {
+        (range.col_end(), range.col_start())
+    } else {
+        (range.col_start(), range.col_end())
+    };
+    let mut edges = vec![];
+    if row_idx == range.row_start() {
+        edges.push([rect.left_top(), rect.right_top()]);
+    }
+    if row_idx == range.row_end() {
+        edges.push([rect.left_bottom(), rect.right_bottom()]);
+    }
+    if col_idx == left_col {
+        edges.push([rect.left_top(), rect.left_bottom()]);
+    }
+    if col_idx == right_col {
+        edges.push([rect.right_top(), rect.right_bottom()]);
+    }
+    for edge in edges {
+        if dashed {
+            painter.extend(Shape::dashed_line(&edge, stroke, 4., 3.));
+        } else {
+            painter.line_segment(edge, stroke);
+        }
+    }
+}
+
+pub(super) fn format_block(
+    format: CopyFormat,
+    headers: Option<Vec<String>>,
+    rows: Vec<Vec<String>>,
+) -> String {
+    match format {
+        CopyFormat::Tsv => delimited(b'\t', headers, rows),
+        CopyFormat::Csv => delimited(b',', headers, rows),
+        CopyFormat::Markdown => {
+            let escape = |s: &String| s.replace('|', "\\|").replace('\n', "<br>");
+            let width = rows.first().map(|r| r.len()).unwrap_or_default();
+            let headers = headers.unwrap_or_else(|| vec![String::new(); width]);
+            let mut text = format!("| {} |\n", headers.iter().map(escape).join(" | "));
+            text += &format!("|{}\n", " --- |".repeat(headers.len()));
+            for row in &rows {
+                text += &format!("| {} |\n", row.iter().map(escape).join(" | "));
+            }
+            text
+        }
+        CopyFormat::Html => {
+            let escape = |s: &String| {
+                s.replace('&', "&amp;")
+                    .replace('<', "&lt;")
+                    .replace('>', "&gt;")
+                    .replace('"', "&quot;")
+            };
+            let mut text = String::from("<table>\n");
+            if let Some(headers) = headers {
+                text += &format!(
+                    "<tr>{}</tr>\n",
+                    headers
+                        .iter()
+                        .map(|h| format!("<th>{}</th>", escape(h)))
+                        .join("")
+                );
+            }
+            for row in &rows {
+                text += &format!(
+                    "<tr>{}</tr>\n",
+                    row.iter()
+                        .map(|c| format!("<td>{}</td>", escape(c)))
+                        .join("")
+                );
+            }
+            text += "</table>\n";
+            text
+        }
+    }
+}
+
+fn delimited(delimiter: u8, headers: Option<Vec<String>>, rows: Vec<Vec<String>>) -> String {
+    let mut wr = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .terminator(csv::Terminator::Any(b'\n'))
+        .from_writer(vec![]);
+    for row in headers.into_iter().chain(rows) {
+        // Writing into a Vec cannot fail
+        let _ = wr.write_record(&row);
+    }
+    let bytes = wr.into_inner().unwrap_or_default();
+    String::from_utf8(bytes).unwrap_or_default()
+}
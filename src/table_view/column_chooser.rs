@@ -0,0 +1,82 @@
+use crate::backend::{ColumnUid, TableBackend};
+use egui::Ui;
+use std::collections::HashSet;
+
+impl super::TableView {
+    /// Hide a column from the view or show it again, after the visible ones.
+    pub fn set_column_hidden(&mut self, col_uid: ColumnUid, hidden: bool) {
+        let changed = if hidden {
+            self.config.hidden_columns.insert(col_uid)
+        } else {
+            self.config.hidden_columns.remove(&col_uid)
+        };
+        self.state.columns_changed |= changed;
+    }
+
+    pub fn is_column_hidden(&self, col_uid: ColumnUid) -> bool {
+        self.config.hidden_columns.contains(&col_uid)
+    }
+
+    /// Checkbox list of all available columns, unchecking one hides it.
+    pub fn column_chooser_ui(&mut self, backend: &impl TableBackend, ui: &mut Ui) {
+        self.state.columns_changed |= column_list_ui(backend, &mut self.config.hidden_columns, ui);
+    }
+
+    /// Bring shown columns in line with [hidden_columns](super::TableViewConfig::hidden_columns),
+    /// columns shown again are added at the end, expanded unit columns right after their values.
+    pub(super) fn sync_hidden_columns(&mut self, backend: &impl TableBackend) {
+        let mut hidden = self.config.hidden_columns.clone();
+        hidden.extend(self.folded_unit_columns().into_values());
+        let mut shown: Vec<ColumnUid> = backend
+            .available_columns()
+            .filter(|col_uid| !hidden.contains(col_uid) && !self.state.columns.contains(col_uid))
+            .collect();
+        shown.sort();
+        let units: Vec<(ColumnUid, Option<ColumnUid>)> = shown
+            .iter()
+            .map(|col_uid| (*col_uid, self.expanded_value_column(*col_uid)))
+            .collect();
+        let columns = &mut self.state.columns;
+        let len = columns.len();
+        columns.retain(|col_uid| !hidden.contains(col_uid));
+        let changed = columns.len() != len || !shown.is_empty();
+        for (col_uid, value_col_uid) in units {
+            match value_col_uid.and_then(|v| columns.iter().position(|c| *c == v)) {
+                Some(idx) => columns.insert(idx + 1, col_uid),
+                None => columns.push(col_uid),
+            }
+        }
+        if changed {
+            // Column indices now point to different columns
+            self.state.selected_range = None;
+            self.state.copied_range = None;
+        }
+    }
+}
+
+/// Returns true if any column was hidden or shown.
+pub(super) fn column_list_ui(
+    backend: &impl TableBackend,
+    hidden_columns: &mut HashSet<ColumnUid>,
+    ui: &mut Ui,
+) -> bool {
+    let mut columns: Vec<ColumnUid> = backend.available_columns().collect();
+    columns.sort();
+    let mut changed = false;
+    for col_uid in columns {
+        let name = backend
+            .column_info(col_uid)
+            .map(|c| c.name.as_str())
+            .unwrap_or_default();
+        let mut is_shown = !hidden_columns.contains(&col_uid);
+        if ui.checkbox(&mut is_shown, name).changed() {
+            if is_shown {
+                hidden_columns.remove(&col_uid);
+            } else {
+                hidden_columns.insert(col_uid);
+            }
+            changed = true;
+        }
+    }
+    changed
+}
@@ -0,0 +1,85 @@
+use crate::backend::{ColumnUid, TableBackend};
+use egui::{Key, RichText, TextEdit, Ui, Widget, Window};
+
+pub(super) struct ColumnSettings {
+    pub(super) col_uid: ColumnUid,
+    /// Synonym being typed in, not yet added.
+    new_synonym: String,
+}
+
+impl ColumnSettings {
+    pub(super) fn new(col_uid: ColumnUid) -> Self {
+        ColumnSettings {
+            col_uid,
+            new_synonym: String::new(),
+        }
+    }
+}
+
+impl super::TableView {
+    /// Show column settings window if it was opened from the header context menu.
+    pub(super) fn show_column_settings(&mut self, backend: &mut impl TableBackend, ui: &Ui) {
+        let Some(settings) = &mut self.state.column_settings else {
+            return;
+        };
+        let col_uid = settings.col_uid;
+        let Some(backend_column) = backend.column_info(col_uid) else {
+            self.state.column_settings = None;
+            return;
+        };
+        let title = format!("Column \"{}\"", backend_column.name);
+        let ty = backend_column.ty.clone();
+        let mut synonyms = backend.column_synonyms(col_uid).map(|s| s.to_vec());
+        let mut synonyms_changed = false;
+
+        let mut is_open = true;
+        Window::new(title)
+            .id(ui.id().with("_egui_tabular_column_settings"))
+            .open(&mut is_open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ui.ctx(), |ui| {
+                ui.label(format!("Type: {ty}"));
+                let Some(synonyms) = &mut synonyms else {
+                    return;
+                };
+                ui.separator();
+                ui.label(RichText::new("Synonyms").strong())
+                    .on_hover_text("Alternative names used to match this column on import");
+                let mut remove = None;
+                for (idx, synonym) in synonyms.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.small_button("✖").clicked() {
+                            remove = Some(idx);
+                        }
+                        ui.label(synonym);
+                    });
+                }
+                if let Some(idx) = remove {
+                    synonyms.remove(idx);
+                    synonyms_changed = true;
+                }
+                ui.horizontal(|ui| {
+                    let edit = TextEdit::singleline(&mut settings.new_synonym)
+                        .hint_text("New synonym")
+                        .ui(ui);
+                    let enter = edit.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+                    if ui.button("Add").clicked() || enter {
+                        let synonym = settings.new_synonym.trim().to_lowercase();
+                        if !synonym.is_empty() && !synonyms.contains(&synonym) {
+                            synonyms.push(synonym);
+                            synonyms_changed = true;
+                        }
+                        settings.new_synonym.clear();
+                    }
+                });
+            });
+
+        if let (true, Some(synonyms)) = (synonyms_changed, synonyms) {
+            backend.set_column_synonyms(col_uid, synonyms);
+        }
+        if !is_open {
+            self.state.column_settings = None;
+        }
+    }
+}
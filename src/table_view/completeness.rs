@@ -0,0 +1,70 @@
+use crate::backend::{OneShotFlags, TableBackend, VisualRowIdx};
+use egui::{Frame, Margin, Ui};
+
+impl super::TableView {
+    /// Check rows for missing required values again once cells or rows changed.
+    pub(super) fn invalidate_incomplete_rows(&mut self, flags: &OneShotFlags) {
+        if flags.row_set_updated
+            || flags.visible_row_vec_updated
+            || flags.column_info_updated
+            || flags.cleared
+            || flags.reloaded
+            || !flags.cells_updated.is_empty()
+        {
+            self.state.incomplete_rows_outdated = true;
+        }
+    }
+
+    /// Band with the number of rows missing required values and a button to go to the next one.
+    pub(super) fn incomplete_rows_ui(&mut self, backend: &impl TableBackend, ui: &mut Ui) {
+        if core::mem::take(&mut self.state.incomplete_rows_outdated) {
+            self.state.incomplete_rows = backend.incomplete_rows().into_iter().collect();
+        }
+        let count = self.state.incomplete_rows.len();
+        if count == 0 {
+            return;
+        }
+        let color = ui.visuals().error_fg_color;
+        let mut go_to_next = false;
+        Frame::none()
+            .fill(color.gamma_multiply(0.15))
+            .inner_margin(Margin::same(4.))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    let text = if count == 1 {
+                        "1 row incomplete".to_string()
+                    } else {
+                        format!("{count} rows incomplete")
+                    };
+                    ui.colored_label(color, text)
+                        .on_hover_text("Required cells are empty or hold values of a wrong type");
+                    go_to_next = ui.small_button("Next").clicked();
+                });
+            });
+        if go_to_next {
+            self.select_next_incomplete_row(backend);
+        }
+    }
+
+    /// Select the first incomplete row after the selection, wrapping around to the top.
+    fn select_next_incomplete_row(&mut self, backend: &impl TableBackend) {
+        let row_count = backend.row_count();
+        let start = self
+            .state
+            .selected_range
+            .map(|r| r.row_start() + 1)
+            .unwrap_or(0);
+        let next = (start..row_count)
+            .chain(0..start.min(row_count))
+            .map(VisualRowIdx)
+            .find(|row_idx| {
+                backend
+                    .row_uid(*row_idx)
+                    .map(|row_uid| self.state.incomplete_rows.contains(&row_uid))
+                    .unwrap_or(false)
+            });
+        if let Some(row_idx) = next {
+            self.select_cell(row_idx, None);
+        }
+    }
+}
@@ -1,9 +1,135 @@
+use crate::backend::ColumnUid;
+use crate::config_version::{ConfigMigrations, VersionedConfig};
+use crate::query::QuerySpec;
+use crate::table_view::clipboard::CopyValues;
+use crate::table_view::layout_presets::LayoutPreset;
+use crate::table_view::paste::PasteConfig;
+use crate::table_view::renderers::CellRenderers;
+use egui::Id;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::time::Duration;
+
+/// Behaviour and look of the view set up by the application. It is not serializable as it holds
+/// callbacks such as [CellRenderers], adjustments made by the user (column order and widths,
+/// hidden columns, sorting, layout presets) are persisted through [TableViewState] instead, see
+/// [view_state](super::TableView::view_state) and [restore_view_state](super::TableView::restore_view_state).
 pub struct TableViewConfig {
     /// Row height will not be lower that this value.
     pub minimum_row_height: f32,
     /// Row height will be determined based on its contents.
     /// There might be some speed and memory penalty for doing this.
     pub use_heterogeneous_row_heights: bool,
+    /// How long notifications are shown over the table.
+    pub toast_duration: Duration,
+    /// Include column names when copying selection with "Copy as…".
+    pub copy_with_headers: bool,
+    /// Whether raw or displayed values are copied, unless overridden for a column or in the "Copy as…" menu.
+    pub copy_values: CopyValues,
+    /// Columns copied differently from [copy_values](Self::copy_values), e.g. formatted amounts next to raw IDs.
+    pub column_copy_values: HashMap<ColumnUid, CopyValues>,
+    /// Render columns from right to left, None to follow [egui::Layout::prefer_right_to_left] of the parent ui.
+    pub right_to_left: Option<bool>,
+    /// Alternate row background each time a value in this column changes, instead of striping every other row.
+    pub band_by_column: Option<ColumnUid>,
+    /// Last choices made in the paste dialog, see [PasteConfig::ask_again] to show it again.
+    pub paste: PasteConfig,
+    /// Distinguishes several tables in the same parent ui.
+    pub id_salt: Option<Id>,
+    /// Show a column with row numbers, used to select whole rows and for row actions.
+    pub tool_column: ToolColumn,
+    /// Table body is scrolled if it doesn't fit into this height.
+    pub max_height: f32,
+    /// Disallow editing, pasting and any other changes from the UI, copying still works.
+    pub read_only: bool,
+    /// Handle keyboard shortcuts, such as undo and skip toggling.
+    pub shortcuts: bool,
+    /// Continue editing the cell below after Enter, adding rows at the end, to key in data without the mouse.
+    pub data_entry: bool,
+    pub style: TableViewStyle,
+    /// Show how many cells of each column were updated during this time, in the header.
+    /// Useful for streaming backends, see [OneShotFlags::cells_updated](crate::backend::OneShotFlags::cells_updated).
+    pub column_activity: Option<Duration>,
+    /// Values of these columns are masked until revealed, see [TableView::reveal_column](super::TableView::reveal_column).
+    /// Mark them in the backend as well to leave them out of CSV export, see
+    /// [set_column_sensitive](crate::backends::variant::VariantBackend::set_column_sensitive).
+    pub sensitive_columns: HashSet<ColumnUid>,
+    /// Copy actual values of sensitive columns, otherwise they are left empty in copied text.
+    pub copy_sensitive: bool,
+    /// Keep this many first columns visible while scrolling horizontally, e.g. an ID or Name column.
+    /// Row heights are not measured while columns are pinned, see [use_heterogeneous_row_heights](Self::use_heterogeneous_row_heights).
+    pub pinned_columns: usize,
+    /// Columns not shown in the view, can be shown again from the column chooser.
+    /// Persisted in [TableViewState::hidden_columns].
+    pub hidden_columns: HashSet<ColumnUid>,
+    /// Column holding manual row order, rewritten with 1..N when rows are dragged in the tool column,
+    /// so that the order survives export and reload. Dragging sorts the view by it, rows can't be
+    /// dragged while sorted by another column.
+    pub order_column: Option<ColumnUid>,
+    /// When to show rows as cards instead of a table, e.g. to review imported data on narrow screens.
+    pub card_view: CardView,
+    /// Saved layouts, see [TableView::apply_layout_preset](super::TableView::apply_layout_preset).
+    pub layout_presets: Vec<LayoutPreset>,
+    /// Show how long the table takes to render each frame in its corner, for a quick check without a profiler.
+    /// See also the `puffin` feature.
+    pub frame_time_overlay: bool,
+    /// Time allowed for drawing cells of rows that just scrolled into view in one frame, cells past it
+    /// are shown as placeholders and drawn over the next frames. Rows already on screen are always drawn.
+    /// Bounds frame time spikes with complex cell UIs on slow machines, None to draw everything at once.
+    pub render_budget: Option<Duration>,
+    /// Value columns mapped to columns holding their units, e.g. "12.3" and "mA" in lab exports.
+    /// Units are shown after the values in one column, until expanded from the header menu.
+    /// Both columns stay in the backend as they are.
+    pub unit_columns: HashMap<ColumnUid, ColumnUid>,
+    /// Briefly highlight cells when an action does nothing, e.g. editing a read-only table or moving past the edge.
+    pub flash_rejected_actions: bool,
+    /// While there are no rows, show a faint row with the type and an example value of each column,
+    /// see [TableBackend::column_example](crate::backend::TableBackend::column_example).
+    pub example_row: bool,
+    /// List invalid cells below the table, see [TableBackend::validation_errors](crate::backend::TableBackend::validation_errors).
+    pub validation_panel: bool,
+    /// Views and editors used instead of the backend ones for some types or columns.
+    pub cell_renderers: CellRenderers,
+}
+
+/// Rows can be shown as cards of "column: value" lines, only used columns are listed.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum CardView {
+    #[default]
+    Never,
+    /// Switch to cards when the available width is less than this value.
+    BelowWidth(f32),
+    Always,
+}
+
+/// Position of the column with row numbers, on screen regardless of the layout direction.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ToolColumn {
+    #[default]
+    Hidden,
+    Left,
+    Right,
+}
+
+/// Visual parameters of the table.
+#[derive(Clone, Debug)]
+pub struct TableViewStyle {
+    pub header_height: f32,
+    /// Stripe every other row, ignored when banding by column.
+    pub striped: bool,
+    /// Opacity of unused columns and skipped rows.
+    pub dimmed_opacity: f32,
+}
+
+impl Default for TableViewStyle {
+    fn default() -> Self {
+        TableViewStyle {
+            header_height: 20.0,
+            striped: true,
+            dimmed_opacity: 0.4,
+        }
+    }
 }
 
 impl Default for TableViewConfig {
@@ -11,12 +137,255 @@ impl Default for TableViewConfig {
         TableViewConfig {
             minimum_row_height: 15.0,
             use_heterogeneous_row_heights: true,
+            toast_duration: Duration::from_secs(4),
+            copy_with_headers: false,
+            copy_values: CopyValues::Raw,
+            column_copy_values: HashMap::new(),
+            right_to_left: None,
+            band_by_column: None,
+            paste: PasteConfig::default(),
+            id_salt: None,
+            tool_column: ToolColumn::Hidden,
+            max_height: f32::MAX,
+            read_only: false,
+            shortcuts: true,
+            data_entry: false,
+            style: TableViewStyle::default(),
+            column_activity: None,
+            sensitive_columns: HashSet::new(),
+            copy_sensitive: false,
+            pinned_columns: 0,
+            hidden_columns: HashSet::new(),
+            order_column: None,
+            card_view: CardView::Never,
+            layout_presets: Vec::new(),
+            frame_time_overlay: false,
+            render_budget: None,
+            unit_columns: HashMap::new(),
+            flash_rejected_actions: true,
+            example_row: true,
+            validation_panel: true,
+            cell_renderers: CellRenderers::default(),
+        }
+    }
+}
+
+/// Configures a [TableView](super::TableView) in one chain, see [TableView::builder](super::TableView::builder).
+#[derive(Default)]
+pub struct TableViewBuilder {
+    config: TableViewConfig,
+}
+
+impl TableViewBuilder {
+    pub fn id_salt(mut self, id_salt: impl Hash) -> Self {
+        self.config.id_salt = Some(Id::new(id_salt));
+        self
+    }
+
+    pub fn tool_column_position(mut self, position: ToolColumn) -> Self {
+        self.config.tool_column = position;
+        self
+    }
+
+    #[deprecated(note = "use tool_column_position")]
+    pub fn tool_column(self, show: bool) -> Self {
+        self.tool_column_position(if show {
+            ToolColumn::Left
+        } else {
+            ToolColumn::Hidden
+        })
+    }
+
+    pub fn pinned_columns(mut self, count: usize) -> Self {
+        self.config.pinned_columns = count;
+        self
+    }
+
+    pub fn max_height(mut self, max_height: f32) -> Self {
+        self.config.max_height = max_height;
+        self
+    }
+
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.config.read_only = read_only;
+        self
+    }
+
+    pub fn shortcuts(mut self, enabled: bool) -> Self {
+        self.config.shortcuts = enabled;
+        self
+    }
+
+    /// Mask values of these columns, e.g. passwords or personal data.
+    pub fn sensitive_columns(mut self, columns: impl IntoIterator<Item = ColumnUid>) -> Self {
+        self.config.sensitive_columns = columns.into_iter().collect();
+        self
+    }
+
+    pub fn copy_sensitive(mut self, allowed: bool) -> Self {
+        self.config.copy_sensitive = allowed;
+        self
+    }
+
+    pub fn copy_values(mut self, values: CopyValues) -> Self {
+        self.config.copy_values = values;
+        self
+    }
+
+    pub fn column_copy_values(mut self, col_uid: ColumnUid, values: CopyValues) -> Self {
+        self.config.column_copy_values.insert(col_uid, values);
+        self
+    }
+
+    pub fn data_entry(mut self, enabled: bool) -> Self {
+        self.config.data_entry = enabled;
+        self
+    }
+
+    pub fn order_column(mut self, col_uid: ColumnUid) -> Self {
+        self.config.order_column = Some(col_uid);
+        self
+    }
+
+    pub fn card_view(mut self, card_view: CardView) -> Self {
+        self.config.card_view = card_view;
+        self
+    }
+
+    pub fn layout_presets(mut self, presets: impl IntoIterator<Item = LayoutPreset>) -> Self {
+        self.config.layout_presets = presets.into_iter().collect();
+        self
+    }
+
+    pub fn frame_time_overlay(mut self, enabled: bool) -> Self {
+        self.config.frame_time_overlay = enabled;
+        self
+    }
+
+    pub fn render_budget(mut self, budget: Duration) -> Self {
+        self.config.render_budget = Some(budget);
+        self
+    }
+
+    pub fn unit_column(mut self, value_col_uid: ColumnUid, unit_col_uid: ColumnUid) -> Self {
+        self.config.unit_columns.insert(value_col_uid, unit_col_uid);
+        self
+    }
+
+    pub fn flash_rejected_actions(mut self, enabled: bool) -> Self {
+        self.config.flash_rejected_actions = enabled;
+        self
+    }
+
+    pub fn example_row(mut self, enabled: bool) -> Self {
+        self.config.example_row = enabled;
+        self
+    }
+
+    pub fn validation_panel(mut self, enabled: bool) -> Self {
+        self.config.validation_panel = enabled;
+        self
+    }
+
+    pub fn cell_renderers(mut self, renderers: CellRenderers) -> Self {
+        self.config.cell_renderers = renderers;
+        self
+    }
+
+    pub fn style(mut self, style: TableViewStyle) -> Self {
+        self.config.style = style;
+        self
+    }
+
+    /// Start from the given config, overriding everything set before.
+    pub fn config(mut self, config: TableViewConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn build(self) -> super::TableView {
+        super::TableView {
+            state: super::state::State::default(),
+            config: self.config,
         }
     }
 }
 
+/// User adjustments of the view that only make sense for a particular data set (e.g. one file).
+/// Missing fields take their defaults when deserializing, states of older versions are upgraded in
+/// [restore_view_state](super::TableView::restore_view_state).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TableViewState {
+    /// 0 for states stored before versioning, see [VersionedConfig].
+    #[serde(default)]
+    pub version: u32,
+    /// Column order as rearranged by the user.
+    #[serde(default)]
+    pub column_order: Vec<ColumnUid>,
+    /// Sorting, filtering and grouping.
+    #[serde(default)]
+    pub query: QuerySpec,
+    #[serde(default)]
+    pub hidden_columns: HashSet<ColumnUid>,
+    /// Column widths as resized by the user, only used as initial widths when the table is first shown.
+    #[serde(default)]
+    pub column_widths: HashMap<ColumnUid, f32>,
+    #[serde(default)]
+    pub layout_presets: Vec<LayoutPreset>,
+}
+
+impl Default for TableViewState {
+    fn default() -> Self {
+        TableViewState {
+            version: Self::VERSION,
+            column_order: Vec::new(),
+            query: QuerySpec::default(),
+            hidden_columns: HashSet::new(),
+            column_widths: HashMap::new(),
+            layout_presets: Vec::new(),
+        }
+    }
+}
+
+impl VersionedConfig for TableViewState {
+    const VERSION: u32 = 1;
+
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+
+    fn migrations() -> ConfigMigrations<Self> {
+        // Nothing to upgrade yet, fields added before versioning take their defaults
+        ConfigMigrations::new()
+    }
+}
+
 impl super::TableView {
     pub fn config_mut(&mut self) -> &mut TableViewConfig {
         &mut self.config
     }
+
+    /// Returns current view adjustments, so that they can be restored later with [restore_view_state](Self::restore_view_state).
+    pub fn view_state(&self) -> TableViewState {
+        TableViewState {
+            version: TableViewState::VERSION,
+            column_order: self.state.columns.clone(),
+            query: self.state.query.clone(),
+            hidden_columns: self.config.hidden_columns.clone(),
+            column_widths: self.state.column_widths.clone(),
+            layout_presets: self.config.layout_presets.clone(),
+        }
+    }
+
+    /// Apply previously saved view adjustments. Takes effect once column info is (re)loaded from the backend.
+    /// States saved by older versions of the crate are upgraded first.
+    pub fn restore_view_state(&mut self, mut view_state: TableViewState) {
+        view_state.migrate();
+        self.state.restore_view_state = Some(view_state);
+    }
 }
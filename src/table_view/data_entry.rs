@@ -0,0 +1,52 @@
+use crate::backend::{TableBackend, VisualRowIdx};
+use crate::table_view::state::SelectedRange;
+use egui::{Key, Modifiers};
+
+impl super::TableView {
+    /// In data-entry mode, Enter after editing a cell or Down on the last row moves to the cell below,
+    /// appending a new row with default values when there is none.
+    pub(super) fn handle_data_entry(
+        &mut self,
+        backend: &mut impl TableBackend,
+        ctx: &egui::Context,
+    ) {
+        let committed_cell = self.state.committed_cell.take();
+        if !self.config.data_entry || self.config.read_only {
+            return;
+        }
+        let (row_idx, col_idx, start_editing) = if let Some((row_idx, col_idx)) = committed_cell {
+            (row_idx, col_idx, true)
+        } else {
+            let Some(selected) = self.state.selected_range.filter(|r| {
+                r.is_single_cell() && !r.is_editing() && r.row_end() + 1 == backend.row_count()
+            }) else {
+                return;
+            };
+            if !ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::ArrowDown)) {
+                return;
+            }
+            (selected.row_start(), selected.col_start(), false)
+        };
+        let mut next_row_idx = row_idx + 1;
+        if next_row_idx >= backend.row_count() {
+            if !backend.can_append_rows() {
+                return;
+            }
+            let Some(row_uid) = backend.append_row() else {
+                return;
+            };
+            // New row may be placed anywhere if rows are sorted
+            let Some(row_idx) = (0..backend.row_count())
+                .find(|idx| backend.row_uid(VisualRowIdx(*idx)) == Some(row_uid))
+            else {
+                return;
+            };
+            next_row_idx = row_idx;
+            self.state.row_bands = None;
+        }
+        let mut next = SelectedRange::single(next_row_idx, col_idx);
+        next.set_editing(start_editing);
+        self.state.selected_range = Some(next);
+        self.state.scroll_to_row = Some(next_row_idx);
+    }
+}
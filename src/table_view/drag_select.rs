@@ -0,0 +1,75 @@
+use crate::backend::ColumnUid;
+use crate::table_view::state::{CellDrag, RowDrag};
+use egui::{DragAndDrop, Pos2, Ui, Vec2};
+
+/// Distance from the body edge at which drag selection starts scrolling, in points.
+const AUTOSCROLL_MARGIN: f32 = 24.;
+/// Scroll speed with the pointer at or beyond the edge, in points per second.
+const AUTOSCROLL_SPEED: f32 = 800.;
+
+impl super::TableView {
+    /// Pointer position to select cells at while drag-selecting or dragging the fill handle, kept inside
+    /// the body so that the selection keeps growing as rows are scrolled in from beyond the edge.
+    pub(super) fn drag_select_pos(&self, ui: &Ui) -> Option<Pos2> {
+        if self.state.drag_select_anchor.is_none() && self.state.fill_drag_to.is_none() {
+            return None;
+        }
+        let pos = ui.input(|i| i.pointer.interact_pos())?;
+        let rect = ui.clip_rect().shrink(1.);
+        Some(pos.clamp(rect.min, rect.max))
+    }
+
+    /// Scroll the body while drag-selecting or dragging a column, row or cell value
+    /// with the pointer close to or beyond its edges, faster the further the pointer is.
+    pub(super) fn drag_autoscroll(&mut self, ui: &Ui) {
+        let Some([horizontal, vertical]) = self.autoscroll_axes(ui) else {
+            return;
+        };
+        if !ui.input(|i| i.pointer.primary_down()) {
+            self.state.drag_select_anchor = None;
+            return;
+        }
+        let Some(pos) = ui.input(|i| i.pointer.interact_pos()) else {
+            return;
+        };
+        let rect = ui.clip_rect();
+        let dt = ui.input(|i| i.stable_dt).min(0.1);
+        let speed = |beyond_margin: f32| {
+            (beyond_margin / AUTOSCROLL_MARGIN).clamp(0., 1.) * AUTOSCROLL_SPEED * dt
+        };
+        // Positive delta moves the content towards the bottom right, revealing what is above and to the left
+        let mut delta = Vec2::new(
+            speed(rect.left() + AUTOSCROLL_MARGIN - pos.x)
+                - speed(pos.x - rect.right() + AUTOSCROLL_MARGIN),
+            speed(rect.top() + AUTOSCROLL_MARGIN - pos.y)
+                - speed(pos.y - rect.bottom() + AUTOSCROLL_MARGIN),
+        );
+        if !horizontal {
+            delta.x = 0.;
+        }
+        if !vertical {
+            delta.y = 0.;
+        }
+        if delta != Vec2::ZERO {
+            ui.scroll_with_delta(delta);
+            ui.ctx().request_repaint();
+        }
+    }
+
+    /// Directions to scroll in, horizontal and vertical, None if nothing is being dragged.
+    fn autoscroll_axes(&self, ui: &Ui) -> Option<[bool; 2]> {
+        let ctx = ui.ctx();
+        if self.state.drag_select_anchor.is_some() || self.state.fill_drag_to.is_some() {
+            Some([true, true])
+        } else if DragAndDrop::has_payload_of_type::<ColumnUid>(ctx) {
+            // Dragged in the header above the body, scrolling up would only get in the way
+            Some([true, false])
+        } else if DragAndDrop::has_payload_of_type::<RowDrag>(ctx)
+            || DragAndDrop::has_payload_of_type::<CellDrag>(ctx)
+        {
+            Some([false, true])
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use crate::backend::TableEvent;
+
+/// Oldest events are dropped beyond this, if the application never drains them.
+const MAX_QUEUED_EVENTS: usize = 1024;
+
+impl super::TableView {
+    /// Take changes reported by the backend and the view since the last call, oldest first.
+    /// Events are kept across frames until drained, unlike [OneShotFlags](crate::backend::OneShotFlags).
+    pub fn drain_events(&mut self) -> Vec<TableEvent> {
+        core::mem::take(&mut self.state.events)
+    }
+
+    /// Keep backend events before its flags are reset.
+    pub(super) fn queue_events(&mut self, events: Vec<TableEvent>) {
+        let queue = &mut self.state.events;
+        queue.extend(events);
+        let overflow = queue.len().saturating_sub(MAX_QUEUED_EVENTS);
+        queue.drain(..overflow);
+    }
+}
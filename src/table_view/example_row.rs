@@ -0,0 +1,45 @@
+use crate::backend::{ColumnUid, TableBackend};
+use crate::table_view::state::DisplayColumn;
+use crate::util::variant_to_string;
+use egui::{Label, RichText, Widget};
+use egui_extras::TableBody;
+
+/// Faint row shown while the table has no rows, with the type and an example value of each column,
+/// so that it is clear what to type before the first row is added.
+pub(super) fn example_row_ui(
+    backend: &impl TableBackend,
+    body: &mut TableBody,
+    columns: &[ColumnUid],
+    cell_order: &[DisplayColumn],
+    height: f32,
+) {
+    body.row(height, |mut row| {
+        for display_column in cell_order {
+            let DisplayColumn::Data(col_idx) = display_column else {
+                row.col(|_| {});
+                continue;
+            };
+            let col_uid = columns[*col_idx];
+            let (_, resp) = row.col(|ui| {
+                let ty = backend
+                    .column_info(col_uid)
+                    .map(|c| c.ty.as_str())
+                    .unwrap_or_default();
+                let text = match backend
+                    .column_example(col_uid)
+                    .map(|v| variant_to_string(&v))
+                {
+                    Some(example) if !example.is_empty() => format!("{example} ({ty})"),
+                    _ => ty.to_string(),
+                };
+                ui.multiply_opacity(0.5);
+                Label::new(RichText::new(text).italics().weak())
+                    .selectable(false)
+                    .ui(ui);
+            });
+            if let Some(description) = backend.column_description(col_uid) {
+                resp.on_hover_text(description);
+            }
+        }
+    });
+}
@@ -0,0 +1,43 @@
+use crate::backend::{ColumnUid, TableBackend};
+use crate::backends::variant::VariantBackend;
+use crate::util::{variant_ty, variant_ty_named};
+use rvariant::{Variant, VariantTy};
+
+impl super::TableView {
+    /// Copy selected rows and columns into a new backend, e.g. to show or export only a part of the table.
+    /// Columns keep their names, types and descriptions, cells not selected are left empty.
+    /// Returns None if nothing is selected.
+    pub fn extract_selection(&self, backend: &impl TableBackend) -> Option<VariantBackend> {
+        self.state.selected_range?;
+        let (columns, cells) = self.copied_cells(backend);
+        let column_defs = columns.iter().enumerate().map(|(idx, col_uid)| {
+            let info = backend.column_info(*col_uid);
+            let name = info.map(|c| c.name.clone()).unwrap_or_default();
+            let ty = info
+                .and_then(|c| variant_ty_named(&c.ty))
+                .or_else(|| {
+                    cells
+                        .iter()
+                        .filter_map(|row| row[idx].and_then(|coord| backend.get(coord)))
+                        .find_map(|value| variant_ty(&value))
+                })
+                .unwrap_or(VariantTy::Str);
+            (name, ty, None)
+        });
+        let mut extracted = VariantBackend::new(column_defs);
+        for (idx, col_uid) in columns.iter().enumerate() {
+            let description = backend.column_description(*col_uid).map(|d| d.to_string());
+            let example = backend.column_example(*col_uid);
+            if description.is_some() || example.is_some() {
+                extracted.set_column_hint(ColumnUid(idx as u32), description, example);
+            }
+        }
+        for row in &cells {
+            extracted.insert_row(row.iter().enumerate().filter_map(|(idx, coord)| {
+                let value = backend.get((*coord)?)?;
+                (!matches!(value, Variant::Empty)).then_some((ColumnUid(idx as u32), value))
+            }));
+        }
+        Some(extracted)
+    }
+}
@@ -0,0 +1,32 @@
+use crate::backend::TableBackend;
+use egui::{Frame, Margin, Ui};
+
+/// Band with a Retry button for each range of rows the backend failed to load.
+pub(super) fn failed_fetches_ui(backend: &mut impl TableBackend, ui: &mut Ui) {
+    let mut retry = None;
+    let color = ui.visuals().error_fg_color;
+    for failed in backend.failed_fetches() {
+        Frame::none()
+            .fill(color.gamma_multiply(0.15))
+            .inner_margin(Margin::same(4.))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        color,
+                        format!(
+                            "Rows {}–{} failed to load — {}",
+                            failed.rows.start.0 + 1,
+                            failed.rows.end.0,
+                            failed.message
+                        ),
+                    );
+                    if ui.small_button("Retry").clicked() {
+                        retry = Some(failed.rows.clone());
+                    }
+                });
+            });
+    }
+    if let Some(rows) = retry {
+        backend.retry_fetch(rows);
+    }
+}
@@ -0,0 +1,171 @@
+use crate::backend::{CellCoord, TableBackend, VisualRowIdx};
+use crate::table_view::state::SelectedRange;
+use crate::util::variant_as_f64;
+use egui::{Rect, Ui, Vec2};
+use rvariant::Variant;
+
+/// Size of the fill handle square, in points.
+const FILL_HANDLE_SIZE: f32 = 6.;
+
+/// Which edge of the selection is copied over the rest of it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum FillDirection {
+    /// First row into the rows below it.
+    Down,
+    /// First column into the columns to the right of it.
+    Right,
+}
+
+impl super::TableView {
+    /// Copy the first row or column of the selected range into the rest of it, as one undo step.
+    pub(super) fn fill_selection(
+        &mut self,
+        backend: &mut impl TableBackend,
+        direction: FillDirection,
+    ) {
+        if self.config.read_only {
+            return;
+        }
+        let Some(selected) = self.state.selected_range else {
+            return;
+        };
+        let columns = &self.state.columns;
+        let coord = |row_idx: usize, col_idx: usize| {
+            Some(CellCoord {
+                row_uid: backend.row_uid(VisualRowIdx(row_idx))?,
+                col_uid: *columns.get(col_idx)?,
+            })
+        };
+        let mut values = vec![];
+        for row_idx in selected.row_start()..=selected.row_end() {
+            for col_idx in selected.col_start()..=selected.col_end() {
+                let (src_row_idx, src_col_idx) = match direction {
+                    FillDirection::Down => (selected.row_start(), col_idx),
+                    FillDirection::Right => (row_idx, selected.col_start()),
+                };
+                if (src_row_idx, src_col_idx) == (row_idx, col_idx) {
+                    continue;
+                }
+                let (Some(src), Some(dst)) =
+                    (coord(src_row_idx, src_col_idx), coord(row_idx, col_idx))
+                else {
+                    continue;
+                };
+                values.push((dst, backend.get(src).unwrap_or(Variant::Empty)));
+            }
+        }
+        if values.is_empty() {
+            return;
+        }
+        backend.modify_many(values);
+        self.state.row_bands = None;
+    }
+
+    /// Selection with a fill handle, a single column not being edited.
+    pub(super) fn fill_handle_range(&self) -> Option<SelectedRange> {
+        if self.config.read_only {
+            return None;
+        }
+        self.state
+            .selected_range
+            .filter(|r| r.width() == 1 && !r.is_editing())
+    }
+
+    /// Cells the fill handle is being dragged over, below the selection.
+    pub(super) fn fill_preview(&self) -> Option<SelectedRange> {
+        let selected = self.fill_handle_range()?;
+        let to = self
+            .state
+            .fill_drag_to
+            .filter(|to| *to > selected.row_end())?;
+        Some(SelectedRange::spanning(
+            selected.row_end() + 1,
+            selected.col_start(),
+            to,
+            selected.col_start(),
+        ))
+    }
+
+    /// Fill the cells the handle was dragged over once the pointer is released.
+    pub(super) fn finish_fill_drag(&mut self, backend: &mut impl TableBackend, ui: &Ui) {
+        if self.state.fill_drag_to.is_none() || ui.input(|i| i.pointer.primary_down()) {
+            return;
+        }
+        let preview = self.fill_preview();
+        self.state.fill_drag_to = None;
+        let (Some(selected), Some(preview)) = (self.fill_handle_range(), preview) else {
+            return;
+        };
+        let Some(&col_uid) = self.state.columns.get(selected.col_start()) else {
+            return;
+        };
+        let coord = |row_idx: usize| {
+            Some(CellCoord {
+                row_uid: backend.row_uid(VisualRowIdx(row_idx))?,
+                col_uid,
+            })
+        };
+        let sources: Vec<Variant> = (selected.row_start()..=selected.row_end())
+            .map(|row_idx| {
+                coord(row_idx)
+                    .and_then(|c| backend.get(c))
+                    .unwrap_or(Variant::Empty)
+            })
+            .collect();
+        let values: Vec<(CellCoord, Variant)> = (preview.row_start()..=preview.row_end())
+            .zip(extend_series(&sources, preview.height()))
+            .filter_map(|(row_idx, value)| Some((coord(row_idx)?, value)))
+            .collect();
+        backend.modify_many(values);
+        self.state.selected_range = Some(SelectedRange::spanning(
+            selected.row_start(),
+            selected.col_start(),
+            preview.row_end(),
+            selected.col_start(),
+        ));
+        self.state.row_bands = None;
+    }
+}
+
+/// Small square in the bottom right corner of a cell, dragged down to fill the cells below.
+pub(super) fn fill_handle_ui(ui: &Ui, cell_rect: Rect) -> Rect {
+    let rect = Rect::from_center_size(
+        cell_rect.right_bottom() - Vec2::splat(FILL_HANDLE_SIZE / 2.),
+        Vec2::splat(FILL_HANDLE_SIZE),
+    );
+    ui.painter()
+        .rect_filled(rect, 0., ui.visuals().warn_fg_color);
+    rect
+}
+
+/// Continue a series like the fill handle in spreadsheets: numbers keep the step between the last two values,
+/// or are incremented by one if there is only one, anything else is repeated.
+fn extend_series(sources: &[Variant], count: usize) -> Vec<Variant> {
+    let step = |last: f64, prev: Option<f64>| prev.map(|prev| last - prev).unwrap_or(1.);
+    let numbers: Option<Vec<f64>> = sources.iter().map(variant_as_f64).collect();
+    let (Some(numbers), Some(last)) = (numbers, sources.last()) else {
+        return sources.iter().cycle().take(count).cloned().collect();
+    };
+    let last_number = numbers[numbers.len() - 1];
+    let step = step(
+        last_number,
+        numbers.len().checked_sub(2).map(|i| numbers[i]),
+    );
+    (1..=count)
+        .map(|n| {
+            let v = last_number + step * n as f64;
+            match last {
+                Variant::U32(_) if v >= 0. && v <= u32::MAX as f64 => Variant::U32(v as u32),
+                Variant::U64(_) if v >= 0. => Variant::U64(v as u64),
+                Variant::I32(_) if v >= i32::MIN as f64 && v <= i32::MAX as f64 => {
+                    Variant::I32(v as i32)
+                }
+                Variant::I64(_) => Variant::I64(v as i64),
+                Variant::F32(_) => Variant::F32(v as f32),
+                Variant::F64(_) => Variant::F64(v),
+                // Out of range of the integer type
+                _ => Variant::Empty,
+            }
+        })
+        .collect()
+}
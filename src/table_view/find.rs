@@ -0,0 +1,289 @@
+use crate::backend::{CellCoord, OneShotFlags, TableBackend, VisualRowIdx};
+use crate::table_view::state::SelectedRange;
+use crate::util::variant_to_string;
+use egui::{Frame, Key, KeyboardShortcut, Margin, Modifiers, TextEdit, Ui, Widget};
+use regex::{NoExpand, Regex, RegexBuilder};
+use rvariant::Variant;
+use std::collections::HashSet;
+
+const FIND: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::F);
+const REPLACE: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::H);
+
+/// Search bar shown above the table with Ctrl+F, or with a replace field with Ctrl+H.
+#[derive(Default)]
+pub(super) struct FindBar {
+    query: String,
+    replacement: String,
+    case_sensitive: bool,
+    use_regex: bool,
+    replace_mode: bool,
+    /// Matching cells in the order they are shown, by rows.
+    matches: Vec<(usize, usize)>,
+    match_set: HashSet<(usize, usize)>,
+    current: Option<usize>,
+    /// Set when the query or the data changed and matches should be searched again.
+    is_stale: bool,
+    focus_requested: bool,
+    regex_error: Option<String>,
+}
+
+impl FindBar {
+    pub(super) fn is_match(&self, row_idx: usize, col_idx: usize) -> bool {
+        self.match_set.contains(&(row_idx, col_idx))
+    }
+
+    pub(super) fn is_current_match(&self, row_idx: usize, col_idx: usize) -> bool {
+        self.current
+            .and_then(|idx| self.matches.get(idx))
+            .is_some_and(|m| *m == (row_idx, col_idx))
+    }
+
+    fn regex(&self) -> Result<Regex, regex::Error> {
+        let pattern = if self.use_regex {
+            self.query.clone()
+        } else {
+            regex::escape(&self.query)
+        };
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .build()
+    }
+
+    /// Replacement of all matches in a cell's text, `$1` style groups are expanded in regex mode only.
+    fn replace(&self, regex: &Regex, text: &str) -> String {
+        if self.use_regex {
+            regex
+                .replace_all(text, self.replacement.as_str())
+                .into_owned()
+        } else {
+            regex
+                .replace_all(text, NoExpand(&self.replacement))
+                .into_owned()
+        }
+    }
+}
+
+impl super::TableView {
+    /// Ctrl+F opens the search bar, Ctrl+H opens it with the replace field, while the table has a selection.
+    pub(super) fn handle_find_shortcuts(&mut self, ctx: &egui::Context) {
+        if self.state.selected_range.is_none() && self.state.find.is_none() {
+            return;
+        }
+        let (find, replace) =
+            ctx.input_mut(|i| (i.consume_shortcut(&FIND), i.consume_shortcut(&REPLACE)));
+        if !find && !replace {
+            return;
+        }
+        let bar = self.state.find.get_or_insert_with(|| FindBar {
+            is_stale: true,
+            ..Default::default()
+        });
+        bar.replace_mode = replace && !self.config.read_only;
+        bar.focus_requested = false;
+    }
+
+    /// Search matches again after the data or the row order changed.
+    pub(super) fn invalidate_find_matches(&mut self, flags: &OneShotFlags) {
+        let Some(bar) = &mut self.state.find else {
+            return;
+        };
+        if flags.row_set_updated
+            || flags.visible_row_vec_updated
+            || flags.column_info_updated
+            || flags.cleared
+            || flags.reloaded
+            || !flags.cells_updated.is_empty()
+        {
+            bar.is_stale = true;
+        }
+    }
+
+    pub(super) fn find_bar_ui(&mut self, backend: &mut impl TableBackend, ui: &mut Ui) {
+        let Some(mut bar) = self.state.find.take() else {
+            return;
+        };
+        let mut close = false;
+        let mut step = None;
+        let mut replace_current = false;
+        let mut replace_all = false;
+        Frame::none()
+            .fill(ui.visuals().faint_bg_color)
+            .inner_margin(Margin::same(4.))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    let edit = TextEdit::singleline(&mut bar.query)
+                        .hint_text("Find")
+                        .desired_width(160.)
+                        .ui(ui);
+                    if !bar.focus_requested {
+                        edit.request_focus();
+                        bar.focus_requested = true;
+                    }
+                    bar.is_stale |= edit.changed();
+                    if edit.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+                        step = Some(if ui.input(|i| i.modifiers.shift) {
+                            -1
+                        } else {
+                            1
+                        });
+                        edit.request_focus();
+                    }
+                    close |= edit.has_focus() && ui.input(|i| i.key_pressed(Key::Escape));
+                    bar.is_stale |= ui
+                        .toggle_value(&mut bar.case_sensitive, "Aa")
+                        .on_hover_text("Match case")
+                        .changed();
+                    bar.is_stale |= ui
+                        .toggle_value(&mut bar.use_regex, ".*")
+                        .on_hover_text("Regular expression")
+                        .changed();
+                    if ui
+                        .small_button("⏶")
+                        .on_hover_text("Previous match, Shift+Enter")
+                        .clicked()
+                    {
+                        step = Some(-1);
+                    }
+                    if ui
+                        .small_button("⏷")
+                        .on_hover_text("Next match, Enter")
+                        .clicked()
+                    {
+                        step = Some(1);
+                    }
+                    match (&bar.regex_error, bar.current) {
+                        (Some(error), _) => {
+                            ui.colored_label(ui.visuals().error_fg_color, "Invalid pattern")
+                                .on_hover_text(error);
+                        }
+                        _ if bar.query.is_empty() => {}
+                        (None, Some(current)) => {
+                            ui.label(format!("{} of {}", current + 1, bar.matches.len()));
+                        }
+                        (None, None) => {
+                            ui.label(format!("{} matches", bar.matches.len()));
+                        }
+                    }
+                    if !self.config.read_only {
+                        ui.toggle_value(&mut bar.replace_mode, "Replace…");
+                    }
+                    close |= ui.small_button("✖").clicked();
+                });
+                if bar.replace_mode && !self.config.read_only {
+                    ui.horizontal(|ui| {
+                        TextEdit::singleline(&mut bar.replacement)
+                            .hint_text("Replace with")
+                            .desired_width(160.)
+                            .ui(ui);
+                        let has_matches = !bar.matches.is_empty();
+                        replace_current = ui
+                            .add_enabled(has_matches, egui::Button::new("Replace"))
+                            .clicked();
+                        replace_all = ui
+                            .add_enabled(has_matches, egui::Button::new("Replace all"))
+                            .clicked();
+                    });
+                }
+            });
+        if close {
+            return;
+        }
+        if bar.is_stale {
+            self.find_matches(backend, &mut bar);
+        }
+        if replace_current || replace_all {
+            self.replace_matches(backend, &mut bar, replace_all);
+            self.find_matches(backend, &mut bar);
+        }
+        if let (Some(step), false) = (step, bar.matches.is_empty()) {
+            let len = bar.matches.len() as isize;
+            let current = match bar.current {
+                Some(current) => (current as isize + step).rem_euclid(len),
+                None if step > 0 => 0,
+                None => len - 1,
+            } as usize;
+            bar.current = Some(current);
+            let (row_idx, col_idx) = bar.matches[current];
+            self.state
+                .select(SelectedRange::single(row_idx, col_idx), false);
+            self.state.scroll_to_row = Some(row_idx);
+        }
+        self.state.find = Some(bar);
+    }
+
+    /// Search all cells of shown columns, masked columns are skipped.
+    fn find_matches(&self, backend: &impl TableBackend, bar: &mut FindBar) {
+        bar.is_stale = false;
+        bar.matches.clear();
+        bar.match_set.clear();
+        bar.current = None;
+        bar.regex_error = None;
+        if bar.query.is_empty() {
+            return;
+        }
+        let regex = match bar.regex() {
+            Ok(regex) => regex,
+            Err(e) => {
+                bar.regex_error = Some(e.to_string());
+                return;
+            }
+        };
+        for (row_idx, row_uid) in backend.visible_rows().into_iter().enumerate() {
+            for (col_idx, col_uid) in self.state.columns.iter().enumerate() {
+                if self.is_masked(*col_uid) {
+                    continue;
+                }
+                let coord = CellCoord {
+                    row_uid,
+                    col_uid: *col_uid,
+                };
+                let Some(value) = backend.get(coord) else {
+                    continue;
+                };
+                if regex.is_match(&variant_to_string(&value)) {
+                    bar.matches.push((row_idx, col_idx));
+                }
+            }
+        }
+        bar.match_set = bar.matches.iter().copied().collect();
+        // Continue from the selected cell
+        bar.current = self.state.selected_range.and_then(|r| {
+            bar.matches
+                .iter()
+                .position(|m| *m == (r.row_start(), r.col_start()))
+        });
+    }
+
+    /// Replace matches in the current cell or in all matching cells, as one undo step.
+    fn replace_matches(&mut self, backend: &mut impl TableBackend, bar: &mut FindBar, all: bool) {
+        let Ok(regex) = bar.regex() else {
+            return;
+        };
+        let cells: Vec<(usize, usize)> = if all {
+            bar.matches.clone()
+        } else {
+            bar.current
+                .or((!bar.matches.is_empty()).then_some(0))
+                .and_then(|idx| bar.matches.get(idx).copied())
+                .into_iter()
+                .collect()
+        };
+        let values: Vec<(CellCoord, Variant)> = cells
+            .into_iter()
+            .filter_map(|(row_idx, col_idx)| {
+                let coord = CellCoord {
+                    row_uid: backend.row_uid(VisualRowIdx(row_idx))?,
+                    col_uid: *self.state.columns.get(col_idx)?,
+                };
+                let text = variant_to_string(&backend.get(coord)?);
+                Some((coord, Variant::Str(bar.replace(&regex, &text))))
+            })
+            .collect();
+        let count = values.len();
+        backend.modify_many(values);
+        self.state.row_bands = None;
+        if all {
+            self.notify(super::ToastKind::Info, format!("Replaced in {count} cells"));
+        }
+    }
+}
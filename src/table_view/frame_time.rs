@@ -0,0 +1,26 @@
+use egui::{Align2, Response, TextStyle, Ui};
+use std::time::Duration;
+
+/// Weight of the last frame in the shown average.
+const SMOOTHING: f32 = 0.1;
+
+impl super::TableView {
+    /// Average time the table took to show, in the top right corner, enabled with
+    /// [frame_time_overlay](super::TableViewConfig::frame_time_overlay).
+    pub(super) fn frame_time_overlay(&mut self, ui: &Ui, resp: &Response, elapsed: Duration) {
+        let ms = elapsed.as_secs_f32() * 1000.;
+        let average = match self.state.frame_time_ms {
+            Some(average) => average + (ms - average) * SMOOTHING,
+            None => ms,
+        };
+        self.state.frame_time_ms = Some(average);
+        let painter = ui.painter();
+        let font = TextStyle::Small.resolve(ui.style());
+        let text = format!("{average:.2} ms");
+        let pos = resp.rect.right_top() + egui::vec2(-4., 4.);
+        let galley = painter.layout_no_wrap(text, font, ui.visuals().weak_text_color());
+        let rect = Align2::RIGHT_TOP.anchor_size(pos, galley.size());
+        painter.rect_filled(rect.expand(2.), 2., ui.visuals().extreme_bg_color);
+        painter.galley(rect.min, galley, ui.visuals().weak_text_color());
+    }
+}
@@ -0,0 +1,90 @@
+use crate::backend::TableBackend;
+use egui::{Slider, Ui};
+
+/// Where scrubbing started, to go back there on cancel.
+pub(super) struct HistoryPreview {
+    start: usize,
+    was_read_only: bool,
+}
+
+impl super::TableView {
+    /// Slider going back and forth through the edit history of the backend, the table is shown at each point
+    /// and is read-only until the chosen point is restored or scrubbing is cancelled.
+    /// Shows nothing if backend doesn't keep history, see [TableBackend::history_position].
+    pub fn history_scrubber_ui(&mut self, backend: &mut impl TableBackend, ui: &mut Ui) {
+        let Some((position, total)) = backend.history_position() else {
+            return;
+        };
+        if self
+            .state
+            .history_preview
+            .as_ref()
+            .is_some_and(|p| p.start > total)
+        {
+            // History was cleared while scrubbing
+            self.finish_history_preview();
+        }
+        ui.horizontal(|ui| {
+            ui.label("History");
+            if total == 0 {
+                ui.weak("No changes yet");
+                return;
+            }
+            let mut target = position;
+            ui.add(
+                Slider::new(&mut target, 0..=total)
+                    .integer()
+                    .custom_formatter(move |n, _| format!("{n} of {total}")),
+            )
+            .on_hover_text("Changes applied at this point, the table is read-only while going through them");
+            if target != position {
+                if self.state.history_preview.is_none() {
+                    self.state.history_preview = Some(HistoryPreview {
+                        start: position,
+                        was_read_only: self.config.read_only,
+                    });
+                    self.config.read_only = true;
+                }
+                self.move_in_history(backend, target);
+            }
+            let Some(start) = self.state.history_preview.as_ref().map(|p| p.start) else {
+                return;
+            };
+            if ui
+                .button("Restore")
+                .on_hover_text("Keep the table as shown, changes after this point can be redone until the next edit")
+                .clicked()
+            {
+                self.finish_history_preview();
+            }
+            if ui.button("Cancel").clicked() {
+                self.move_in_history(backend, start);
+                self.finish_history_preview();
+            }
+        });
+    }
+
+    /// Undo or redo until `target` changes are applied.
+    fn move_in_history(&mut self, backend: &mut impl TableBackend, target: usize) {
+        while let Some((position, _)) = backend.history_position() {
+            if position > target {
+                backend.undo();
+            } else if position < target {
+                backend.redo();
+            } else {
+                break;
+            }
+            if backend.history_position().map(|(p, _)| p) == Some(position) {
+                // Backend could not move further
+                break;
+            }
+        }
+        self.state.row_bands = None;
+    }
+
+    fn finish_history_preview(&mut self) {
+        if let Some(preview) = self.state.history_preview.take() {
+            self.config.read_only = preview.was_read_only;
+        }
+    }
+}
@@ -1,28 +1,10 @@
-use std::rc::Rc;
-
-use egui::{Color32, TextFormat, Ui};
+use egui::Ui;
 use rvariant::Variant;
 
 use crate::cell::CellCoord;
 
 use super::TableView;
 
-#[derive(Clone, PartialEq)]
-pub enum Lint {
-    /// Highlight string range
-    HighlightRange,
-    /// Highlight one of the items of StrList or other array-like Variants
-    HighlightIndex {
-        idx: usize,
-        text_format: TextFormat,
-    },
-    AddButton,
-    AddIcon {
-        color: Color32,
-        icon: &'static str,
-    },
-}
-
 // TODO: column ty conversion dropdown
 // TODO: kind change changes cell holes somehow
 #[derive(Default)]
@@ -132,41 +114,6 @@ impl TableView {
         self.state.tool_ui_response.take()
     }
 
-    pub fn add_cell_lint(&mut self, coord: CellCoord, lint: Lint) {
-        let lints = &mut self.state.cell_metadata.entry(coord).or_default().lints;
-        if !lints.contains(&lint) {
-            lints.push(lint);
-        }
-    }
-
-    pub fn add_cell_tooltip(&mut self, coord: CellCoord, tooltip: Rc<String>) {
-        self.state
-            .cell_metadata
-            .entry(coord)
-            .or_default()
-            .tooltips
-            .push(tooltip);
-    }
-
-    pub fn set_cell_text_format(&mut self, coord: CellCoord, format: TextFormat) {
-        self.state
-            .cell_metadata
-            .entry(coord)
-            .or_default()
-            .text_format = Some(format);
-    }
-
-    pub fn clear_cell_lints_for(&mut self, coord: CellCoord) {
-        self.state.cell_metadata.entry(coord).and_modify(|m| {
-            m.tooltips.clear();
-            m.lints.clear();
-        });
-    }
-
-    pub fn clear_cell_lints(&mut self) {
-        self.state.cell_metadata.clear();
-    }
-
     pub fn load_state(&mut self, state: PersistentSettings) {
         self.persistent_settings = state;
     }
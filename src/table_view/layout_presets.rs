@@ -0,0 +1,124 @@
+use crate::backend::ColumnUid;
+use egui::{Button, TextEdit, Ui, Widget};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Named set of visible columns, widths and row density for a particular workflow, e.g. "review" or "data entry".
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LayoutPreset {
+    pub name: String,
+    #[serde(default)]
+    pub hidden_columns: HashSet<ColumnUid>,
+    #[serde(default)]
+    pub column_widths: HashMap<ColumnUid, f32>,
+    /// Minimum row height, lower values give a denser view.
+    pub row_height: f32,
+}
+
+impl super::TableView {
+    /// Save currently visible columns, their widths and row height under a name,
+    /// replacing a preset with the same name.
+    pub fn save_layout_preset(&mut self, name: impl Into<String>) {
+        let preset = LayoutPreset {
+            name: name.into(),
+            hidden_columns: self.config.hidden_columns.clone(),
+            column_widths: self.state.column_widths.clone(),
+            row_height: self.config.minimum_row_height,
+        };
+        self.state.active_layout_preset = Some(preset.name.clone());
+        match self
+            .config
+            .layout_presets
+            .iter_mut()
+            .find(|p| p.name == preset.name)
+        {
+            Some(existing) => *existing = preset,
+            None => self.config.layout_presets.push(preset),
+        }
+    }
+
+    /// Switch to a saved layout, returns false if there is no preset with this name.
+    pub fn apply_layout_preset(&mut self, name: &str) -> bool {
+        let Some(preset) = self.config.layout_presets.iter().find(|p| p.name == name) else {
+            return false;
+        };
+        self.config.hidden_columns = preset.hidden_columns.clone();
+        self.config.minimum_row_height = preset.row_height;
+        self.state.column_widths = preset.column_widths.clone();
+        self.state.row_heights.clear();
+        // Table id changes, so that egui_extras takes the preset widths instead of the ones resized before
+        self.state.layout_generation += 1;
+        self.state.active_layout_preset = Some(name.to_string());
+        self.state.columns_changed = true;
+        true
+    }
+
+    pub fn remove_layout_preset(&mut self, name: &str) {
+        self.config.layout_presets.retain(|p| p.name != name);
+        if self.state.active_layout_preset.as_deref() == Some(name) {
+            self.state.active_layout_preset = None;
+        }
+    }
+
+    pub fn layout_presets(&self) -> &[LayoutPreset] {
+        &self.config.layout_presets
+    }
+
+    /// Name of the last applied or saved preset.
+    pub fn active_layout_preset(&self) -> Option<&str> {
+        self.state.active_layout_preset.as_deref()
+    }
+
+    /// Dropdown switching between presets, with saving the current layout under a new name.
+    pub fn layout_presets_ui(&mut self, ui: &mut Ui) {
+        let title = match &self.state.active_layout_preset {
+            Some(name) => format!("Layout: {name}"),
+            None => "Layout".to_string(),
+        };
+        ui.menu_button(title, |ui| {
+            let mut apply = None;
+            let mut remove = None;
+            for preset in &self.config.layout_presets {
+                ui.horizontal(|ui| {
+                    let is_active =
+                        self.state.active_layout_preset.as_deref() == Some(preset.name.as_str());
+                    if ui.selectable_label(is_active, &preset.name).clicked() {
+                        apply = Some(preset.name.clone());
+                        ui.close_menu();
+                    }
+                    if ui
+                        .small_button("🗑")
+                        .on_hover_text("Remove preset")
+                        .clicked()
+                    {
+                        remove = Some(preset.name.clone());
+                    }
+                });
+            }
+            if !self.config.layout_presets.is_empty() {
+                ui.separator();
+            }
+            ui.horizontal(|ui| {
+                TextEdit::singleline(&mut self.state.new_layout_preset_name)
+                    .hint_text("Preset name")
+                    .desired_width(120.)
+                    .ui(ui);
+                let name = self.state.new_layout_preset_name.trim().to_string();
+                if ui
+                    .add_enabled(!name.is_empty(), Button::new("Save current"))
+                    .clicked()
+                {
+                    self.save_layout_preset(name);
+                    self.state.new_layout_preset_name.clear();
+                    ui.close_menu();
+                }
+            });
+            if let Some(name) = apply {
+                self.apply_layout_preset(&name);
+            }
+            if let Some(name) = remove {
+                self.remove_layout_preset(&name);
+            }
+        });
+    }
+}
@@ -0,0 +1,128 @@
+use crate::backend::{CellCoord, TableBackend, VisualRowIdx};
+use crate::table_view::state::SelectedRange;
+use egui::{Key, Modifiers};
+
+impl super::TableView {
+    /// Keyboard navigation while a cell is selected and not being edited, as in spreadsheets.
+    /// Arrows move by one cell, Ctrl+Arrow jumps to the edge of a block of filled cells.
+    /// Home and End go to the first and last column, Ctrl+Home and Ctrl+End to the table corners.
+    /// Page Up and Page Down move by the number of rows currently in view.
+    pub(super) fn handle_selection_moves(
+        &mut self,
+        backend: &impl TableBackend,
+        ctx: &egui::Context,
+    ) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let Some(selected) = self.state.selected_range.filter(|r| !r.is_editing()) else {
+            return;
+        };
+        let row_count = backend.row_count();
+        let col_count = self.state.columns.len();
+        if row_count == 0 || col_count == 0 {
+            return;
+        }
+        let (left, right) = if self.state.is_right_to_left {
+            (Key::ArrowRight, Key::ArrowLeft)
+        } else {
+            (Key::ArrowLeft, Key::ArrowRight)
+        };
+        let page = self
+            .state
+            .visible_rows
+            .as_ref()
+            .map(|r| r.len().saturating_sub(1).max(1))
+            .unwrap_or(1);
+        let (row_idx, col_idx) = (selected.row_start(), selected.col_start());
+        let last_row = row_count - 1;
+        let last_col = col_count - 1;
+        let is_empty = |row_idx: usize, col_idx: usize| {
+            let Some(row_uid) = backend.row_uid(VisualRowIdx(row_idx)) else {
+                return true;
+            };
+            let col_uid = self.state.columns[col_idx];
+            backend.is_cell_empty(CellCoord { row_uid, col_uid })
+        };
+        // Ctrl variants must be consumed first
+        let target = ctx.input_mut(|i| {
+            if i.consume_key(Modifiers::COMMAND, Key::ArrowUp) {
+                Some((jump(row_idx, 0, |r| is_empty(r, col_idx)), col_idx))
+            } else if i.consume_key(Modifiers::COMMAND, Key::ArrowDown) {
+                Some((jump(row_idx, last_row, |r| is_empty(r, col_idx)), col_idx))
+            } else if i.consume_key(Modifiers::COMMAND, left) {
+                Some((row_idx, jump(col_idx, 0, |c| is_empty(row_idx, c))))
+            } else if i.consume_key(Modifiers::COMMAND, right) {
+                Some((row_idx, jump(col_idx, last_col, |c| is_empty(row_idx, c))))
+            } else if i.consume_key(Modifiers::COMMAND, Key::Home) {
+                Some((0, 0))
+            } else if i.consume_key(Modifiers::COMMAND, Key::End) {
+                Some((last_row, last_col))
+            } else if i.consume_key(Modifiers::NONE, Key::ArrowUp) {
+                Some((row_idx.saturating_sub(1), col_idx))
+            } else if i.consume_key(Modifiers::NONE, Key::ArrowDown) {
+                Some(((row_idx + 1).min(last_row), col_idx))
+            } else if i.consume_key(Modifiers::NONE, left) {
+                Some((row_idx, col_idx.saturating_sub(1)))
+            } else if i.consume_key(Modifiers::NONE, right) {
+                Some((row_idx, (col_idx + 1).min(last_col)))
+            } else if i.consume_key(Modifiers::NONE, Key::Home) {
+                Some((row_idx, 0))
+            } else if i.consume_key(Modifiers::NONE, Key::End) {
+                Some((row_idx, last_col))
+            } else if i.consume_key(Modifiers::NONE, Key::PageUp) {
+                Some((row_idx.saturating_sub(page), col_idx))
+            } else if i.consume_key(Modifiers::NONE, Key::PageDown) {
+                Some(((row_idx + page).min(last_row), col_idx))
+            } else {
+                None
+            }
+        });
+        let Some((row_idx, col_idx)) = target else {
+            return;
+        };
+        if selected.is_single_cell()
+            && (row_idx, col_idx) == (selected.row_start(), selected.col_start())
+        {
+            // Already at the edge
+            let now = ctx.input(|i| i.time);
+            self.state.flash_rejected([selected], now);
+            return;
+        }
+        self.state.select(
+            SelectedRange::single(row_idx.min(last_row), col_idx.min(last_col)),
+            false,
+        );
+        let is_in_view = self
+            .state
+            .visible_rows
+            .as_ref()
+            .is_some_and(|r| r.start < row_idx && row_idx + 1 < r.end);
+        if !is_in_view {
+            self.state.scroll_to_row = Some(row_idx);
+        }
+    }
+}
+
+/// Index reached with Ctrl+Arrow from `from` towards `to`: the last filled cell of the current block,
+/// or the first filled one after empty cells, or `to` if there are none.
+fn jump(from: usize, to: usize, is_empty: impl Fn(usize) -> bool) -> usize {
+    let next = |idx: usize| if to > idx { idx + 1 } else { idx - 1 };
+    if from == to {
+        return from;
+    }
+    let mut idx = from;
+    if !is_empty(from) && !is_empty(next(from)) {
+        while idx != to && !is_empty(next(idx)) {
+            idx = next(idx);
+        }
+        return idx;
+    }
+    while idx != to {
+        idx = next(idx);
+        if !is_empty(idx) {
+            break;
+        }
+    }
+    idx
+}
@@ -0,0 +1,280 @@
+use crate::backend::{CellCoord, TableBackend, VisualRowIdx};
+use crate::table_view::clipboard::TypedClipboard;
+use crate::table_view::state::SelectedRange;
+use egui::{Align2, Event, Ui, Window};
+use rvariant::Variant;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Ways a pasted block might not fit into the selection, user is asked what to do for each of them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PasteMismatch {
+    /// Pasted block has less rows or columns than selected.
+    BlockSmaller,
+    /// Pasted block has more rows or columns than selected.
+    BlockLarger,
+    /// Rows of the pasted block have different number of cells.
+    RaggedBlock,
+    /// Pasted block extends below the last row of a table that can have rows appended.
+    PastLastRow,
+}
+
+/// Choices made in the paste dialog, used as defaults next time it is shown.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PasteConfig {
+    /// Repeat smaller block until the whole selection is filled.
+    pub repeat_to_fill: bool,
+    /// Paste whole block starting from selection, even if it is larger than the selection.
+    pub extend_beyond_selection: bool,
+    /// Leave cells without pasted values unchanged, otherwise they are cleared.
+    pub skip_missing_cells: bool,
+    /// Append rows for the part of the block below the last row, otherwise it is left out.
+    #[serde(default)]
+    pub append_rows: bool,
+    /// Mismatches for which the last choice is used without showing the dialog.
+    pub dont_ask: HashSet<PasteMismatch>,
+}
+
+impl Default for PasteConfig {
+    fn default() -> Self {
+        PasteConfig {
+            repeat_to_fill: false,
+            extend_beyond_selection: true,
+            skip_missing_cells: true,
+            append_rows: false,
+            dont_ask: HashSet::new(),
+        }
+    }
+}
+
+impl PasteConfig {
+    /// Show the paste dialog again for all mismatches.
+    pub fn ask_again(&mut self) {
+        self.dont_ask.clear();
+    }
+}
+
+/// Block waiting for the user's confirmation in the paste dialog.
+pub(super) struct PendingPaste {
+    rows: Vec<Vec<Variant>>,
+    mismatches: Vec<PasteMismatch>,
+}
+
+impl super::TableView {
+    /// Paste tab separated text into the selection, asking the user what to do if it doesn't fit.
+    pub(super) fn handle_paste(&mut self, backend: &mut impl TableBackend, ui: &Ui) {
+        let Some(selected) = self.state.selected_range.filter(|r| !r.is_editing()) else {
+            return;
+        };
+        let text = ui.input(|i| {
+            i.events.iter().find_map(|e| match e {
+                Event::Paste(text) => Some(text.clone()),
+                _ => None,
+            })
+        });
+        let Some(text) = text else {
+            return;
+        };
+        if self.config.read_only {
+            let now = ui.input(|i| i.time);
+            self.state.flash_rejected([selected], now);
+            return;
+        }
+        // Copied from a table of this app, values keep their types
+        let rows = TypedClipboard::load(ui.ctx(), &text).unwrap_or_else(|| {
+            text.trim_end_matches(['\r', '\n'])
+                .split('\n')
+                .map(|row| {
+                    row.trim_end_matches('\r')
+                        .split('\t')
+                        .map(|cell| Variant::Str(cell.trim().to_string()))
+                        .collect()
+                })
+                .collect()
+        });
+        let block_height = rows.len();
+        let block_width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+
+        let mut mismatches = vec![];
+        // Single selected cell is a starting point for any block
+        if !selected.is_single_cell() {
+            if block_height < selected.height() || block_width < selected.width() {
+                mismatches.push(PasteMismatch::BlockSmaller);
+            }
+            if block_height > selected.height() || block_width > selected.width() {
+                mismatches.push(PasteMismatch::BlockLarger);
+            }
+        }
+        if rows.iter().any(|r| r.len() != block_width) {
+            mismatches.push(PasteMismatch::RaggedBlock);
+        }
+        if selected.row_start() + block_height > backend.row_count() && backend.can_append_rows() {
+            mismatches.push(PasteMismatch::PastLastRow);
+        }
+        let ask = mismatches
+            .iter()
+            .any(|m| !self.config.paste.dont_ask.contains(m));
+        if ask {
+            self.state.pending_paste = Some(PendingPaste { rows, mismatches });
+        } else {
+            self.paste_block(backend, &rows, ui.input(|i| i.time));
+        }
+    }
+
+    pub(super) fn show_paste_dialog(&mut self, backend: &mut impl TableBackend, ui: &Ui) {
+        let Some(pending) = &self.state.pending_paste else {
+            return;
+        };
+        let block_height = pending.rows.len();
+        let block_width = pending.rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        let (selected_height, selected_width) = self
+            .state
+            .selected_range
+            .map(|r| (r.height(), r.width()))
+            .unwrap_or_default();
+        let config = &mut self.config.paste;
+        let mut paste = false;
+        let mut cancel = false;
+        Window::new("Paste")
+            .id(ui.id().with("_egui_tabular_paste_dialog"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, [0., 0.])
+            .show(ui.ctx(), |ui| {
+                ui.label(format!(
+                    "You are about to paste {block_height}x{block_width} block into {selected_height}x{selected_width} selection"
+                ));
+                for mismatch in &pending.mismatches {
+                    ui.separator();
+                    match mismatch {
+                        PasteMismatch::BlockSmaller => {
+                            ui.checkbox(&mut config.repeat_to_fill, "Repeat block to fill selection");
+                        }
+                        PasteMismatch::BlockLarger => {
+                            ui.checkbox(
+                                &mut config.extend_beyond_selection,
+                                "Paste whole block, beyond selection",
+                            );
+                        }
+                        PasteMismatch::RaggedBlock => {
+                            ui.checkbox(
+                                &mut config.skip_missing_cells,
+                                "Leave cells missing in shorter rows unchanged",
+                            );
+                        }
+                        PasteMismatch::PastLastRow => {
+                            ui.checkbox(
+                                &mut config.append_rows,
+                                "Append rows for the part of the block below the last row",
+                            );
+                        }
+                    }
+                    let mut dont_ask = config.dont_ask.contains(mismatch);
+                    if ui
+                        .checkbox(&mut dont_ask, "Always do this without asking")
+                        .changed()
+                    {
+                        if dont_ask {
+                            config.dont_ask.insert(*mismatch);
+                        } else {
+                            config.dont_ask.remove(mismatch);
+                        }
+                    }
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    cancel = ui.button("Cancel").clicked();
+                    paste = ui.button("Paste").clicked();
+                });
+            });
+        if paste {
+            if let Some(pending) = self.state.pending_paste.take() {
+                self.paste_block(backend, &pending.rows, ui.input(|i| i.time));
+            }
+        } else if cancel {
+            self.state.pending_paste = None;
+        }
+    }
+
+    /// Write block starting from the top left selected cell, according to the paste config.
+    /// Unused columns are left as they are.
+    fn paste_block(&mut self, backend: &mut impl TableBackend, rows: &[Vec<Variant>], now: f64) {
+        profile_function!();
+        let Some(selected) = self.state.selected_range else {
+            return;
+        };
+        let config = &self.config.paste;
+        let block_height = rows.len();
+        let block_width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        if block_height == 0 || block_width == 0 {
+            return;
+        }
+        let fit = |block: usize, selected: usize| {
+            if selected == 1 || block == selected {
+                block
+            } else if block < selected {
+                if config.repeat_to_fill {
+                    selected
+                } else {
+                    block
+                }
+            } else if config.extend_beyond_selection {
+                block
+            } else {
+                selected
+            }
+        };
+        let height = fit(block_height, selected.height());
+        let width = fit(block_width, selected.width());
+
+        let mut values = vec![];
+        let rejected_cols: Vec<usize> = (selected.col_start()..selected.col_start() + width)
+            .filter(|col_idx| {
+                self.state
+                    .columns
+                    .get(*col_idx)
+                    .is_some_and(|col_uid| self.state.unused_columns.contains(col_uid))
+            })
+            .collect();
+        for r in 0..height {
+            let row_uid = match backend.row_uid(VisualRowIdx(selected.row_start() + r)) {
+                Some(row_uid) => row_uid,
+                None if config.append_rows && backend.can_append_rows() => {
+                    let Some(row_uid) = backend.append_row() else {
+                        break;
+                    };
+                    row_uid
+                }
+                None => break,
+            };
+            let row = &rows[r % block_height];
+            for c in 0..width {
+                let Some(col_uid) = self.state.columns.get(selected.col_start() + c) else {
+                    break;
+                };
+                if rejected_cols.contains(&(selected.col_start() + c)) {
+                    continue;
+                }
+                let value = match row.get(c % block_width) {
+                    Some(value) => value.clone(),
+                    None if config.skip_missing_cells => continue,
+                    None => Variant::Empty,
+                };
+                values.push((
+                    CellCoord {
+                        row_uid,
+                        col_uid: *col_uid,
+                    },
+                    value,
+                ));
+            }
+        }
+        backend.modify_many(values);
+        self.state.row_bands = None;
+        let last_row = selected.row_start() + height - 1;
+        let rejected = rejected_cols.into_iter().map(|col_idx| {
+            SelectedRange::spanning(selected.row_start(), col_idx, last_row, col_idx)
+        });
+        self.state.flash_rejected(rejected, now);
+    }
+}
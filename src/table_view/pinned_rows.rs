@@ -0,0 +1,105 @@
+use crate::backend::{CellCoord, CellState, ColumnUid, TableBackend};
+use crate::table_view::state::{DisplayColumn, TablePart};
+use crate::table_view::{placeholder, redaction, units};
+use egui::{Sense, Ui, Widget};
+use egui_extras::{Column, TableBuilder};
+
+impl super::TableView {
+    /// Rows pinned in the backend, shown between the header and the scrolled body with the same column widths.
+    pub(super) fn pinned_rows_ui(
+        &mut self,
+        backend: &mut impl TableBackend,
+        ui: &mut Ui,
+        part: &TablePart,
+        columns: &[ColumnUid],
+    ) {
+        let pinned_rows = backend.pinned_rows();
+        if pinned_rows.is_empty() {
+            return;
+        }
+        let cell_order = self.state.cell_order(part);
+        let folded_units = self.folded_unit_columns();
+        let mut builder = TableBuilder::new(ui)
+            .id_salt((self.config.id_salt, part.is_pinned, "pinned_rows"))
+            .vscroll(false)
+            .sense(Sense::click());
+        if self.state.is_right_to_left {
+            builder = builder.cell_layout(egui::Layout::right_to_left(egui::Align::Center));
+        }
+        for display_column in &cell_order {
+            let width = match display_column {
+                DisplayColumn::Data(col_idx) => {
+                    self.state.column_widths.get(&columns[*col_idx]).copied()
+                }
+                DisplayColumn::Tool => self.state.tool_column_width,
+                DisplayColumn::Actions => self.state.actions_column_width,
+            };
+            builder = builder.column(width.map(Column::exact).unwrap_or(Column::auto()));
+        }
+        let mut unpin = None;
+        builder.body(|body| {
+            body.rows(
+                self.config.minimum_row_height,
+                pinned_rows.len(),
+                |mut row| {
+                    let row_uid = pinned_rows[row.index()];
+                    for display_column in &cell_order {
+                        let col_idx = match display_column {
+                            DisplayColumn::Data(col_idx) => *col_idx,
+                            DisplayColumn::Tool => {
+                                row.col(|ui| {
+                                    let unpin_button = egui::Button::new("📌")
+                                        .small()
+                                        .frame(false)
+                                        .ui(ui)
+                                        .on_hover_text("Unpin row");
+                                    if unpin_button.clicked() {
+                                        unpin = Some(row_uid);
+                                    }
+                                });
+                                continue;
+                            }
+                            DisplayColumn::Actions => {
+                                row.col(|_| {});
+                                continue;
+                            }
+                        };
+                        let col_uid = columns[col_idx];
+                        let coord = CellCoord { row_uid, col_uid };
+                        let masked = self.is_masked(col_uid);
+                        row.col(|ui| {
+                            if masked {
+                                redaction::masked_value_ui(ui);
+                                return;
+                            }
+                            match backend.cell_state(coord) {
+                                CellState::Available => {
+                                    ui.add_enabled_ui(false, |ui| {
+                                        self.config.cell_renderers.cell_view_ui(backend, coord, ui);
+                                    });
+                                    if let Some(unit_col_uid) = folded_units.get(&col_uid) {
+                                        let coord = CellCoord {
+                                            row_uid,
+                                            col_uid: *unit_col_uid,
+                                        };
+                                        units::unit_suffix_ui(backend, coord, ui);
+                                    }
+                                }
+                                CellState::Loading => placeholder::loading_placeholder_ui(ui),
+                            }
+                        });
+                    }
+                    row.response().context_menu(|ui| {
+                        if ui.button("Unpin row").clicked() {
+                            unpin = Some(row_uid);
+                            ui.close_menu();
+                        }
+                    });
+                },
+            );
+        });
+        if let Some(row_uid) = unpin {
+            backend.pin_row(row_uid, false);
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use egui::{Rounding, Sense, Ui, Vec2};
+
+/// Shimmer period in seconds.
+const PERIOD: f64 = 1.2;
+
+/// Pulsing bar shown in place of a cell value that is still being fetched.
+pub(super) fn loading_placeholder_ui(ui: &mut Ui) {
+    let height = ui.text_style_height(&egui::TextStyle::Body) * 0.7;
+    let width = (ui.available_width() * 0.8).max(0.);
+    let (rect, _) = ui.allocate_exact_size(Vec2::new(width, height), Sense::hover());
+    let time = ui.input(|i| i.time);
+    let phase = (time % PERIOD / PERIOD * std::f64::consts::TAU).sin() as f32;
+    let color = ui
+        .visuals()
+        .widgets
+        .noninteractive
+        .bg_stroke
+        .color
+        .gamma_multiply(0.6 + 0.3 * phase);
+    ui.painter()
+        .rect_filled(rect, Rounding::same(height / 2.), color);
+    ui.ctx().request_repaint();
+}
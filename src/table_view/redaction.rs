@@ -0,0 +1,31 @@
+use crate::backend::ColumnUid;
+use egui::{Label, RichText, Ui, Widget};
+
+impl super::TableView {
+    /// Whether values of a column are currently hidden, only sensitive columns can be masked.
+    pub fn is_masked(&self, col_uid: ColumnUid) -> bool {
+        self.config.sensitive_columns.contains(&col_uid)
+            && !self.state.reveal_all
+            && !self.state.revealed_columns.contains(&col_uid)
+    }
+
+    /// Show or mask again values of a sensitive column.
+    pub fn reveal_column(&mut self, col_uid: ColumnUid, reveal: bool) {
+        if reveal {
+            self.state.revealed_columns.insert(col_uid);
+        } else {
+            self.state.revealed_columns.remove(&col_uid);
+        }
+    }
+
+    /// Show values of all sensitive columns, or mask the ones not revealed individually.
+    pub fn reveal_all(&mut self, reveal: bool) {
+        self.state.reveal_all = reveal;
+    }
+}
+
+pub(super) fn masked_value_ui(ui: &mut Ui) {
+    Label::new(RichText::new("••••").weak())
+        .selectable(false)
+        .ui(ui);
+}
@@ -0,0 +1,36 @@
+use crate::table_view::state::{SelectedRange, State};
+
+/// How long cells of a rejected action stay highlighted, in seconds.
+const FLASH_DURATION: f64 = 0.4;
+
+impl State {
+    /// Briefly highlight cells an action could not change, so that a no-op doesn't go unnoticed,
+    /// see [flash_rejected_actions](super::TableViewConfig::flash_rejected_actions).
+    pub(super) fn flash_rejected(
+        &mut self,
+        ranges: impl IntoIterator<Item = SelectedRange>,
+        now: f64,
+    ) {
+        let ranges: Vec<SelectedRange> = ranges.into_iter().collect();
+        if !ranges.is_empty() {
+            self.rejected_flash = Some((ranges, now));
+        }
+    }
+}
+
+impl super::TableView {
+    /// Ranges to highlight with the highlight opacity, None when there is nothing to show.
+    pub(super) fn rejected_flash(
+        &mut self,
+        ctx: &egui::Context,
+    ) -> Option<(Vec<SelectedRange>, f32)> {
+        let (ranges, started) = self.state.rejected_flash.as_ref()?;
+        let elapsed = ctx.input(|i| i.time) - started;
+        if !self.config.flash_rejected_actions || elapsed > FLASH_DURATION {
+            self.state.rejected_flash = None;
+            return None;
+        }
+        ctx.request_repaint();
+        Some((ranges.clone(), 1. - (elapsed / FLASH_DURATION) as f32))
+    }
+}
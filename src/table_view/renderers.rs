@@ -0,0 +1,232 @@
+use crate::backend::{CellCoord, ColumnUid, TableBackend};
+use crate::util::{variant_ty, variant_ty_named};
+use egui::{Align2, FontId, Response, Ui};
+use rvariant::{Variant, VariantTy};
+
+/// Shows a cell value instead of [TableBackend::show_cell_view].
+pub type CellViewFn = Box<dyn Fn(&Variant, &mut Ui)>;
+/// Edits a cell value instead of [TableBackend::show_cell_editor], the value is written back
+/// with [TableBackend::modify_many] when the edit is committed.
+pub type CellEditFn = Box<dyn Fn(&mut Variant, &mut Ui) -> Response>;
+
+/// View and edit functions used table-wide for values of a type or for a column, overriding the ones
+/// of the backend. Built-in views of the plain types are registered by default and can be replaced,
+/// editors and everything else not registered here are shown by the backend itself.
+///
+/// Column entries take precedence over type entries. The type is the one of the value, or the one
+/// of the column for empty cells.
+pub struct CellRenderers {
+    views: Vec<(RendererKey, CellViewFn)>,
+    editors: Vec<(RendererKey, CellEditFn)>,
+}
+
+#[derive(PartialEq)]
+enum RendererKey {
+    Type(VariantTy),
+    Column(ColumnUid),
+}
+
+/// Value being edited with a registered editor, see [CellEditFn].
+pub(super) struct CustomEdit {
+    pub(super) coord: CellCoord,
+    pub(super) value: Variant,
+}
+
+/// Types shown with [builtin_view] by default.
+const BUILTIN_VIEW_TYPES: &[VariantTy] = &[
+    VariantTy::Bool,
+    VariantTy::U32,
+    VariantTy::U64,
+    VariantTy::I32,
+    VariantTy::I64,
+    VariantTy::F32,
+    VariantTy::F64,
+    VariantTy::Str,
+    VariantTy::StrList,
+];
+
+impl Default for CellRenderers {
+    fn default() -> Self {
+        let mut renderers = Self::empty();
+        for ty in BUILTIN_VIEW_TYPES {
+            renderers.view_type(*ty, builtin_view);
+        }
+        renderers
+    }
+}
+
+impl CellRenderers {
+    /// No views or editors at all, every cell is shown by the backend.
+    pub fn empty() -> Self {
+        CellRenderers {
+            views: Vec::new(),
+            editors: Vec::new(),
+        }
+    }
+
+    /// Show all values of a type with the provided function.
+    pub fn view_type(&mut self, ty: VariantTy, view: impl Fn(&Variant, &mut Ui) + 'static) {
+        Self::insert(&mut self.views, RendererKey::Type(ty), Box::new(view));
+    }
+
+    /// Edit all values of a type with the provided function.
+    pub fn edit_type(
+        &mut self,
+        ty: VariantTy,
+        edit: impl Fn(&mut Variant, &mut Ui) -> Response + 'static,
+    ) {
+        Self::insert(&mut self.editors, RendererKey::Type(ty), Box::new(edit));
+    }
+
+    /// Show all values of a column with the provided function.
+    pub fn view_column(&mut self, col_uid: ColumnUid, view: impl Fn(&Variant, &mut Ui) + 'static) {
+        Self::insert(
+            &mut self.views,
+            RendererKey::Column(col_uid),
+            Box::new(view),
+        );
+    }
+
+    /// Edit all values of a column with the provided function.
+    pub fn edit_column(
+        &mut self,
+        col_uid: ColumnUid,
+        edit: impl Fn(&mut Variant, &mut Ui) -> Response + 'static,
+    ) {
+        Self::insert(
+            &mut self.editors,
+            RendererKey::Column(col_uid),
+            Box::new(edit),
+        );
+    }
+
+    /// Go back to the backend view and editor for a type, including the built-in view.
+    pub fn remove_type(&mut self, ty: VariantTy) {
+        let key = RendererKey::Type(ty);
+        self.views.retain(|(k, _)| *k != key);
+        self.editors.retain(|(k, _)| *k != key);
+    }
+
+    /// Go back to the backend view and editor for a column.
+    pub fn remove_column(&mut self, col_uid: ColumnUid) {
+        let key = RendererKey::Column(col_uid);
+        self.views.retain(|(k, _)| *k != key);
+        self.editors.retain(|(k, _)| *k != key);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.views.is_empty() && self.editors.is_empty()
+    }
+
+    fn insert<F>(entries: &mut Vec<(RendererKey, F)>, key: RendererKey, f: F) {
+        match entries.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => *existing = f,
+            None => entries.push((key, f)),
+        }
+    }
+
+    fn find<'a, F>(
+        entries: &'a [(RendererKey, F)],
+        backend: &impl TableBackend,
+        col_uid: ColumnUid,
+        value: &Variant,
+    ) -> Option<&'a F> {
+        if entries.is_empty() {
+            return None;
+        }
+        let column = RendererKey::Column(col_uid);
+        if let Some((_, f)) = entries.iter().find(|(k, _)| *k == column) {
+            return Some(f);
+        }
+        let ty = variant_ty(value).or_else(|| {
+            backend
+                .column_info(col_uid)
+                .and_then(|info| variant_ty_named(&info.ty))
+        })?;
+        let ty = RendererKey::Type(ty);
+        entries.iter().find(|(k, _)| *k == ty).map(|(_, f)| f)
+    }
+
+    /// Show a cell with the registered view, or with the backend one if there is none.
+    pub(super) fn cell_view_ui(&self, backend: &impl TableBackend, coord: CellCoord, ui: &mut Ui) {
+        let value = backend.get(coord).unwrap_or(Variant::Empty);
+        match Self::find(&self.views, backend, coord.col_uid, &value) {
+            Some(view) => view(&value, ui),
+            None => backend.show_cell_view(coord, ui),
+        }
+        if let Some(badge) = backend.cell_badge(coord) {
+            ui.painter().text(
+                ui.max_rect().right_top(),
+                Align2::RIGHT_TOP,
+                badge,
+                FontId::proportional(8.),
+                ui.visuals().weak_text_color(),
+            );
+        }
+    }
+
+    /// Show the registered editor for a cell, or the backend one if there is none.
+    /// Value edited with a registered editor is kept in `edit` until committed or cancelled.
+    pub(super) fn cell_editor_ui(
+        &self,
+        backend: &impl TableBackend,
+        coord: CellCoord,
+        edit: &mut Option<CustomEdit>,
+        ui: &mut Ui,
+    ) -> Option<Response> {
+        if self.editors.is_empty() {
+            return backend.show_cell_editor(coord, ui);
+        }
+        let value = match edit.take() {
+            Some(edit) if edit.coord == coord => edit.value,
+            _ => backend.get(coord).unwrap_or(Variant::Empty),
+        };
+        let Some(editor) = Self::find(&self.editors, backend, coord.col_uid, &value) else {
+            return backend.show_cell_editor(coord, ui);
+        };
+        let mut value = value;
+        let resp = editor(&mut value, ui);
+        *edit = Some(CustomEdit { coord, value });
+        Some(resp)
+    }
+}
+
+/// Value shown as text, a checkbox for booleans and numbered lines for lists.
+pub(crate) fn builtin_view(value: &Variant, ui: &mut Ui) {
+    match value {
+        Variant::Empty => {}
+        Variant::Bool(v) => {
+            let mut v = *v;
+            ui.checkbox(&mut v, "");
+        }
+        Variant::Str(v) => {
+            ui.label(v);
+        }
+        Variant::StrList(list) => {
+            for (idx, v) in list.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.monospace(format!("{idx}:"));
+                    ui.label(v);
+                });
+            }
+        }
+        other => {
+            ui.label(other.to_string().as_str());
+        }
+    }
+}
+
+/// Write back a value edited with a registered editor, or commit the backend editor otherwise.
+pub(super) fn commit_cell_edit(
+    backend: &mut impl TableBackend,
+    coord: CellCoord,
+    edit: &mut Option<CustomEdit>,
+) {
+    match edit.take() {
+        Some(edit) if edit.coord == coord => {
+            backend.cancel_cell_edit(coord);
+            backend.modify_many(vec![(coord, edit.value)]);
+        }
+        _ => backend.commit_cell_edit(coord),
+    }
+}
@@ -0,0 +1,23 @@
+use crate::backend::{RowAction, RowUid};
+use egui::Ui;
+
+impl super::TableView {
+    /// Show an actions column on the right, `row_actions_ui` draws compact buttons of one row
+    /// (e.g. `ui.small_button("🗑")`) and returns an action if one of them was clicked.
+    /// Chosen actions are delivered as [TableEvent::RowAction](crate::backend::TableEvent::RowAction),
+    /// see [drain_events](Self::drain_events).
+    pub fn row_actions(
+        &mut self,
+        row_actions_ui: impl Fn(RowUid, &mut Ui) -> Option<RowAction> + 'static,
+    ) {
+        self.state.row_actions = Some(Box::new(row_actions_ui));
+    }
+
+    #[deprecated(note = "use row_actions, clicks are then delivered as events")]
+    pub fn actions_column(&mut self, row_actions_ui: impl Fn(RowUid, &mut Ui) + 'static) {
+        self.row_actions(move |row_uid, ui| {
+            row_actions_ui(row_uid, ui);
+            None
+        });
+    }
+}
@@ -0,0 +1,67 @@
+use crate::backend::{CellCoord, OneShotFlags, RowUid};
+use crate::table_view::shortcuts::SkipChange;
+use std::collections::HashMap;
+
+impl super::TableView {
+    /// Keep row keyed state pointing at the same rows after the backend reassigned row uids,
+    /// or drop it when rows were cleared or reloaded without a mapping.
+    pub(super) fn handle_row_identity_changes(&mut self, flags: &OneShotFlags) {
+        if let Some(map) = &flags.rows_remapped {
+            self.remap_rows(map);
+        } else if flags.cleared || flags.reloaded {
+            self.state.row_heights.clear();
+            self.state.incomplete_rows.clear();
+            self.state.cell_styles.clear();
+            self.forget_visual_positions();
+        }
+    }
+
+    fn remap_rows(&mut self, map: &HashMap<RowUid, RowUid>) {
+        let s = &mut self.state;
+        s.row_heights = s
+            .row_heights
+            .iter()
+            .filter_map(|(row_uid, height)| Some((*map.get(row_uid)?, *height)))
+            .collect();
+        s.incomplete_rows = s
+            .incomplete_rows
+            .iter()
+            .filter_map(|row_uid| map.get(row_uid).copied())
+            .collect();
+        s.cell_styles = core::mem::take(&mut s.cell_styles)
+            .into_iter()
+            .filter_map(|(coord, style)| {
+                let row_uid = *map.get(&coord.row_uid)?;
+                Some((
+                    CellCoord {
+                        row_uid,
+                        col_uid: coord.col_uid,
+                    },
+                    style,
+                ))
+            })
+            .collect();
+        for change in &mut s.skip_changes {
+            if let SkipChange::Rows { row_uids, .. } = change {
+                *row_uids = row_uids
+                    .iter()
+                    .filter_map(|row_uid| map.get(row_uid).copied())
+                    .collect();
+            }
+        }
+        self.forget_visual_positions();
+    }
+
+    /// Selection and other state stored as visual indices may now point at different rows.
+    fn forget_visual_positions(&mut self) {
+        let s = &mut self.state;
+        s.selected_range = None;
+        s.selection.clear();
+        s.copied_range = None;
+        s.hovered_cell = None;
+        s.last_hovered_cell = None;
+        s.committed_cell = None;
+        s.drag_select_anchor = None;
+        s.row_bands = None;
+    }
+}
@@ -0,0 +1,45 @@
+use crate::backend::{CellCoord, ColumnUid, RowUid, TableBackend};
+use crate::query::{QuerySpec, SortKey};
+use crate::util::variant_to_string;
+use rvariant::Variant;
+
+/// Rows are only sorted ascending by the order column, renumbering them in the shown order keeps it.
+pub(super) fn is_sorted_by_order(query: &QuerySpec, order_column: ColumnUid) -> bool {
+    query.group_by.is_none()
+        && query.sort
+            == [SortKey {
+                col_uid: order_column,
+                ascending: true,
+            }]
+}
+
+/// Number all rows 1..N in `order_column` as if `row_uid` was moved to `to`, in the order they are shown.
+/// Only changed cells are written, as one undoable action. Rows are not moved in the backend,
+/// the view is sorted by the order column instead.
+pub(super) fn renumber_rows(
+    backend: &mut impl TableBackend,
+    order_column: ColumnUid,
+    row_uid: RowUid,
+    to: usize,
+) {
+    let mut rows = backend.visible_rows();
+    rows.retain(|r| *r != row_uid);
+    rows.insert(to.min(rows.len()), row_uid);
+    let values: Vec<(CellCoord, Variant)> = rows
+        .into_iter()
+        .enumerate()
+        .filter_map(|(idx, row_uid)| {
+            let coord = CellCoord {
+                row_uid,
+                col_uid: order_column,
+            };
+            let position = (idx + 1).to_string();
+            let current = backend.get(coord).map(|v| variant_to_string(&v));
+            (current.as_deref() != Some(position.as_str()))
+                .then_some((coord, Variant::Str(position)))
+        })
+        .collect();
+    if !values.is_empty() {
+        backend.modify_many(values);
+    }
+}
@@ -0,0 +1,29 @@
+use crate::backend::{CellCoord, RowUid, TableBackend, VisualRowIdx};
+
+impl super::TableView {
+    /// Scroll the row into view on the next frame, e.g. to show a validation error or a search result.
+    /// Nothing happens if the row is not shown, e.g. filtered out.
+    pub fn scroll_to_row(&mut self, row_uid: RowUid) {
+        self.state.pending_scroll = Some((row_uid, None));
+    }
+
+    /// Scroll the cell into view on the next frame, also horizontally if the table is in a horizontal scroll area.
+    pub fn scroll_to_cell(&mut self, coord: CellCoord) {
+        self.state.pending_scroll = Some((coord.row_uid, Some(coord.col_uid)));
+    }
+
+    /// Find where the requested row is shown, rows can only be scrolled to by index.
+    pub(super) fn resolve_pending_scroll(&mut self, backend: &impl TableBackend) {
+        let Some((row_uid, col_uid)) = self.state.pending_scroll.take() else {
+            return;
+        };
+        let Some(row_idx) = (0..backend.row_count())
+            .map(VisualRowIdx)
+            .find(|row_idx| backend.row_uid(*row_idx) == Some(row_uid))
+        else {
+            return;
+        };
+        self.state.scroll_to_row = Some(row_idx.0);
+        self.state.scroll_to_cell = col_uid.map(|col_uid| CellCoord { row_uid, col_uid });
+    }
+}
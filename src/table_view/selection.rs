@@ -0,0 +1,56 @@
+use crate::backend::{RowUid, TableBackend, TableEvent, VisualRowIdx};
+use crate::table_view::state::SelectedRange;
+
+impl super::TableView {
+    /// Select all rows of a column after a click on its header, Shift extends the selection
+    /// across columns and Ctrl adds the column to it.
+    pub(super) fn select_column(&mut self, col_idx: usize, row_count: usize, ctx: &egui::Context) {
+        if row_count == 0 {
+            return;
+        }
+        let last_row_idx = row_count - 1;
+        let (shift, command) = ctx.input(|i| (i.modifiers.shift, i.modifiers.command));
+        match &mut self.state.selected_range {
+            Some(r) if shift => {
+                *r = SelectedRange::spanning(
+                    0,
+                    r.col_start().min(col_idx),
+                    last_row_idx,
+                    r.col_end().max(col_idx),
+                );
+            }
+            _ => self.state.select(
+                SelectedRange::spanning(0, col_idx, last_row_idx, col_idx),
+                command,
+            ),
+        }
+    }
+
+    /// Report rows of all selected ranges through [OneShotFlags::rows_selected](crate::backend::OneShotFlags::rows_selected)
+    /// when they change.
+    pub(super) fn report_selected_rows(&mut self, backend: &mut impl TableBackend) {
+        if self.state.selected_range.is_none() {
+            self.state.selection.clear();
+        }
+        if self
+            .state
+            .selected_ranges()
+            .eq(self.state.last_selected_ranges.iter())
+        {
+            return;
+        }
+        self.state.last_selected_ranges = self.state.selected_ranges().copied().collect();
+        let selected_rows = self.state.selected_rows();
+        if selected_rows == self.state.last_selected_rows {
+            return;
+        }
+        let row_uids: Vec<RowUid> = selected_rows
+            .iter()
+            .filter_map(|row_idx| backend.row_uid(VisualRowIdx(*row_idx)))
+            .collect();
+        let flags = backend.one_shot_flags_mut();
+        flags.push_event(TableEvent::SelectionChanged(row_uids.clone()));
+        flags.rows_selected = Some(row_uids);
+        self.state.last_selected_rows = selected_rows;
+    }
+}
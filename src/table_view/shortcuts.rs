@@ -0,0 +1,153 @@
+use crate::backend::{CellCoord, ColumnUid, RowUid, TableBackend, VisualRowIdx};
+use crate::table_view::fill::FillDirection;
+use crate::table_view::state::SelectedRange;
+use egui::{Key, KeyboardShortcut, Modifiers};
+use itertools::Itertools;
+
+/// Rows skipped or columns marked as unused with a keyboard shortcut or the tool column menu.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SkipChange {
+    Rows {
+        row_uids: Vec<RowUid>,
+        skip: bool,
+    },
+    Columns {
+        col_uids: Vec<ColumnUid>,
+        is_used: bool,
+    },
+}
+
+const UNDO: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+const REDO: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Z);
+const REDO_ALT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Y);
+const TOGGLE_SKIP_ROWS: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Minus);
+const TOGGLE_SKIP_COLUMNS: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Minus);
+const SELECT_ALL: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::A);
+const CLEAR: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::Delete);
+const CLEAR_ALT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::Backspace);
+const FILL_DOWN: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::D);
+const FILL_RIGHT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::R);
+
+impl super::TableView {
+    /// Shortcuts acting on selection, only handled while a cell is selected and not being edited.
+    /// Ctrl+Z to undo, Ctrl+Shift+Z or Ctrl+Y to redo.
+    /// Ctrl+- to toggle skip of selected rows, Ctrl+Shift+- to toggle use of selected columns.
+    /// Ctrl+A to select all cells, Delete or Backspace to clear selected cells.
+    /// Ctrl+D to fill the selection down from its first row, Ctrl+R to fill it right from its first column.
+    pub(super) fn handle_shortcuts(
+        &mut self,
+        backend: &mut impl TableBackend,
+        ctx: &egui::Context,
+    ) {
+        if !self.config.shortcuts || self.config.read_only {
+            return;
+        }
+        let Some(selected) = self.state.selected_range.filter(|r| !r.is_editing()) else {
+            return;
+        };
+        // Keys without modifiers may belong to a text field outside the table
+        let text_input = ctx.wants_keyboard_input();
+        // Shortcuts with more modifiers must be consumed first.
+        let (redo, undo, skip_columns, skip_rows, select_all, clear, fill) = ctx.input_mut(|i| {
+            (
+                i.consume_shortcut(&REDO) || i.consume_shortcut(&REDO_ALT),
+                i.consume_shortcut(&UNDO),
+                i.consume_shortcut(&TOGGLE_SKIP_COLUMNS),
+                i.consume_shortcut(&TOGGLE_SKIP_ROWS),
+                !text_input && i.consume_shortcut(&SELECT_ALL),
+                !text_input && (i.consume_shortcut(&CLEAR) || i.consume_shortcut(&CLEAR_ALT)),
+                if text_input {
+                    None
+                } else if i.consume_shortcut(&FILL_DOWN) {
+                    Some(FillDirection::Down)
+                } else if i.consume_shortcut(&FILL_RIGHT) {
+                    Some(FillDirection::Right)
+                } else {
+                    None
+                },
+            )
+        });
+        if select_all {
+            let row_count = backend.row_count();
+            let col_count = self.state.columns.len();
+            if row_count > 0 && col_count > 0 {
+                self.state
+                    .select(SelectedRange::block(0, 0, row_count, col_count), false);
+            }
+            return;
+        }
+        if clear {
+            let coords: Vec<CellCoord> = self
+                .state
+                .selected_ranges()
+                .flat_map(|r| {
+                    (r.row_start()..=r.row_end()).flat_map(move |row_idx| {
+                        (r.col_start()..=r.col_end()).map(move |col_idx| (row_idx, col_idx))
+                    })
+                })
+                // Ranges added with Ctrl+click may overlap
+                .unique()
+                .filter_map(|(row_idx, col_idx)| {
+                    Some(CellCoord {
+                        row_uid: backend.row_uid(VisualRowIdx(row_idx))?,
+                        col_uid: *self.state.columns.get(col_idx)?,
+                    })
+                })
+                .collect();
+            backend.clear_cells(coords.into_iter());
+            self.state.row_bands = None;
+        }
+        if let Some(direction) = fill {
+            self.fill_selection(backend, direction);
+        }
+        if undo {
+            backend.undo();
+        }
+        if redo {
+            backend.redo();
+        }
+        if undo || redo {
+            self.state.row_bands = None;
+        }
+
+        if skip_rows {
+            let row_uids: Vec<RowUid> = (selected.row_start()..=selected.row_end())
+                .filter_map(|row_idx| backend.row_uid(VisualRowIdx(row_idx)))
+                .collect();
+            // Skip all unless all are already skipped
+            let skip = !row_uids
+                .iter()
+                .all(|row_uid| backend.is_row_skipped(*row_uid));
+            for row_uid in &row_uids {
+                backend.skip_row(*row_uid, skip);
+            }
+            self.state
+                .skip_changes
+                .push(SkipChange::Rows { row_uids, skip });
+        }
+        if skip_columns {
+            let col_uids: Vec<ColumnUid> = self
+                .state
+                .columns
+                .get(selected.col_start()..=selected.col_end())
+                .unwrap_or_default()
+                .to_vec();
+            let is_used = col_uids
+                .iter()
+                .all(|col_uid| self.state.unused_columns.contains(col_uid));
+            for col_uid in &col_uids {
+                backend.use_column(*col_uid, is_used);
+            }
+            self.state
+                .skip_changes
+                .push(SkipChange::Columns { col_uids, is_used });
+        }
+    }
+
+    /// Returns skip changes made with keyboard shortcuts or the tool column menu since the last call.
+    pub fn take_skip_changes(&mut self) -> Vec<SkipChange> {
+        core::mem::take(&mut self.state.skip_changes)
+    }
+}
@@ -1,10 +1,165 @@
-use crate::backend::{ColumnUid, RowUid};
-use std::collections::HashMap;
+use crate::backend::{CellCoord, ColumnUid, RowAction, RowUid, TableEvent};
+use crate::query::QuerySpec;
+use crate::table_view::activity::ColumnActivity;
+use crate::table_view::banding::RowBands;
+use crate::table_view::cell_style::CellStyle;
+use crate::table_view::clipboard::CopyValues;
+use crate::table_view::column_settings::ColumnSettings;
+use crate::table_view::config::{TableViewState, ToolColumn};
+use crate::table_view::find::FindBar;
+use crate::table_view::history_scrubber::HistoryPreview;
+use crate::table_view::paste::PendingPaste;
+use crate::table_view::renderers::CustomEdit;
+use crate::table_view::shortcuts::SkipChange;
+use crate::table_view::toasts::Toast;
+use egui::Ui;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ops::Range;
 
 pub(super) struct State {
     pub(super) row_heights: HashMap<RowUid, f32>,
     pub(super) columns: Vec<ColumnUid>,
+    /// Columns that are shown, but not used by the backend.
+    pub(super) unused_columns: HashSet<ColumnUid>,
     pub(super) selected_range: Option<SelectedRange>,
+    /// Ranges added with Ctrl+click besides the selected one, cleared when it is.
+    pub(super) selection: Selection,
+    /// Rows of all selected ranges when they were last reported.
+    pub(super) last_selected_rows: BTreeSet<usize>,
+    /// Selected ranges [State::last_selected_rows] were collected from, rows are only collected again
+    /// when the ranges change.
+    pub(super) last_selected_ranges: Vec<SelectedRange>,
+    /// Cell where the pointer was pressed when selecting by dragging, (row_idx, col_idx).
+    pub(super) drag_select_anchor: Option<(usize, usize)>,
+    /// Cell which edit was committed with Enter in this frame, as (row, column) index.
+    pub(super) committed_cell: Option<(usize, usize)>,
+    /// View state to apply on next column info update.
+    pub(super) restore_view_state: Option<TableViewState>,
+    pub(super) toasts: Vec<Toast>,
+    /// Set when columns were renamed, reordered or (un)used from the header in the current frame.
+    pub(super) columns_changed: bool,
+    /// Pass in which backend flags were last handled and reset, see [egui::Context::cumulative_pass_nr].
+    pub(super) flags_pass_nr: Option<u64>,
+    /// Column which name is being edited in the header.
+    pub(super) renaming_column: Option<ColumnRename>,
+    /// Column which settings window is open.
+    pub(super) column_settings: Option<ColumnSettings>,
+    /// Columns are rendered in reverse order, selection indices are not affected.
+    pub(super) is_right_to_left: bool,
+    /// Cached row backgrounds when banding by value, None when not used or invalidated.
+    pub(super) row_bands: Option<RowBands>,
+    /// Changes made with keyboard shortcuts or the tool column menu, not yet taken by the user.
+    pub(super) skip_changes: Vec<SkipChange>,
+    /// Row to scroll to on the next frame.
+    pub(super) scroll_to_row: Option<usize>,
+    /// Requested with [TableView::scroll_to_row](super::TableView::scroll_to_row) or
+    /// [scroll_to_cell](super::TableView::scroll_to_cell), resolved to an index before showing the table.
+    pub(super) pending_scroll: Option<(RowUid, Option<ColumnUid>)>,
+    /// Cell to scroll to horizontally once its row is shown.
+    pub(super) scroll_to_cell: Option<CellCoord>,
+    /// Pasted block waiting for the user's confirmation.
+    pub(super) pending_paste: Option<PendingPaste>,
+    /// Last copied range, shown with a dashed border until Escape is pressed.
+    pub(super) copied_range: Option<SelectedRange>,
+    /// Cell under the pointer in any of the table parts in the current frame, (row_idx, col_idx).
+    pub(super) hovered_cell: Option<(usize, usize)>,
+    /// [State::hovered_cell] of the previous frame.
+    pub(super) last_hovered_cell: Option<(usize, usize)>,
+    /// Widths of the header cells, used when the body is shown separately.
+    pub(super) column_widths: HashMap<ColumnUid, f32>,
+    pub(super) tool_column_width: Option<f32>,
+    pub(super) actions_column_width: Option<f32>,
+    /// Vertical scroll offset shared by pinned and scrolled tables.
+    pub(super) pinned_scroll_offset: f32,
+    pub(super) row_actions: Option<RowActionsUi>,
+    pub(super) query: QuerySpec,
+    /// Query was changed in the UI or by the user and should be passed to the backend.
+    pub(super) query_changed: bool,
+    /// Recent cell updates per column, only tracked if enabled in the config.
+    pub(super) column_activity: HashMap<ColumnUid, ColumnActivity>,
+    /// Sensitive columns shown unmasked.
+    pub(super) revealed_columns: HashSet<ColumnUid>,
+    pub(super) reveal_all: bool,
+    /// Rows missing required values, updated once cells or rows change.
+    pub(super) incomplete_rows: HashSet<RowUid>,
+    pub(super) incomplete_rows_outdated: bool,
+    /// Incremented each time a layout preset is applied.
+    pub(super) layout_generation: usize,
+    pub(super) active_layout_preset: Option<String>,
+    /// Name typed in the layout presets dropdown.
+    pub(super) new_layout_preset_name: String,
+    /// Smoothed time the table took to show, only measured when the overlay is enabled.
+    pub(super) frame_time_ms: Option<f32>,
+    /// Value columns with their unit column shown separately.
+    pub(super) expanded_unit_columns: HashSet<ColumnUid>,
+    /// Editor was opened from the keyboard and should take focus once shown.
+    pub(super) focus_cell_editor: bool,
+    /// Rows shown in the body during the last frame, used to move by a page.
+    pub(super) visible_rows: Option<Range<usize>>,
+    /// Last row the fill handle is dragged to.
+    pub(super) fill_drag_to: Option<usize>,
+    /// Search bar, open while Some.
+    pub(super) find: Option<FindBar>,
+    /// Values chosen in the "Copy as…" menu, None to copy as configured.
+    pub(super) copy_as_values: Option<CopyValues>,
+    /// Lints, tooltips and text formats set by the application.
+    pub(super) cell_styles: HashMap<CellCoord, CellStyle>,
+    /// Waiting for [TableView::drain_events](super::TableView::drain_events).
+    pub(super) events: Vec<TableEvent>,
+    /// Cells of the last rejected action and when it happened.
+    pub(super) rejected_flash: Option<(Vec<SelectedRange>, f64)>,
+    /// Invalid cells reported by the backend, None if not collected yet or outdated.
+    pub(super) validation_errors: Option<Vec<(CellCoord, String)>>,
+    /// Positions of the rows with validation errors, collected along with them.
+    pub(super) validation_error_rows: HashMap<RowUid, usize>,
+    /// Edit history is being scrubbed through, the table is read-only meanwhile.
+    pub(super) history_preview: Option<HistoryPreview>,
+    /// Value in a registered cell editor, see [CellRenderers](super::CellRenderers).
+    pub(super) custom_edit: Option<CustomEdit>,
+    /// Rows fully drawn in any of the table parts in the current frame.
+    pub(super) rendered_rows: HashSet<RowUid>,
+    /// Rows fully drawn last frame, the others are subject to [render_budget](super::TableViewConfig::render_budget).
+    pub(super) last_rendered_rows: HashSet<RowUid>,
+}
+
+pub(super) struct ColumnRename {
+    pub(super) col_uid: ColumnUid,
+    pub(super) name: String,
+    pub(super) focus_requested: bool,
+}
+
+/// Columns shown by one table, there are two of them when some columns are pinned.
+pub(super) struct TablePart {
+    /// Range of shown column indices.
+    pub(super) columns: Range<usize>,
+    pub(super) tool_column: ToolColumn,
+    pub(super) actions_column: bool,
+    pub(super) is_pinned: bool,
+}
+
+/// Column of the rendered table.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum DisplayColumn {
+    /// Row numbers.
+    Tool,
+    /// Index into the shown columns.
+    Data(usize),
+    /// Per-row buttons provided by the host.
+    Actions,
+}
+
+/// Draws buttons of one row in the actions column.
+pub(super) type RowActionsUi = Box<dyn Fn(RowUid, &mut Ui) -> Option<RowAction>>;
+
+/// Drag and drop payload used to move a row from the tool column.
+pub(super) struct RowDrag {
+    pub(super) row_uid: RowUid,
+}
+
+/// Drag and drop payload used to move a cell value to another row.
+pub(super) struct CellDrag {
+    pub(super) col_uid: ColumnUid,
+    pub(super) row_idx: usize,
 }
 
 impl Default for State {
@@ -12,9 +167,179 @@ impl Default for State {
         State {
             row_heights: HashMap::new(),
             columns: Vec::new(),
+            unused_columns: HashSet::new(),
             selected_range: None,
+            selection: Selection::default(),
+            last_selected_rows: BTreeSet::new(),
+            last_selected_ranges: Vec::new(),
+            drag_select_anchor: None,
+            committed_cell: None,
+            restore_view_state: None,
+            toasts: Vec::new(),
+            columns_changed: false,
+            flags_pass_nr: None,
+            renaming_column: None,
+            column_settings: None,
+            is_right_to_left: false,
+            row_bands: None,
+            skip_changes: Vec::new(),
+            scroll_to_row: None,
+            pending_scroll: None,
+            scroll_to_cell: None,
+            pending_paste: None,
+            copied_range: None,
+            hovered_cell: None,
+            last_hovered_cell: None,
+            column_widths: HashMap::new(),
+            tool_column_width: None,
+            actions_column_width: None,
+            pinned_scroll_offset: 0.,
+            row_actions: None,
+            column_activity: HashMap::new(),
+            revealed_columns: HashSet::new(),
+            reveal_all: false,
+            incomplete_rows: HashSet::new(),
+            incomplete_rows_outdated: true,
+            layout_generation: 0,
+            active_layout_preset: None,
+            new_layout_preset_name: String::new(),
+            frame_time_ms: None,
+            expanded_unit_columns: HashSet::new(),
+            focus_cell_editor: false,
+            visible_rows: None,
+            fill_drag_to: None,
+            find: None,
+            copy_as_values: None,
+            cell_styles: HashMap::new(),
+            events: Vec::new(),
+            rejected_flash: None,
+            validation_errors: None,
+            validation_error_rows: HashMap::new(),
+            history_preview: None,
+            custom_edit: None,
+            rendered_rows: HashSet::new(),
+            last_rendered_rows: HashSet::new(),
+            query: QuerySpec::default(),
+            query_changed: false,
+        }
+    }
+}
+
+impl State {
+    /// Selected range followed by the ones added with Ctrl+click.
+    pub(super) fn selected_ranges(&self) -> impl Iterator<Item = &SelectedRange> {
+        self.selected_range.iter().chain(self.selection.ranges())
+    }
+
+    pub(super) fn is_selected(&self, row_idx: usize, col_idx: usize) -> bool {
+        self.selected_ranges().any(|r| r.contains(row_idx, col_idx))
+    }
+
+    /// Add a cell to the selection on Ctrl+click, or remove it if it is already selected.
+    /// The last added range becomes the selected one, used for editing, pasting and other single range actions.
+    pub(super) fn toggle_cell(&mut self, row_idx: usize, col_idx: usize) {
+        if self.is_selected(row_idx, col_idx) {
+            if let Some(selected) = self.selected_range.take() {
+                self.selection.push(selected);
+            }
+            self.selection.remove_cell(row_idx, col_idx);
+            self.selected_range = self.selection.pop();
+        } else {
+            if let Some(selected) = self.selected_range.take() {
+                self.selection.push(selected);
+            }
+            self.selected_range = Some(SelectedRange::single(row_idx, col_idx));
         }
     }
+
+    /// Start a new range, keeping the current ones if `add` is true.
+    pub(super) fn select(&mut self, range: SelectedRange, add: bool) {
+        match self.selected_range.take() {
+            Some(selected) if add => self.selection.push(selected),
+            _ => self.selection.clear(),
+        }
+        self.selected_range = Some(range);
+    }
+
+    /// Union of the rows of all selected ranges.
+    pub(super) fn selected_rows(&self) -> BTreeSet<usize> {
+        self.selected_ranges()
+            .flat_map(|r| r.row_start()..=r.row_end())
+            .collect()
+    }
+
+    /// Union of the columns of all selected ranges.
+    pub(super) fn selected_cols(&self) -> BTreeSet<usize> {
+        self.selected_ranges()
+            .flat_map(|r| r.col_start()..=r.col_end())
+            .collect()
+    }
+
+    /// Columns of one table part in the order they are rendered, with the tool and actions columns added if shown.
+    pub(super) fn cell_order(&self, part: &TablePart) -> Vec<DisplayColumn> {
+        let columns = part.columns.clone();
+        let mut order: Vec<DisplayColumn> = if self.is_right_to_left {
+            columns.rev().map(DisplayColumn::Data).collect()
+        } else {
+            columns.map(DisplayColumn::Data).collect()
+        };
+        match part.tool_column {
+            ToolColumn::Hidden => {}
+            ToolColumn::Left => order.insert(0, DisplayColumn::Tool),
+            ToolColumn::Right => order.push(DisplayColumn::Tool),
+        }
+        if part.actions_column {
+            order.push(DisplayColumn::Actions);
+        }
+        order
+    }
+}
+
+/// Ranges selected in addition to [State::selected_range], possibly overlapping.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Selection {
+    ranges: Vec<SelectedRange>,
+}
+
+impl Selection {
+    pub fn ranges(&self) -> &[SelectedRange] {
+        &self.ranges
+    }
+
+    pub fn clear(&mut self) {
+        self.ranges.clear();
+    }
+
+    pub fn push(&mut self, range: SelectedRange) {
+        self.ranges.push(range);
+    }
+
+    pub fn pop(&mut self) -> Option<SelectedRange> {
+        self.ranges.pop()
+    }
+
+    /// Deselect one cell, splitting the ranges containing it into up to four parts around it.
+    pub fn remove_cell(&mut self, row_idx: usize, col_idx: usize) {
+        self.ranges = self
+            .ranges
+            .iter()
+            .flat_map(|r| {
+                if r.contains(row_idx, col_idx) {
+                    r.without_cell(row_idx, col_idx)
+                } else {
+                    vec![*r]
+                }
+            })
+            .collect();
+    }
+}
+
+/// Where selection goes after committing a cell edit, Enter moves down and Tab or Shift+Tab sideways.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum EditCommitMove {
+    Down,
+    Right,
+    Left,
 }
 
 /// All indices are from 0 to row or column count currently in view
@@ -47,6 +372,28 @@ impl SelectedRange {
         }
     }
 
+    /// Range of the given size starting from the cell.
+    pub fn block(row_idx: usize, col_idx: usize, height: usize, width: usize) -> Self {
+        SelectedRange {
+            row_start: row_idx,
+            row_end: row_idx + height.max(1) - 1,
+            col_start: col_idx,
+            col_end: col_idx + width.max(1) - 1,
+            is_editing: false,
+        }
+    }
+
+    /// Range between two opposite corners given in any order.
+    pub fn spanning(row1_idx: usize, col1_idx: usize, row2_idx: usize, col2_idx: usize) -> Self {
+        SelectedRange {
+            row_start: row1_idx.min(row2_idx),
+            row_end: row1_idx.max(row2_idx),
+            col_start: col1_idx.min(col2_idx),
+            col_end: col1_idx.max(col2_idx),
+            is_editing: false,
+        }
+    }
+
     pub fn row_start(&self) -> usize {
         self.row_start
     }
@@ -55,6 +402,22 @@ impl SelectedRange {
         self.row_end
     }
 
+    pub fn col_start(&self) -> usize {
+        self.col_start
+    }
+
+    pub fn col_end(&self) -> usize {
+        self.col_end
+    }
+
+    pub fn height(&self) -> usize {
+        self.row_end - self.row_start + 1
+    }
+
+    pub fn width(&self) -> usize {
+        self.col_end - self.col_start + 1
+    }
+
     pub fn is_editing(&self) -> bool {
         self.is_editing
     }
@@ -69,6 +432,20 @@ impl SelectedRange {
         self.row_start == self.row_end && self.col_start == self.col_end
     }
 
+    /// Single cell next to the top left one, staying in place at the table edges.
+    pub fn moved(&self, direction: EditCommitMove, row_count: usize, col_count: usize) -> Self {
+        let (row_idx, col_idx) = (self.row_start, self.col_start);
+        match direction {
+            EditCommitMove::Down => {
+                SelectedRange::single((row_idx + 1).min(row_count.max(1) - 1), col_idx)
+            }
+            EditCommitMove::Right => {
+                SelectedRange::single(row_idx, (col_idx + 1).min(col_count.max(1) - 1))
+            }
+            EditCommitMove::Left => SelectedRange::single(row_idx, col_idx.saturating_sub(1)),
+        }
+    }
+
     pub fn swap_col(&mut self, col1_idx: usize, col2_idx: usize) {
         if !self.is_single_cell() {
             return;
@@ -101,6 +478,44 @@ impl SelectedRange {
         }
     }
 
+    /// Parts of the range above, below, to the left and to the right of a cell inside it.
+    pub fn without_cell(&self, row_idx: usize, col_idx: usize) -> Vec<SelectedRange> {
+        let mut parts = vec![];
+        if row_idx > self.row_start {
+            parts.push(SelectedRange::spanning(
+                self.row_start,
+                self.col_start,
+                row_idx - 1,
+                self.col_end,
+            ));
+        }
+        if row_idx < self.row_end {
+            parts.push(SelectedRange::spanning(
+                row_idx + 1,
+                self.col_start,
+                self.row_end,
+                self.col_end,
+            ));
+        }
+        if col_idx > self.col_start {
+            parts.push(SelectedRange::spanning(
+                row_idx,
+                self.col_start,
+                row_idx,
+                col_idx - 1,
+            ));
+        }
+        if col_idx < self.col_end {
+            parts.push(SelectedRange::spanning(
+                row_idx,
+                col_idx + 1,
+                row_idx,
+                self.col_end,
+            ));
+        }
+        parts
+    }
+
     pub fn contains(&self, row_idx: usize, col_idx: usize) -> bool {
         row_idx >= self.row_start
             && row_idx <= self.row_end
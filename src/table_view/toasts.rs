@@ -0,0 +1,65 @@
+use egui::{Align2, Area, Frame, Order, Rect, RichText, Ui, Vec2};
+use std::time::Duration;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ToastKind {
+    Info,
+    Warning,
+    Error,
+}
+
+pub(super) struct Toast {
+    kind: ToastKind,
+    text: String,
+    /// Time of creation in egui's input time, set on the first frame it is shown.
+    shown_at: Option<f64>,
+}
+
+impl super::TableView {
+    /// Show a transient message over the table, e.g. when an action was refused or finished.
+    pub fn notify(&mut self, kind: ToastKind, text: impl Into<String>) {
+        self.state.toasts.push(Toast {
+            kind,
+            text: text.into(),
+            shown_at: None,
+        });
+    }
+
+    pub(super) fn show_toasts(&mut self, ui: &Ui, table_rect: Rect) {
+        if self.state.toasts.is_empty() {
+            return;
+        }
+        let now = ui.input(|i| i.time);
+        let duration = self.config.toast_duration.as_secs_f64();
+        self.state.toasts.retain_mut(|toast| {
+            let shown_at = *toast.shown_at.get_or_insert(now);
+            now - shown_at < duration
+        });
+        if self.state.toasts.is_empty() {
+            return;
+        }
+
+        let visuals = &ui.style().visuals;
+        Area::new(ui.id().with("_egui_tabular_toasts"))
+            .order(Order::Foreground)
+            .interactable(false)
+            .pivot(Align2::RIGHT_BOTTOM)
+            .fixed_pos(table_rect.right_bottom() - Vec2::splat(8.0))
+            .show(ui.ctx(), |ui| {
+                ui.vertical(|ui| {
+                    for toast in &self.state.toasts {
+                        let color = match toast.kind {
+                            ToastKind::Info => visuals.text_color(),
+                            ToastKind::Warning => visuals.warn_fg_color,
+                            ToastKind::Error => visuals.error_fg_color,
+                        };
+                        Frame::popup(ui.style()).show(ui, |ui| {
+                            ui.label(RichText::new(toast.text.as_str()).color(color));
+                        });
+                    }
+                });
+            });
+        ui.ctx()
+            .request_repaint_after(Duration::from_secs_f64(duration / 4.0));
+    }
+}
@@ -0,0 +1,102 @@
+use crate::backend::{RowUid, TableBackend};
+use egui::{Label, RichText, Ui, Widget};
+
+/// Row action chosen in the tool column, applied after the table body is shown.
+pub(super) enum ToolColumnAction {
+    SkipRow { row_uid: RowUid, skip: bool },
+    PinRows { row_uids: Vec<RowUid>, pin: bool },
+    RemoveRows { row_uids: Vec<RowUid> },
+}
+
+/// Row number, skipped rows are shown crossed out and incomplete ones with a red dot.
+pub(super) fn tool_column_ui(
+    ui: &mut Ui,
+    row_idx: usize,
+    is_row_skipped: bool,
+    is_row_incomplete: bool,
+) {
+    let text = RichText::new(format!("{}", row_idx + 1)).weak();
+    let text = if is_row_skipped {
+        text.strikethrough()
+    } else {
+        text
+    };
+    Label::new(text).selectable(false).ui(ui);
+    if is_row_incomplete {
+        let rect = ui.max_rect();
+        ui.painter().circle_filled(
+            egui::pos2(rect.right() - 4., rect.center().y),
+            2.5,
+            ui.visuals().error_fg_color,
+        );
+    }
+}
+
+pub(super) fn tool_column_context_menu_ui(
+    ui: &mut Ui,
+    backend: &impl TableBackend,
+    row_uid: RowUid,
+    read_only: bool,
+    selected_rows: &[RowUid],
+    action: &mut Option<ToolColumnAction>,
+) {
+    let is_row_pinned = backend.pinned_rows().contains(&row_uid);
+    let text = if is_row_pinned {
+        "Unpin row"
+    } else {
+        "Pin row to the top"
+    };
+    if ui.button(text).clicked() {
+        *action = Some(ToolColumnAction::PinRows {
+            row_uids: vec![row_uid],
+            pin: !is_row_pinned,
+        });
+        ui.close_menu();
+    }
+    if !selected_rows.is_empty()
+        && ui
+            .button(format!("Pin {} selected rows", selected_rows.len()))
+            .clicked()
+    {
+        *action = Some(ToolColumnAction::PinRows {
+            row_uids: selected_rows.to_vec(),
+            pin: true,
+        });
+        ui.close_menu();
+    }
+    let is_row_skipped = backend.is_row_skipped(row_uid);
+    ui.add_enabled_ui(!read_only, |ui| {
+        let text = if is_row_skipped {
+            "Include row"
+        } else {
+            "Skip row"
+        };
+        if ui.button(text).clicked() {
+            *action = Some(ToolColumnAction::SkipRow {
+                row_uid,
+                skip: !is_row_skipped,
+            });
+            ui.close_menu();
+        }
+        if !backend.can_remove_rows() {
+            return;
+        }
+        ui.separator();
+        if ui.button("Delete row").clicked() {
+            *action = Some(ToolColumnAction::RemoveRows {
+                row_uids: vec![row_uid],
+            });
+            ui.close_menu();
+        }
+        if !selected_rows.is_empty()
+            && ui
+                .button(format!("Delete {} selected rows", selected_rows.len()))
+                .clicked()
+        {
+            *action = Some(ToolColumnAction::RemoveRows {
+                row_uids: selected_rows.to_vec(),
+            });
+            ui.close_menu();
+        }
+    });
+}
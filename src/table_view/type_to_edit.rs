@@ -0,0 +1,74 @@
+use crate::backend::{CellCoord, ColumnOrigin, ColumnUid, TableBackend, VisualRowIdx};
+use egui::{Event, Key, Modifiers, Response, TextEdit};
+
+impl super::TableView {
+    /// Cells of the column can be edited from the UI, it is not masked or computed and the view is not read only.
+    pub(super) fn is_column_editable(
+        &self,
+        backend: &impl TableBackend,
+        col_uid: ColumnUid,
+    ) -> bool {
+        !self.config.read_only
+            && !self.is_masked(col_uid)
+            && backend.column_origin(col_uid) != ColumnOrigin::Computed
+    }
+
+    /// Typing over a single selected cell starts editing it with the typed text replacing the value,
+    /// F2 starts editing with the current value and the cursor at the end.
+    pub(super) fn handle_type_to_edit(
+        &mut self,
+        backend: &mut impl TableBackend,
+        ctx: &egui::Context,
+    ) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let Some(selected) = self
+            .state
+            .selected_range
+            .filter(|r| r.is_single_cell() && !r.is_editing())
+        else {
+            return;
+        };
+        let typed = ctx.input_mut(|i| {
+            if i.consume_key(Modifiers::NONE, Key::F2) {
+                return Some(None);
+            }
+            i.events.iter().find_map(|event| match event {
+                Event::Text(text) if !text.chars().any(char::is_control) => {
+                    Some(Some(text.clone()))
+                }
+                _ => None,
+            })
+        });
+        let Some(typed) = typed else {
+            return;
+        };
+        let Some(&col_uid) = self.state.columns.get(selected.col_start()) else {
+            return;
+        };
+        if !self.is_column_editable(backend, col_uid) {
+            let now = ctx.input(|i| i.time);
+            self.state.flash_rejected([selected], now);
+            return;
+        }
+        let Some(row_uid) = backend.row_uid(VisualRowIdx(selected.row_start())) else {
+            return;
+        };
+        backend.start_cell_edit(CellCoord { row_uid, col_uid }, typed);
+        let mut editing = selected;
+        editing.set_editing(true);
+        self.state.selected_range = Some(editing);
+        self.state.focus_cell_editor = true;
+    }
+}
+
+/// Focus an editor opened from the keyboard, so that typing continues in it with the cursor at the end.
+pub(super) fn focus_cell_editor(resp: &Response) {
+    resp.request_focus();
+    if let Some(mut state) = TextEdit::load_state(&resp.ctx, resp.id) {
+        // Cursor left from a previous edit at the same place
+        state.cursor.set_char_range(None);
+        state.store(&resp.ctx, resp.id);
+    }
+}
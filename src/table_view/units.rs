@@ -0,0 +1,53 @@
+use crate::backend::{CellCoord, ColumnUid, TableBackend};
+use egui::{Label, RichText, Ui, Widget};
+use rvariant::Variant;
+use std::collections::HashMap;
+
+impl super::TableView {
+    /// Show a unit column next to its value column again, or fold it back into the value cells.
+    pub fn set_unit_column_expanded(&mut self, value_col_uid: ColumnUid, expanded: bool) {
+        let changed = if expanded {
+            self.state.expanded_unit_columns.insert(value_col_uid)
+        } else {
+            self.state.expanded_unit_columns.remove(&value_col_uid)
+        };
+        self.state.columns_changed |= changed;
+    }
+
+    pub fn is_unit_column_expanded(&self, value_col_uid: ColumnUid) -> bool {
+        self.state.expanded_unit_columns.contains(&value_col_uid)
+    }
+
+    /// Value columns with their unit columns currently folded into them.
+    pub(super) fn folded_unit_columns(&self) -> HashMap<ColumnUid, ColumnUid> {
+        self.config
+            .unit_columns
+            .iter()
+            .filter(|(value_col_uid, _)| !self.state.expanded_unit_columns.contains(value_col_uid))
+            .map(|(value_col_uid, unit_col_uid)| (*value_col_uid, *unit_col_uid))
+            .collect()
+    }
+
+    /// Value column of a unit column that is shown expanded.
+    pub(super) fn expanded_value_column(&self, unit_col_uid: ColumnUid) -> Option<ColumnUid> {
+        self.config
+            .unit_columns
+            .iter()
+            .find(|(value_col_uid, u)| {
+                **u == unit_col_uid && self.state.expanded_unit_columns.contains(value_col_uid)
+            })
+            .map(|(value_col_uid, _)| *value_col_uid)
+    }
+}
+
+/// Unit shown after the value, nothing if the unit cell is empty.
+pub(super) fn unit_suffix_ui(backend: &impl TableBackend, coord: CellCoord, ui: &mut Ui) {
+    let unit = match backend.get(coord) {
+        None | Some(Variant::Empty) => return,
+        Some(Variant::Str(unit)) => unit,
+        Some(unit) => unit.to_string(),
+    };
+    Label::new(RichText::new(unit).weak())
+        .selectable(false)
+        .ui(ui);
+}
@@ -0,0 +1,85 @@
+use crate::backend::{OneShotFlags, RowUid, TableBackend, VisualRowIdx};
+use egui::{CollapsingHeader, ScrollArea, Ui};
+use std::collections::{HashMap, HashSet};
+
+/// Errors listed in the panel, the rest are only counted.
+const MAX_LISTED: usize = 200;
+
+impl super::TableView {
+    /// Collect errors again after the data or the row order changed.
+    pub(super) fn invalidate_validation_errors(&mut self, flags: &OneShotFlags) {
+        if flags.row_set_updated
+            || flags.visible_row_vec_updated
+            || flags.column_info_updated
+            || flags.cleared
+            || flags.reloaded
+            || !flags.cells_updated.is_empty()
+        {
+            self.state.validation_errors = None;
+        }
+    }
+
+    /// Collapsible "N validation errors" list below the table, clicking an error selects its cell.
+    pub(super) fn validation_panel_ui(&mut self, backend: &impl TableBackend, ui: &mut Ui) {
+        if !self.config.validation_panel {
+            return;
+        }
+        if self.state.validation_errors.is_none() {
+            let errors = backend.validation_errors();
+            let error_rows: HashSet<RowUid> =
+                errors.iter().map(|(coord, _)| coord.row_uid).collect();
+            self.state.validation_error_rows = if error_rows.is_empty() {
+                HashMap::new()
+            } else {
+                backend
+                    .visible_rows()
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(_, row_uid)| error_rows.contains(row_uid))
+                    .map(|(idx, row_uid)| (row_uid, idx))
+                    .collect()
+            };
+            self.state.validation_errors = Some(errors);
+        }
+        let Some(errors) = &self.state.validation_errors else {
+            return;
+        };
+        if errors.is_empty() {
+            return;
+        }
+        let row_indices = &self.state.validation_error_rows;
+        let title = match errors.len() {
+            1 => "1 validation error".to_string(),
+            n => format!("{n} validation errors"),
+        };
+        let mut jump_to = None;
+        CollapsingHeader::new(title)
+            .id_salt((self.config.id_salt, "validation_errors"))
+            .show(ui, |ui| {
+                ScrollArea::vertical()
+                    .id_salt((self.config.id_salt, "validation_errors_list"))
+                    .max_height(160.)
+                    .show(ui, |ui| {
+                        for (coord, error) in errors.iter().take(MAX_LISTED) {
+                            let Some(row_idx) = row_indices.get(&coord.row_uid) else {
+                                continue;
+                            };
+                            let column = backend
+                                .column_info(coord.col_uid)
+                                .map(|c| c.name.as_str())
+                                .unwrap_or_default();
+                            let text = format!("Row {}, {column}: {error}", row_idx + 1);
+                            if ui.selectable_label(false, text).clicked() {
+                                jump_to = Some((*row_idx, coord.col_uid));
+                            }
+                        }
+                        if errors.len() > MAX_LISTED {
+                            ui.weak(format!("and {} more", errors.len() - MAX_LISTED));
+                        }
+                    });
+            });
+        if let Some((row_idx, col_uid)) = jump_to {
+            self.select_cell(VisualRowIdx(row_idx), Some(col_uid));
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use crate::backend::{CellCoord, ColumnUid, TableBackend};
+use crate::backends::variant::VariantBackend;
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+use rvariant::{Variant, VariantTy};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CsvExportConfig {
+    pub separator: u8,
+    pub quote_style: QuoteStyle,
+    /// Write column names as the first row.
+    pub write_headers: bool,
+    pub line_ending: LineEnding,
+    /// Also export skipped rows and unused columns.
+    pub include_skipped: bool,
+    /// Also export columns marked with [set_column_sensitive](VariantBackend::set_column_sensitive),
+    /// they are left out by default.
+    #[serde(default)]
+    pub include_sensitive: bool,
+    /// Columns left out in addition to the sensitive ones.
+    #[serde(default)]
+    pub exclude_columns: HashSet<ColumnUid>,
+}
+
+impl Default for CsvExportConfig {
+    fn default() -> Self {
+        CsvExportConfig {
+            separator: b',',
+            quote_style: QuoteStyle::default(),
+            write_headers: true,
+            line_ending: LineEnding::default(),
+            include_skipped: false,
+            include_sensitive: false,
+            exclude_columns: HashSet::new(),
+        }
+    }
+}
+
+#[derive(
+    strum::EnumIter, strum::Display, Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize,
+)]
+pub enum QuoteStyle {
+    /// Quote fields only when necessary, e.g. when they contain a separator or quotes.
+    #[default]
+    Necessary,
+    Always,
+    /// Quote all fields that are not numbers.
+    NonNumeric,
+    /// Never quote, even if it produces invalid CSV.
+    Never,
+}
+
+#[derive(
+    strum::EnumIter, strum::Display, Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize,
+)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl From<QuoteStyle> for csv::QuoteStyle {
+    fn from(value: QuoteStyle) -> Self {
+        match value {
+            QuoteStyle::Necessary => csv::QuoteStyle::Necessary,
+            QuoteStyle::Always => csv::QuoteStyle::Always,
+            QuoteStyle::NonNumeric => csv::QuoteStyle::NonNumeric,
+            QuoteStyle::Never => csv::QuoteStyle::Never,
+        }
+    }
+}
+
+impl From<LineEnding> for csv::Terminator {
+    fn from(value: LineEnding) -> Self {
+        match value {
+            LineEnding::Lf => csv::Terminator::Any(b'\n'),
+            LineEnding::CrLf => csv::Terminator::CRLF,
+        }
+    }
+}
+
+/// Export table into a CSV file with default options.
+pub fn export_csv(table: &VariantBackend, path: impl AsRef<Path>) -> Result<(), csv::Error> {
+    let file = std::fs::File::create(path)?;
+    export_csv_with(table, &CsvExportConfig::default(), file)
+}
+
+/// Export table as CSV into any writer, columns are ordered by their uids.
+/// Sensitive columns are left out unless [include_sensitive](CsvExportConfig::include_sensitive) is set.
+pub fn export_csv_with(
+    table: &VariantBackend,
+    config: &CsvExportConfig,
+    writer: impl Write,
+) -> Result<(), csv::Error> {
+    let mut wr = csv::WriterBuilder::new()
+        .delimiter(config.separator)
+        .quote_style(config.quote_style.into())
+        .terminator(config.line_ending.into())
+        .from_writer(writer);
+
+    let used_columns: HashSet<ColumnUid> = table.used_columns().collect();
+    let mut columns: Vec<ColumnUid> = table
+        .available_columns()
+        .filter(|col_uid| config.include_skipped || used_columns.contains(col_uid))
+        .filter(|col_uid| config.include_sensitive || !table.is_column_sensitive(*col_uid))
+        .filter(|col_uid| !config.exclude_columns.contains(col_uid))
+        .collect();
+    columns.sort();
+
+    if config.write_headers {
+        wr.write_record(columns.iter().map(|col_uid| {
+            table
+                .column_info(*col_uid)
+                .map(|c| c.name.as_str())
+                .unwrap_or_default()
+        }))?;
+    }
+    let rows = if config.include_skipped {
+        table.visible_rows()
+    } else {
+        table.un_skipped_rows()
+    };
+    for row_uid in rows {
+        wr.write_record(columns.iter().map(|col_uid| {
+            let coord = CellCoord {
+                row_uid,
+                col_uid: *col_uid,
+            };
+            table
+                .get(coord)
+                .map(|value| variant_to_string(&value))
+                .unwrap_or_default()
+        }))?;
+    }
+    wr.flush()?;
+    Ok(())
+}
+
+/// Guess text encoding from the beginning of a file, byte order mark is used if present.
+/// Only the sample is looked at, so a file that is valid UTF-8 within it is decoded as UTF-8
+/// even if other bytes appear later on.
+pub fn detect_encoding(sample: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(sample) {
+        return encoding;
+    }
+    // Valid UTF-8, possibly with the last character cut off
+    if let Err(e) = std::str::from_utf8(sample) {
+        if e.error_len().is_none() {
+            return UTF_8;
+        }
+    } else {
+        return UTF_8;
+    }
+    let mut detector = EncodingDetector::new();
+    detector.feed(sample, false);
+    detector.guess(None, true)
+}
+
+pub(crate) fn variant_to_string(value: &Variant) -> String {
+    match value {
+        Variant::Empty => String::new(),
+        Variant::Str(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Stable hash of row values, the same across runs, platforms and crate versions for booleans,
+/// numbers, strings and string lists, so that hashes from different exports of a data set can be compared.
+/// Values are hashed with FNV-1a in the encoding of [hash_bytes], empty and missing values are the same.
+/// Other types are hashed as displayed by rvariant, which may change with its version.
+pub fn row_hash(values: impl IntoIterator<Item = Option<Variant>>) -> String {
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    let mut hash = OFFSET;
+    for (idx, value) in values.into_iter().enumerate() {
+        let bytes = value.map(|v| hash_bytes(&v)).unwrap_or_default();
+        // Unit separator between values, so that "ab","c" and "a","bc" differ
+        let separator: &[u8] = if idx == 0 { &[] } else { &[0x1f] };
+        for byte in separator.iter().chain(&bytes) {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+/// Canonical encoding of a value for [row_hash]: a tag of the kind of value followed by its text,
+/// integers of any width are the same and floats are hashed by their bits, not by how they are formatted.
+fn hash_bytes(value: &Variant) -> Vec<u8> {
+    let text = match value {
+        Variant::Empty => return Vec::new(),
+        Variant::Bool(v) => format!("b{}", u8::from(*v)),
+        Variant::U32(v) => format!("i{v}"),
+        Variant::U64(v) => format!("i{v}"),
+        Variant::I32(v) => format!("i{v}"),
+        Variant::I64(v) => format!("i{v}"),
+        Variant::F32(v) => format!("f{:016x}", f64::from(*v).to_bits()),
+        Variant::F64(v) => format!("f{:016x}", v.to_bits()),
+        Variant::Str(v) => format!("s{v}"),
+        // Record separator between items, so that lists differ from strings with the same text
+        Variant::StrList(list) => format!("l{}", list.join("\x1e")),
+        other => format!("d{other}"),
+    };
+    text.into_bytes()
+}
+
+/// Numeric value as f64, None for anything else.
+pub(crate) fn variant_as_f64(value: &Variant) -> Option<f64> {
+    match value {
+        Variant::U32(v) => Some(*v as f64),
+        Variant::U64(v) => Some(*v as f64),
+        Variant::I32(v) => Some(*v as f64),
+        Variant::I64(v) => Some(*v as f64),
+        Variant::F32(v) => Some(*v as f64),
+        Variant::F64(v) => Some(*v),
+        _ => None,
+    }
+}
+
+/// Type with the given name as shown in column headers, e.g. "u32".
+#[cfg(feature = "gui")]
+pub(crate) fn variant_ty_named(name: &str) -> Option<VariantTy> {
+    [
+        VariantTy::Bool,
+        VariantTy::U32,
+        VariantTy::U64,
+        VariantTy::I32,
+        VariantTy::I64,
+        VariantTy::F32,
+        VariantTy::F64,
+        VariantTy::Str,
+        VariantTy::StrList,
+    ]
+    .into_iter()
+    .find(|ty| ty.to_string() == name)
+}
+
+/// Type of a value, None for empty values and the ones without a plain type, such as enums.
+pub(crate) fn variant_ty(value: &Variant) -> Option<VariantTy> {
+    let ty = match value {
+        Variant::Bool(_) => VariantTy::Bool,
+        Variant::U32(_) => VariantTy::U32,
+        Variant::U64(_) => VariantTy::U64,
+        Variant::I32(_) => VariantTy::I32,
+        Variant::I64(_) => VariantTy::I64,
+        Variant::F32(_) => VariantTy::F32,
+        Variant::F64(_) => VariantTy::F64,
+        Variant::Str(_) => VariantTy::Str,
+        Variant::StrList(_) => VariantTy::StrList,
+        _ => return None,
+    };
+    Some(ty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export_csv_with, row_hash, CsvExportConfig};
+    use crate::backend::ColumnUid;
+    use crate::backends::variant::VariantBackend;
+    use rvariant::{Variant, VariantTy};
+
+    #[test]
+    fn sensitive_columns_are_not_exported_by_default() {
+        let mut backend = VariantBackend::new([
+            ("name".to_string(), VariantTy::Str, None),
+            ("password".to_string(), VariantTy::Str, None),
+        ]);
+        backend.insert_row([
+            (ColumnUid(0), Variant::Str("admin".to_string())),
+            (ColumnUid(1), Variant::Str("secret".to_string())),
+        ]);
+        backend.set_column_sensitive(ColumnUid(1), true);
+
+        let export = |config: &CsvExportConfig| {
+            let mut out = Vec::new();
+            export_csv_with(&backend, config, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        assert_eq!(export(&CsvExportConfig::default()), "name\nadmin\n");
+        let config = CsvExportConfig {
+            include_sensitive: true,
+            ..CsvExportConfig::default()
+        };
+        assert_eq!(export(&config), "name,password\nadmin,secret\n");
+    }
+
+    #[test]
+    fn row_hash_is_canonical() {
+        // FNV-1a of "i5", fixed so that a change of the encoding is noticed
+        assert_eq!(row_hash([Some(Variant::U32(5))]), "08b6ff07b55beae3");
+        assert_eq!(
+            row_hash([Some(Variant::U32(5))]),
+            row_hash([Some(Variant::I64(5))])
+        );
+        assert_ne!(
+            row_hash([Some(Variant::U32(5))]),
+            row_hash([Some(Variant::Str("5".to_string()))])
+        );
+        assert_eq!(
+            row_hash([Some(Variant::Empty), Some(Variant::Str("a".to_string()))]),
+            row_hash([None, Some(Variant::Str("a".to_string()))])
+        );
+    }
+}
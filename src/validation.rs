@@ -0,0 +1,277 @@
+use crate::backend::ColumnUid;
+use crate::util::{variant_as_f64, variant_to_string};
+use regex::Regex;
+use rvariant::Variant;
+use std::cmp::Ordering;
+
+/// Check of cell values in one column, see [VariantBackend::add_column_validator](crate::backends::variant::VariantBackend::add_column_validator).
+/// Empty cells are only checked by [NonEmpty](Self::NonEmpty), so that optional values can be left out.
+pub enum ColumnValidator {
+    /// Values as text must match the pattern, e.g. `^[A-Z]{2}-\d{4}$` for the whole value.
+    Regex(Regex),
+    /// Numbers must be within the range, bounds are inclusive.
+    Range {
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    NonEmpty,
+    /// Returns an error message for invalid values.
+    Custom(CheckFn),
+}
+
+/// Returns an error message for an invalid value.
+type CheckFn = Box<dyn Fn(&Variant) -> Result<(), String> + Send>;
+
+/// Check of several cells of the same row, e.g. start date not after the end date,
+/// see [VariantBackend::add_row_rule](crate::backends::variant::VariantBackend::add_row_rule).
+/// All cells of a failing rule are marked as invalid.
+pub enum RowRule {
+    /// `left op right`, rows where either cell is empty are not checked.
+    /// Numbers are compared as numbers, other values as text.
+    Compare {
+        left: ColumnUid,
+        op: Comparison,
+        right: ColumnUid,
+    },
+    /// Values of the columns are passed in the same order, returns an error message if they don't fit together.
+    Custom {
+        columns: Vec<ColumnUid>,
+        check: RowCheckFn,
+    },
+}
+
+/// Returns an error message for invalid values of a row.
+type RowCheckFn = Box<dyn Fn(&[Variant]) -> Result<(), String> + Send>;
+
+#[derive(strum::Display, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    #[strum(to_string = "less than")]
+    Less,
+    #[strum(to_string = "less than or equal to")]
+    LessOrEqual,
+    #[strum(to_string = "equal to")]
+    Equal,
+    #[strum(to_string = "not equal to")]
+    NotEqual,
+    #[strum(to_string = "greater than or equal to")]
+    GreaterOrEqual,
+    #[strum(to_string = "greater than")]
+    Greater,
+}
+
+impl ColumnValidator {
+    pub fn regex(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(ColumnValidator::Regex(Regex::new(pattern)?))
+    }
+
+    pub fn range(min: Option<f64>, max: Option<f64>) -> Self {
+        ColumnValidator::Range { min, max }
+    }
+
+    pub fn custom(check: impl Fn(&Variant) -> Result<(), String> + Send + 'static) -> Self {
+        ColumnValidator::Custom(Box::new(check))
+    }
+
+    /// Error message if the value is invalid.
+    pub fn check(&self, value: &Variant) -> Result<(), String> {
+        let is_empty = is_empty(value);
+        match self {
+            ColumnValidator::NonEmpty if is_empty => Err("Value is required".to_string()),
+            _ if is_empty => Ok(()),
+            ColumnValidator::NonEmpty => Ok(()),
+            ColumnValidator::Regex(regex) => {
+                if regex.is_match(&variant_to_string(value)) {
+                    Ok(())
+                } else {
+                    Err(format!("Doesn't match {}", regex.as_str()))
+                }
+            }
+            ColumnValidator::Range { min, max } => {
+                let number = match value {
+                    Variant::Str(s) => s.trim().parse().ok(),
+                    value => variant_as_f64(value),
+                };
+                let Some(number) = number else {
+                    return Err("Not a number".to_string());
+                };
+                match (min, max) {
+                    (Some(min), _) if number < *min => Err(format!("Less than {min}")),
+                    (_, Some(max)) if number > *max => Err(format!("More than {max}")),
+                    _ => Ok(()),
+                }
+            }
+            ColumnValidator::Custom(check) => check(value),
+        }
+    }
+}
+
+impl RowRule {
+    pub fn compare(left: ColumnUid, op: Comparison, right: ColumnUid) -> Self {
+        RowRule::Compare { left, op, right }
+    }
+
+    pub fn custom(
+        columns: impl IntoIterator<Item = ColumnUid>,
+        check: impl Fn(&[Variant]) -> Result<(), String> + Send + 'static,
+    ) -> Self {
+        RowRule::Custom {
+            columns: columns.into_iter().collect(),
+            check: Box::new(check),
+        }
+    }
+
+    /// Columns which cells are checked together.
+    pub fn columns(&self) -> Vec<ColumnUid> {
+        match self {
+            RowRule::Compare { left, right, .. } => vec![*left, *right],
+            RowRule::Custom { columns, .. } => columns.clone(),
+        }
+    }
+
+    /// Check values of [columns](Self::columns) of one row, `column_name` is used in error messages.
+    pub fn check(
+        &self,
+        values: &[Variant],
+        column_name: impl Fn(ColumnUid) -> String,
+    ) -> Result<(), String> {
+        match self {
+            RowRule::Compare { left, op, right } => {
+                let [a, b] = values else {
+                    return Ok(());
+                };
+                if is_empty(a) || is_empty(b) {
+                    return Ok(());
+                }
+                if compare_values(a, b).map(|o| op.holds(o)).unwrap_or(true) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "{} must be {op} {}",
+                        column_name(*left),
+                        column_name(*right)
+                    ))
+                }
+            }
+            RowRule::Custom { check, .. } => check(values),
+        }
+    }
+}
+
+impl Comparison {
+    pub fn holds(&self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Less => ordering.is_lt(),
+            Comparison::LessOrEqual => ordering.is_le(),
+            Comparison::Equal => ordering.is_eq(),
+            Comparison::NotEqual => ordering.is_ne(),
+            Comparison::GreaterOrEqual => ordering.is_ge(),
+            Comparison::Greater => ordering.is_gt(),
+        }
+    }
+}
+
+fn is_empty(value: &Variant) -> bool {
+    match value {
+        Variant::Empty => true,
+        Variant::Str(s) => s.trim().is_empty(),
+        _ => false,
+    }
+}
+
+/// Numbers (including numeric text) by value, anything else as text.
+fn compare_values(a: &Variant, b: &Variant) -> Option<Ordering> {
+    let as_number = |value: &Variant| match value {
+        Variant::Str(s) => s.trim().parse().ok(),
+        value => variant_as_f64(value),
+    };
+    match (as_number(a), as_number(b)) {
+        (Some(a), Some(b)) => a.partial_cmp(&b),
+        _ => Some(variant_to_string(a).cmp(&variant_to_string(b))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> Variant {
+        Variant::Str(value.to_string())
+    }
+
+    #[test]
+    fn empty_values_only_fail_non_empty() {
+        let validators = [
+            ColumnValidator::regex(r"^\d+$").unwrap(),
+            ColumnValidator::range(Some(1.), Some(10.)),
+            ColumnValidator::custom(|_| Err("always".to_string())),
+        ];
+        for validator in &validators {
+            assert_eq!(validator.check(&Variant::Empty), Ok(()));
+            assert_eq!(validator.check(&text("  ")), Ok(()));
+        }
+        assert!(ColumnValidator::NonEmpty.check(&text(" ")).is_err());
+        assert_eq!(ColumnValidator::NonEmpty.check(&text("a")), Ok(()));
+    }
+
+    #[test]
+    fn regex_matches_text() {
+        let validator = ColumnValidator::regex(r"^[A-Z]{2}-\d{4}$").unwrap();
+        assert_eq!(validator.check(&text("AB-1234")), Ok(()));
+        assert!(validator.check(&text("AB-12")).is_err());
+    }
+
+    #[test]
+    fn range_is_inclusive() {
+        let validator = ColumnValidator::range(Some(1.), Some(10.));
+        assert_eq!(validator.check(&Variant::U32(1)), Ok(()));
+        assert_eq!(validator.check(&Variant::F64(10.)), Ok(()));
+        assert_eq!(validator.check(&text(" 5 ")), Ok(()));
+        assert_eq!(
+            validator.check(&Variant::I32(0)),
+            Err("Less than 1".to_string())
+        );
+        assert_eq!(
+            validator.check(&Variant::U64(11)),
+            Err("More than 10".to_string())
+        );
+        assert_eq!(
+            validator.check(&text("five")),
+            Err("Not a number".to_string())
+        );
+    }
+
+    #[test]
+    fn compare_rule() {
+        let rule = RowRule::compare(ColumnUid(0), Comparison::LessOrEqual, ColumnUid(1));
+        let name = |col_uid: ColumnUid| format!("c{}", col_uid.0);
+        assert_eq!(rule.check(&[text("2"), text("10")], name), Ok(()));
+        assert_eq!(
+            rule.check(&[Variant::U32(3), Variant::U32(3)], name),
+            Ok(())
+        );
+        assert_eq!(
+            rule.check(&[Variant::F64(10.5), text("10")], name),
+            Err("c0 must be less than or equal to c1".to_string())
+        );
+        // Text is compared as text
+        assert!(rule.check(&[text("b"), text("a")], name).is_err());
+        // Rows with an empty cell are not checked
+        assert_eq!(rule.check(&[Variant::Empty, text("1")], name), Ok(()));
+    }
+
+    #[test]
+    fn custom_rule_gets_values_in_order() {
+        let rule = RowRule::custom([ColumnUid(1), ColumnUid(0)], |values| match values {
+            [Variant::U32(a), Variant::U32(b)] if a > b => Ok(()),
+            _ => Err("first must be larger".to_string()),
+        });
+        assert_eq!(rule.columns(), vec![ColumnUid(1), ColumnUid(0)]);
+        assert_eq!(
+            rule.check(&[Variant::U32(2), Variant::U32(1)], |_| String::new()),
+            Ok(())
+        );
+        assert!(rule
+            .check(&[Variant::U32(1), Variant::U32(2)], |_| String::new())
+            .is_err());
+    }
+}